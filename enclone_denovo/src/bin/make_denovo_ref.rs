@@ -0,0 +1,52 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Build a VDJ reference for a species from a genome fasta file.
+//
+// Usage: make_denovo_ref genome.fasta[.gz] species out.fasta <optional args>
+//
+// optional args:
+// - ORDER=x: taxonomic order of the species, e.g. Primates
+// - QC=x: write QC report to this file
+//
+// The output fasta can be passed to enclone using REF=out.fasta.  See
+// enclone_denovo/src/denovo_ref.rs.
+
+use enclone_denovo::denovo_ref::{make_denovo_ref, read_genome_fasta};
+use io_utils::{fwrite, open_for_write_new};
+use std::env;
+use std::io::Write;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 4 {
+        eprintln!("\nusage = make_denovo_ref genome.fasta species out.fasta [ORDER=x] [QC=x]\n");
+        std::process::exit(1);
+    }
+    let (genome, species, out) = (&args[1], &args[2], &args[3]);
+    let mut order = String::new();
+    let mut qc = String::new();
+    for i in 4..args.len() {
+        if let Some(x) = args[i].strip_prefix("ORDER=") {
+            order = x.to_string();
+        } else if let Some(x) = args[i].strip_prefix("QC=") {
+            qc = x.to_string();
+        } else {
+            eprintln!("\nIllegal argument {}.\n", args[i]);
+            std::process::exit(1);
+        }
+    }
+    let r = read_genome_fasta(genome).and_then(|refx| make_denovo_ref(refx, species, &order));
+    let r = match r {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut f = open_for_write_new![out];
+    fwrite!(f, "{}", r.fasta());
+    if !qc.is_empty() {
+        let mut f = open_for_write_new![&qc];
+        fwrite!(f, "{}", r.qc_report());
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Build a VDJ reference for a species from a genome fasta file, using the denovo engine (see
+// denovo.rs) for all of V, D, J and C.  The reference is in 10x style, with header lines like
+//
+// >12|IGHV3 enclone|IGHV3|L-REGION+V-REGION|IG|IGH|None|00
+//
+// and can be passed directly to enclone using REF=.
+//
+// We also generate a QC report.  For each V gene, it lists some of the conditions from the list
+// in denovo.rs, together with the observed value, and flags values that are at the edge of the
+// allowed range.  These are conditions 1, 10, 11, 12, 14, 15 and 16, which depend only on the
+// final sequence.  The report is thus not a full account of why denovo accepted a gene, and
+// cannot say why it rejected one.  The other conditions involve the genomic context of a gene
+// (exon one, the intron and the recombination signal sequence), scores computed during the
+// search, or comparisons between candidate genes, and are applied only by denovo.
+
+use crate::denovo::{denovo, lscore1, DenovoOpts};
+use crate::vdj_features::{cdr1, cdr2, cdr3_start, fr1_start, fwr1, fwr2, fwr3};
+use amino::nucleotide_to_aminoacid_sequence;
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use string_utils::{strme, TextUtils};
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// One record in a denovo reference.
+
+#[derive(Clone)]
+pub struct DenovoRefEntry {
+    pub id: usize,           // record number, starting at one
+    pub gene: String,        // e.g. IGHV3
+    pub region_type: String, // e.g. L-REGION+V-REGION
+    pub chain: String,       // IGH, IGK or IGL
+    pub isotype: String,     // for C-REGION, the gene name after the chain, else None
+    pub seq: Vec<u8>,        // bases
+}
+
+// Status of one condition for one gene.  Edge means that the value is within one unit of the
+// boundary of the allowed range.
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum QcStatus {
+    Pass,
+    Edge,
+    Fail,
+}
+
+impl QcStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QcStatus::Pass => "pass",
+            QcStatus::Edge => "edge",
+            QcStatus::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ConditionQc {
+    pub number: usize,      // the number of the condition in the list in denovo.rs
+    pub name: &'static str, // short description
+    pub value: String,      // observed value
+    pub status: QcStatus,
+}
+
+#[derive(Clone)]
+pub struct GeneQc {
+    pub gene: String,
+    pub region_type: String,
+    pub conditions: Vec<ConditionQc>,
+}
+
+#[derive(Clone, Default)]
+pub struct DenovoRef {
+    pub entries: Vec<DenovoRefEntry>,
+    pub qc: Vec<GeneQc>,
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Read a genome fasta file, which may be gzipped, into the form used by denovo: a Vec<Vec<u8>>,
+// with records alternating between headers (including the leading '>') and bases.
+
+pub fn read_genome_fasta(path: &str) -> Result<Vec<Vec<u8>>, String> {
+    let f = File::open(path).map_err(|e| format!("\nUnable to open {}: {}.\n", path, e))?;
+    let f: Box<dyn Read> = if path.ends_with(".gz") {
+        Box::new(MultiGzDecoder::new(f))
+    } else {
+        Box::new(f)
+    };
+    let mut refx = Vec::<Vec<u8>>::new();
+    for line in BufReader::new(f).lines() {
+        let s = line.map_err(|e| format!("\nFailed to read {}: {}.\n", path, e))?;
+        let s = s.trim_end();
+        if s.starts_with('>') {
+            refx.push(s.as_bytes().to_vec());
+            refx.push(Vec::new());
+        } else if !s.is_empty() {
+            if refx.is_empty() {
+                return Err(format!(
+                    "\nThe file {} does not appear to be fasta.\n",
+                    path
+                ));
+            }
+            let n = refx.len();
+            refx[n - 1].extend(s.as_bytes());
+        }
+    }
+    if refx.is_empty() {
+        return Err(format!("\nThe file {} has no fasta records.\n", path));
+    }
+    Ok(refx)
+}
+
+// Find all VDJC genes in a genome, given as for read_genome_fasta.  The species name is used
+// to label output.  The order is the taxonomic order of the species, e.g. Primates, and may be
+// empty; currently it only affects the naming of constant region genes for Artiodactyla.
+
+pub fn make_denovo_ref(
+    refx: Vec<Vec<u8>>,
+    species: &str,
+    order: &str,
+) -> Result<DenovoRef, String> {
+    let opts = DenovoOpts {
+        species: species.to_string(),
+        order: order.to_string(),
+        use_v: true,
+        use_d: true,
+        use_j: true,
+        use_c: true,
+        store_fasta: true,
+        tenx: true,
+        ..Default::default()
+    };
+    let fasta = denovo(&opts, refx).fasta;
+    let entries = parse_denovo_fasta(strme(&fasta))?;
+    let mut qc = Vec::<GeneQc>::new();
+    for e in entries.iter() {
+        if e.region_type == "L-REGION+V-REGION" {
+            qc.push(v_gene_qc(e));
+        }
+    }
+    Ok(DenovoRef { entries, qc })
+}
+
+// Parse 10x style fasta, as emitted by denovo.
+
+fn parse_denovo_fasta(fasta: &str) -> Result<Vec<DenovoRefEntry>, String> {
+    let mut entries = Vec::<DenovoRefEntry>::new();
+    let lines = fasta.lines().collect::<Vec<&str>>();
+    for i in (0..lines.len()).step_by(2) {
+        let fields = lines[i].after(">").split('|').collect::<Vec<&str>>();
+        if fields.len() != 8 || i + 1 == lines.len() {
+            return Err(format!("\nUnexpected denovo fasta header {}.\n", lines[i]));
+        }
+        entries.push(DenovoRefEntry {
+            id: fields[0].force_usize(),
+            gene: fields[2].to_string(),
+            region_type: fields[3].to_string(),
+            chain: fields[5].to_string(),
+            isotype: fields[6].to_string(),
+            seq: lines[i + 1].as_bytes().to_vec(),
+        });
+    }
+    Ok(entries)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Evaluate conditions 1, 10, 11, 12, 14, 15 and 16 of denovo.rs for a V gene, see above.  The
+// other conditions are not evaluated.
+
+fn range_status(x: usize, low: usize, high: usize) -> QcStatus {
+    if x < low || x > high {
+        QcStatus::Fail
+    } else if x == low || x == high {
+        QcStatus::Edge
+    } else {
+        QcStatus::Pass
+    }
+}
+
+pub fn v_gene_qc(e: &DenovoRefEntry) -> GeneQc {
+    let ct = e.chain.as_str();
+    let aa = nucleotide_to_aminoacid_sequence(&e.seq, 0);
+    let mut conditions = Vec::<ConditionQc>::new();
+    let cdr3 = cdr3_start(&aa, ct, false);
+
+    // 1. FWR3 must end with YYC or YFC or YHC or FYC or YIC.
+
+    let end = if cdr3 >= 3 {
+        &aa[cdr3 - 3..cdr3]
+    } else {
+        &aa[0..0]
+    };
+    let motif_ok = [b"YYC", b"YFC", b"YHC", b"FYC", b"YIC"]
+        .iter()
+        .any(|m| end == &m[..]);
+    conditions.push(ConditionQc {
+        number: 1,
+        name: "FWR3 end motif",
+        value: strme(end).to_string(),
+        status: if motif_ok {
+            QcStatus::Pass
+        } else {
+            QcStatus::Fail
+        },
+    });
+
+    // 10. The length of leader..FWR3 in amino acids must be between 102 and 125.
+
+    conditions.push(ConditionQc {
+        number: 10,
+        name: "leader..FWR3 length",
+        value: format!("{}", cdr3),
+        status: range_status(cdr3, 102, 125),
+    });
+
+    // 11. The leader must have length between 17 and 28 amino acids.
+
+    let f1_start = fr1_start(&aa, ct);
+    conditions.push(ConditionQc {
+        number: 11,
+        name: "leader length",
+        value: format!("{}", f1_start),
+        status: range_status(f1_start, 17, 28),
+    });
+
+    // 12. score1 of the leader must be at least 4.
+
+    let ls = if f1_start >= 2 {
+        lscore1(&aa[0..f1_start])
+    } else {
+        0
+    };
+    conditions.push(ConditionQc {
+        number: 12,
+        name: "leader score1",
+        value: format!("{}", ls),
+        status: if ls < 4 {
+            QcStatus::Fail
+        } else if ls == 4 {
+            QcStatus::Edge
+        } else {
+            QcStatus::Pass
+        },
+    });
+
+    // 14. All features must be computable.
+
+    let f1 = fwr1(&aa, ct, false);
+    let computable = f1.is_some()
+        && cdr1(&aa, ct, false).is_some()
+        && fwr2(&aa, ct, false).is_some()
+        && cdr2(&aa, ct, false).is_some()
+        && fwr3(&aa, ct, false).is_some();
+    conditions.push(ConditionQc {
+        number: 14,
+        name: "features computable",
+        value: format!("{}", computable),
+        status: if computable {
+            QcStatus::Pass
+        } else {
+            QcStatus::Fail
+        },
+    });
+
+    // 15. FWR1 must have length between 20 and 26 amino acids.
+    // 16. For IGL, the FWR1 must have length at most 22.
+
+    if let Some(f1) = f1 {
+        conditions.push(ConditionQc {
+            number: 15,
+            name: "FWR1 length",
+            value: format!("{}", f1.len()),
+            status: range_status(f1.len(), 20, 26),
+        });
+        if ct == "IGL" {
+            conditions.push(ConditionQc {
+                number: 16,
+                name: "IGL FWR1 length",
+                value: format!("{}", f1.len()),
+                status: range_status(f1.len(), 20, 22),
+            });
+        }
+    }
+    GeneQc {
+        gene: e.gene.clone(),
+        region_type: e.region_type.clone(),
+        conditions,
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+impl DenovoRef {
+    // Return the reference as fasta.
+
+    pub fn fasta(&self) -> String {
+        let mut s = String::new();
+        for e in self.entries.iter() {
+            s += &format!(
+                ">{}|{} enclone|{}|{}|IG|{}|{}|00\n{}\n",
+                e.id,
+                e.gene,
+                e.gene,
+                e.region_type,
+                e.chain,
+                e.isotype,
+                strme(&e.seq)
+            );
+        }
+        s
+    }
+
+    // Return the QC report as text, one line per condition, tab-separated.  Genes having a
+    // condition at the edge of its range or failing it are summarized at the end.
+
+    pub fn qc_report(&self) -> String {
+        let mut s = "gene\tregion\tcondition\tname\tvalue\tstatus\n".to_string();
+        let (mut edges, mut fails) = (0, 0);
+        for g in self.qc.iter() {
+            let mut edge = false;
+            let mut fail = false;
+            for c in g.conditions.iter() {
+                s += &format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\n",
+                    g.gene,
+                    g.region_type,
+                    c.number,
+                    c.name,
+                    c.value,
+                    c.status.as_str()
+                );
+                edge |= c.status == QcStatus::Edge;
+                fail |= c.status == QcStatus::Fail;
+            }
+            if fail {
+                fails += 1;
+            } else if edge {
+                edges += 1;
+            }
+        }
+        s += &format!(
+            "# {} genes, {} V genes, {} at edge, {} failing\n",
+            self.entries.len(),
+            self.qc.len(),
+            edges,
+            fails
+        );
+        s
+    }
+}
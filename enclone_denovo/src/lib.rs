@@ -1,9 +1,12 @@
 // Copyright (c) 2020 10x Genomics, Inc. All rights reserved.
 
 pub mod const_ighd;
+pub mod denovo;
+pub mod denovo_ref;
 pub mod make_fwr3_freqs;
 pub mod make_mammalian_fixed_len;
 pub mod make_mammalian_pwms;
 pub mod mammalian_fixed_len;
 pub mod mammalian_pwms;
 pub mod vdj_features;
+pub mod vdj_regions;
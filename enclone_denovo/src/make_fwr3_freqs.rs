@@ -2,7 +2,15 @@
 
 // Compute FWR3 for all IMGT mammalian reference sequences, and report out a per-chain
 // amino acid frequency table.
-
+//
+// There are two versions.  The first, make_fwr3_freqs, computes the table from the IMGT
+// reference sequences in the directory v_segments.  The second, fwr3_freqs, computes a similar
+// table from mammalian_fixed_len.table, which is built into the code, and is used by denovo.
+// That table was made from the same sequences by make_mammalian_fixed_len.rs, but not with the
+// same filters: it applies the junk filters below to human and mouse too.  So the two tables are
+// close but not identical.
+
+use crate::mammalian_fixed_len::mammalian_fixed_len;
 use crate::vdj_features::{cdr3_score, cdr3_start, fr3_start};
 use amino::nucleotide_to_aminoacid_sequence;
 use debruijn::dna_string::DnaString;
@@ -10,7 +18,7 @@ use fasta_tools::read_fasta_into_vec_dna_string_plus_headers;
 use std::fs::read_dir;
 use string_utils::TextUtils;
 // use vdj_ann::refx::*;
-use vector_utils::make_freq;
+use vector_utils::{make_freq, reverse_sort};
 
 pub fn make_fwr3_freqs() -> Vec<Vec<Vec<(u32, u8)>>> {
     // Define constants.
@@ -222,3 +230,46 @@ pub fn make_fwr3_freqs() -> Vec<Vec<Vec<(u32, u8)>>> {
 
     freqs
 }
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+pub fn fwr3_freqs() -> Vec<Vec<Vec<(u32, u8)>>> {
+    // Define constants.
+
+    const MOTIF: usize = 30;
+    let chain_types = ["IGH", "IGK", "IGL", "TRA", "TRB"];
+
+    // Sum the counts for FWR3 sequences of length at least MOTIF, aligning them on the right.
+
+    let mut counts = vec![vec![vec![0_u32; 256]; MOTIF]; 5];
+    for x in mammalian_fixed_len().iter() {
+        if x.1 != "fwr3" || x.2 < MOTIF {
+            continue;
+        }
+        let r = chain_types.iter().position(|c| *c == x.0);
+        if r.is_none() {
+            continue;
+        }
+        let (r, n) = (r.unwrap(), x.2);
+        for (j, c) in counts[r].iter_mut().enumerate() {
+            for m in x.3[n - j - 1].iter() {
+                c[m.1 as usize] += m.0;
+            }
+        }
+    }
+
+    // Organize the counts, in the same order as make_freq.
+
+    let mut freqs = vec![vec![Vec::<(u32, u8)>::new(); MOTIF]; 5];
+    for (r, c) in counts.iter().enumerate() {
+        for (j, c) in c.iter().enumerate() {
+            for (b, count) in c.iter().enumerate() {
+                if *count > 0 {
+                    freqs[r][j].push((*count, b as u8));
+                }
+            }
+            reverse_sort(&mut freqs[r][j]);
+        }
+    }
+    freqs
+}
@@ -13,9 +13,19 @@
 
 // THIS IS A COPY FROM THE ENCLONE REPO TO FACILITATE EXPERIMENTATION!
 
+use std::cmp::max;
 use string_utils::strme;
 use vector_utils::reverse_sort;
 
+// Several functions below have a "scored" form, which also returns a confidence score.  This is
+// the score of the best motif match, as a fraction of the maximum possible score.
+
+fn max_pwm_score(pwm: &[Vec<(usize, u8)>]) -> usize {
+    pwm.iter()
+        .map(|x| x.iter().map(|y| y.0).max().unwrap_or(0))
+        .sum()
+}
+
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
 // Given the amino acid sequence for a V reference sequence, attempt to find the start of the
@@ -36,6 +46,10 @@ use vector_utils::reverse_sort;
 // If the starting amino acid is C, we add one to the start position.
 
 pub fn fr1_start(aa: &Vec<u8>, chain_type: &str) -> usize {
+    fr1_start_scored(aa, chain_type).0
+}
+
+pub fn fr1_start_scored(aa: &Vec<u8>, chain_type: &str) -> (usize, f64) {
     // Define PWM.
 
     let mut pwm = Vec::<Vec<(usize, u8)>>::new();
@@ -108,7 +122,7 @@ pub fn fr1_start(aa: &Vec<u8>, chain_type: &str) -> usize {
     if aa[p] == b'C' {
         p += 1;
     }
-    p
+    (p, score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64)
 }
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
@@ -119,6 +133,10 @@ pub fn fr1_start(aa: &Vec<u8>, chain_type: &str) -> usize {
 // Chain type is one of IGH, IGK, IGL, TRA or TRB.
 
 pub fn cdr1_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize> {
+    cdr1_start_scored(aa, chain_type, verbose).map(|x| x.0)
+}
+
+pub fn cdr1_start_scored(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<(usize, f64)> {
     // Define PWM for eight amino acids.
 
     let mut pwm = Vec::<Vec<(usize, u8)>>::new();
@@ -190,7 +208,10 @@ pub fn cdr1_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize
     if score_pos.is_empty() {
         return None;
     }
-    Some(score_pos[0].1 + add + 2)
+    Some((
+        score_pos[0].1 + add + 2,
+        score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+    ))
 }
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
@@ -199,6 +220,10 @@ pub fn cdr1_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize
 // FR2 region.  Chain type is one of IGH, IGK, IGL, TRA or TRB'
 
 pub fn fr2_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize> {
+    fr2_start_scored(aa, chain_type, verbose).map(|x| x.0)
+}
+
+pub fn fr2_start_scored(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<(usize, f64)> {
     // Define PWM for six amino acids.
 
     let mut pwm = Vec::<Vec<(usize, u8)>>::new();
@@ -286,7 +311,10 @@ pub fn fr2_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize>
     if score_pos.is_empty() {
         None
     } else {
-        Some(score_pos[0].1 + add - 1)
+        Some((
+            score_pos[0].1 + add - 1,
+            score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+        ))
     }
 }
 
@@ -296,9 +324,16 @@ pub fn fr2_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize>
 // CDR2 region.  Chain type is one of IGH, IGK, IGL, TRA or TRB.
 
 pub fn cdr2_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize> {
-    let s2 = fr2_start(aa, chain_type, false);
+    cdr2_start_scored(aa, chain_type, verbose).map(|x| x.0)
+}
+
+// For chain types where the CDR2 start is at a fixed offset from the FR2 start, the confidence
+// score is that of the FR2 start.
+
+pub fn cdr2_start_scored(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<(usize, f64)> {
+    let s2 = fr2_start_scored(aa, chain_type, false);
     s2?;
-    let s2 = s2.unwrap();
+    let (s2, s2_score) = s2.unwrap();
     let mut add = 0_isize;
     if chain_type == "IGH" {
         // Six amino acids preceeding the CDR2 start.
@@ -350,7 +385,10 @@ pub fn cdr2_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize
             score_pos.push((score, j));
         }
         reverse_sort(&mut score_pos);
-        Some(score_pos[0].1 + 7)
+        Some((
+            score_pos[0].1 + 7,
+            score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+        ))
     } else if chain_type == "TRA" {
         // Six amino acids preceeding the CDR2 start.
 
@@ -410,22 +448,52 @@ pub fn cdr2_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize
         if score_pos.is_empty() {
             None
         } else {
-            Some(score_pos[0].1 + 6)
+            Some((
+                score_pos[0].1 + 6,
+                score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+            ))
         }
     } else {
         if chain_type == "IGK" || chain_type == "IGL" {
             add = -2;
         }
-        Some(s2 + (17 + add) as usize)
+        Some((s2 + (17 + add) as usize, s2_score))
     }
 }
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
 pub fn cdr3_start(aa: &Vec<u8>, _chain_type: &str, _verbose: bool) -> usize {
+    cdr3_start_reach(aa, 18).0
+}
+
+pub fn cdr3_start_scored(aa: &Vec<u8>, _chain_type: &str, _verbose: bool) -> (usize, f64) {
+    cdr3_start_reach(aa, 18)
+}
+
+// Find the start of the CDR3 anywhere in a sequence, rather than near its end.  This is for
+// sequences that extend beyond the CDR3.
+
+pub fn cdr3_start_anywhere(aa: &[u8]) -> usize {
+    cdr3_start_reach(aa, aa.len()).0
+}
+
+fn cdr3_start_reach(aa: &[u8], reach: usize) -> (usize, f64) {
     let motif = [b"LQPEDSAVYYC", b"VEASQTGTYFC", b"ATSGQASLYLC"];
     let nm = motif[0].len();
-    let reach = 18;
+    let mut max_score = 0;
+    for k in 0..nm {
+        let mut m = 0;
+        for l in 0..motif.len() {
+            let c = motif[l][k];
+            if c == b'Q' {
+                m = max(m, 1);
+            } else {
+                m = max(m, motif.iter().filter(|x| x[k] == c).count());
+            }
+        }
+        max_score += m;
+    }
     let mut scores = Vec::<(usize, usize)>::new();
     for j in aa.len() as isize - nm as isize - reach as isize..=aa.len() as isize - nm as isize {
         if j < 0 {
@@ -446,7 +514,7 @@ pub fn cdr3_start(aa: &Vec<u8>, _chain_type: &str, _verbose: bool) -> usize {
         scores.push((score, j + nm));
     }
     reverse_sort(&mut scores);
-    scores[0].1
+    (scores[0].1, scores[0].0 as f64 / max_score as f64)
 }
 
 pub fn cdr3_score(aa: &Vec<u8>, _chain_type: &str, _verbose: bool) -> usize {
@@ -478,6 +546,10 @@ pub fn cdr3_score(aa: &Vec<u8>, _chain_type: &str, _verbose: bool) -> usize {
 // FR3 region.
 
 pub fn fr3_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize> {
+    fr3_start_scored(aa, chain_type, verbose).map(|x| x.0)
+}
+
+pub fn fr3_start_scored(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<(usize, f64)> {
     // First find the start of the CDR3.
 
     let cdr3_start = cdr3_start(aa, chain_type, verbose);
@@ -546,7 +618,10 @@ pub fn fr3_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize>
         }
         reverse_sort(&mut score_pos);
         if !score_pos.is_empty() {
-            Some(score_pos[0].1)
+            Some((
+                score_pos[0].1,
+                score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+            ))
         } else {
             None
         }
@@ -613,7 +688,10 @@ pub fn fr3_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize>
         reverse_sort(&mut score_pos);
         if !score_pos.is_empty() {
             if -score_pos[0].1 >= 1 {
-                Some((-score_pos[0].1) as usize - 1)
+                Some((
+                    (-score_pos[0].1) as usize - 1,
+                    score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+                ))
             } else {
                 None
             }
@@ -693,7 +771,10 @@ pub fn fr3_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize>
         }
         reverse_sort(&mut score_pos);
         if !score_pos.is_empty() {
-            Some(score_pos[0].1 + 1)
+            Some((
+                score_pos[0].1 + 1,
+                score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+            ))
         } else {
             None
         }
@@ -750,7 +831,10 @@ pub fn fr3_start(aa: &Vec<u8>, chain_type: &str, verbose: bool) -> Option<usize>
         }
         reverse_sort(&mut score_pos);
         if !score_pos.is_empty() {
-            Some(score_pos[0].1)
+            Some((
+                score_pos[0].1,
+                score_pos[0].0 as f64 / max_pwm_score(&pwm) as f64,
+            ))
         } else {
             None
         }
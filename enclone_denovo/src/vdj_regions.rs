@@ -0,0 +1,173 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Find the framework and CDR regions of a V segment, together with confidence scores.  This is
+// a wrapper around the feature finders in vdj_features.rs, which may be applied to arbitrary
+// sequences, including:
+// - amino acid or nucleotide sequences
+// - sequences with or without a leader
+// - sequences that extend beyond the start of the CDR3, e.g. a full V..J protein sequence.
+//
+// All coordinates are zero-based amino acid positions on the input sequence (after
+// translation, in the case of bases).  Confidence scores are between zero and one, see
+// vdj_features.rs.  The confidence score of a region is the lesser of the scores of its two
+// boundaries.
+
+use crate::vdj_features::{
+    cdr1_start_scored, cdr2_start_scored, cdr3_start_anywhere, cdr3_start_scored, fr1_start_scored,
+    fr2_start_scored, fr3_start_scored,
+};
+use amino::nucleotide_to_aminoacid_sequence;
+use std::cmp::min;
+
+pub const REGION_NAMES: [&str; 5] = ["FWR1", "CDR1", "FWR2", "CDR2", "FWR3"];
+
+// Sequences without a leader are given this fake leader, as described in vdj_features.rs.
+
+const FAKE_LEADER: &[u8; 20] = b"MXXXXXXXXXXXXXXXXXXX";
+
+// Number of amino acids retained beyond the start of the CDR3.
+
+const CDR3_TRIM: usize = 10;
+
+// Minimum position of the CDR3 start, including the leader.  This guarantees that the feature
+// finders do not go off the start of the sequence.
+
+const MIN_CDR3_START: usize = 80;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VRegion {
+    pub name: &'static str, // one of REGION_NAMES
+    pub start: usize,
+    pub stop: usize,
+    pub confidence: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VRegions {
+    pub chain_type: String,
+    pub aa: Vec<u8>,           // the amino acid sequence that was annotated
+    pub frame: usize,          // reading frame, for nucleotide input
+    pub regions: Vec<VRegion>, // FWR1, CDR1, FWR2, CDR2, FWR3, in that order, see v_regions
+    pub cdr3_start: usize,
+    pub cdr3_confidence: f64,
+}
+
+impl VRegions {
+    pub fn region(&self, name: &str) -> Option<&VRegion> {
+        self.regions.iter().find(|r| r.name == name)
+    }
+
+    // Return the amino acids in a region.
+
+    pub fn region_seq(&self, name: &str) -> Option<&[u8]> {
+        self.region(name).map(|r| &self.aa[r.start..r.stop])
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Find the regions in an amino acid sequence.  Chain type is one of IGH, IGK, IGL, TRA or TRB.
+// If has_leader is false, the sequence is assumed to start at or near the start of FWR1.  If it
+// starts after the start of FWR1, there is no FWR1 region.
+
+pub fn v_regions(aa: &[u8], chain_type: &str, has_leader: bool) -> Result<VRegions, String> {
+    if !["IGH", "IGK", "IGL", "TRA", "TRB"].contains(&chain_type) {
+        return Err(format!(
+            "\nIllegal chain type {}, must be one of IGH, IGK, IGL, TRA or TRB.\n",
+            chain_type
+        ));
+    }
+
+    // Add the fake leader if needed, then trim shortly after the CDR3 start.
+
+    let aa = aa.to_ascii_uppercase();
+    let offset = if has_leader { 0 } else { FAKE_LEADER.len() };
+    let mut x = Vec::<u8>::new();
+    if !has_leader {
+        x.extend(FAKE_LEADER.iter());
+    }
+    x.extend(aa.iter());
+    if x.len() < MIN_CDR3_START {
+        return Err("\nSequence is too short.\n".to_string());
+    }
+    let c3 = cdr3_start_anywhere(&x);
+    if c3 < MIN_CDR3_START {
+        return Err("\nUnable to find CDR3 start, or sequence is too short.\n".to_string());
+    }
+    x.truncate(min(x.len(), c3 + CDR3_TRIM));
+
+    // Find the boundaries.  Each is a position and a confidence score.
+
+    let (cdr3, cdr3_score) = cdr3_start_scored(&x, chain_type, false);
+    let fail = |name: &str| format!("\nUnable to find {} start.\n", name);
+    let mut bounds = vec![fr1_start_scored(&x, chain_type)];
+    bounds.push(cdr1_start_scored(&x, chain_type, false).ok_or_else(|| fail("CDR1"))?);
+    bounds.push(fr2_start_scored(&x, chain_type, false).ok_or_else(|| fail("FWR2"))?);
+    bounds.push(cdr2_start_scored(&x, chain_type, false).ok_or_else(|| fail("CDR2"))?);
+    bounds.push(fr3_start_scored(&x, chain_type, false).ok_or_else(|| fail("FWR3"))?);
+    bounds.push((cdr3, cdr3_score));
+
+    // Convert to regions on the input sequence.  A region that starts in the fake leader, which
+    // happens if the sequence starts after the start of FWR1, is omitted.
+
+    let mut regions = Vec::<VRegion>::new();
+    for i in 0..REGION_NAMES.len() {
+        let (start, stop) = (bounds[i].0, bounds[i + 1].0);
+        if start > stop {
+            return Err(format!(
+                "\nFound inconsistent boundaries for {}.\n",
+                REGION_NAMES[i]
+            ));
+        }
+        if start < offset {
+            continue;
+        }
+        regions.push(VRegion {
+            name: REGION_NAMES[i],
+            start: start - offset,
+            stop: stop - offset,
+            confidence: bounds[i].1.min(bounds[i + 1].1),
+        });
+    }
+    if cdr3 < offset {
+        return Err("\nUnable to find CDR3 start.\n".to_string());
+    }
+    Ok(VRegions {
+        chain_type: chain_type.to_string(),
+        aa,
+        frame: 0,
+        regions,
+        cdr3_start: cdr3 - offset,
+        cdr3_confidence: cdr3_score,
+    })
+}
+
+// Find the regions in a nucleotide sequence.  We translate in the frame having the fewest stop
+// codons, favoring lower frames in case of ties.
+
+pub fn v_regions_from_bases(
+    bases: &[u8],
+    chain_type: &str,
+    has_leader: bool,
+) -> Result<VRegions, String> {
+    let bases = bases.to_ascii_uppercase();
+    let mut best = (usize::MAX, 0);
+    for frame in 0..min(3, bases.len()) {
+        let aa = nucleotide_to_aminoacid_sequence(&bases, frame);
+        let stops = aa.iter().filter(|c| **c == b'*').count();
+        if stops < best.0 {
+            best = (stops, frame);
+        }
+    }
+    let frame = best.1;
+    let aa = nucleotide_to_aminoacid_sequence(&bases, frame);
+    let mut r = v_regions(&aa, chain_type, has_leader)?;
+    r.frame = frame;
+    Ok(r)
+}
+
+// Test if a sequence appears to consist of bases rather than amino acids.
+
+pub fn is_bases(seq: &[u8]) -> bool {
+    seq.iter().all(|c| b"ACGTNacgtn".contains(c))
+}
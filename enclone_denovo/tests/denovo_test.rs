@@ -0,0 +1,393 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Tests for the denovo heuristics and for the V segment feature finders.  These use only data
+// in this crate, see test_data/README.
+
+use amino::aa_seq;
+use enclone_denovo::denovo::{denovo, lscore1, lscore2, reverse_complement, DenovoOpts};
+use enclone_denovo::denovo_ref::read_genome_fasta;
+use enclone_denovo::vdj_features::{
+    cdr1, cdr1_start, cdr2, cdr2_start, cdr3_start, fr1_start, fr2_start, fr3_start, fwr1, fwr3,
+};
+use enclone_denovo::vdj_regions::{v_regions, v_regions_from_bases};
+use string_utils::{strme, TextUtils};
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Parse fasta text into (gene type, sequence) pairs, sorted, where the gene type is the header
+// with trailing digits removed, e.g. IGHJ.
+
+fn gene_types_and_seqs(fasta: &str) -> Vec<(String, String)> {
+    let mut x = Vec::<(String, String)>::new();
+    let lines = fasta.lines().collect::<Vec<&str>>();
+    for i in (0..lines.len()).step_by(2) {
+        assert!(lines[i].starts_with('>'));
+        let t = lines[i]
+            .after(">")
+            .trim_end_matches(|c: char| c.is_ascii_digit());
+        x.push((t.to_string(), lines[i + 1].to_string()));
+    }
+    x.sort();
+    x
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 1. Test that the J genes planted in the synthetic genome are found, and nothing else.  The
+// genome includes a decoy that differs from a J gene at three constrained positions.
+
+#[test]
+fn test_synthetic_j_genes() {
+    let refx = read_genome_fasta("test_data/synthetic_genome.fasta").unwrap();
+    let opts = DenovoOpts {
+        species: "synthetic".to_string(),
+        use_j: true,
+        store_fasta: true,
+        ..Default::default()
+    };
+    let fasta = denovo(&opts, refx).fasta;
+    let found = gene_types_and_seqs(strme(&fasta));
+    let truth = std::fs::read_to_string("test_data/synthetic_genome_truth.fasta").unwrap();
+    let truth = gene_types_and_seqs(&truth);
+    if found != truth {
+        eprintln!("\nfound J genes:");
+        for x in found.iter() {
+            eprintln!("{} {}", x.0, x.1);
+        }
+        eprintln!("\nexpected J genes:");
+        for x in truth.iter() {
+            eprintln!("{} {}", x.0, x.1);
+        }
+        panic!("J genes found in synthetic genome do not match truth");
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 2. Test feature boundaries for IG reference sequences, leader through the start of CDR3.
+// Boundaries are zero-based amino acid positions: FWR1, CDR1, FWR2, CDR2, FWR3, CDR3.
+
+#[test]
+fn test_ig_feature_boundaries() {
+    let examples = [
+        (
+            "human IGHV1-69D",
+            "IGH",
+            "MDWTWRFLFVVAAATGVQSQVQLVQSGAEVKKPGSSVKVSCKASGGTFSSYAISWVRQAPGQGLEWMGGIIPIFGTANYAQ\
+             KFQGRVTITADESTSTAYMELSSLRSEDTAVYYCAR",
+            [19, 44, 51, 70, 76, 115],
+        ),
+        (
+            "human IGKV1-39",
+            "IGK",
+            "MDMRVPAQLLGLLLLWLRGARCDIQMTQSPSSLSASVGDRVTITCRASQSISSYLNWYQQKPGKAPKLLIYAASSLQSGVPS\
+             RFSGSGSGTDFTLTISSLQPEDFATYYCQQSYSTP",
+            [22, 45, 56, 71, 78, 110],
+        ),
+        (
+            "human IGLV2-14",
+            "IGL",
+            "MAWALLLLTLLTQGTGSWAQSALTQPASVSGSPGQSITISCTGTSSDVGGYNYVSWYQQHPGKAPKLMIYEVSNRPSGVSNR\
+             FSGSKSGNTASLTISGLQAEDEADYYCSSYTSSSTL",
+            [19, 41, 55, 70, 77, 109],
+        ),
+    ];
+    for (name, chain_type, aa, bounds) in examples.iter() {
+        let aa = aa.as_bytes().to_vec();
+        let found = [
+            fr1_start(&aa, chain_type),
+            cdr1_start(&aa, chain_type, false).unwrap(),
+            fr2_start(&aa, chain_type, false).unwrap(),
+            cdr2_start(&aa, chain_type, false).unwrap(),
+            fr3_start(&aa, chain_type, false).unwrap(),
+            cdr3_start(&aa, chain_type, false),
+        ];
+        assert_eq!(&found, bounds, "feature boundaries wrong for {}", name);
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 3. Test CDR1 and CDR2 for TCR protein sequences against structural truth data, see
+// enclone_tools/src/bin/cdr_truth_data.rs.  As described in vdj_features.rs, we add a fake
+// leader and truncate shortly after the start of the CDR3.
+
+#[test]
+fn test_tcr_cdrs() {
+    let examples = [
+        (
+            "3tjhC TRAV9D-3",
+            "TRA",
+            "AQSVTQPDARVTVSEGASLQLRCKYSYSATPYLFWYVQYPRQGLQMLLKYYSGDPVVQGVNGFEAEFSKSDSSFHLRKASVH\
+             WSDSAVYFCAV",
+            "YSATPY",
+            "YYSGDPVV",
+        ),
+        (
+            "3qiwC TRAV4N-4",
+            "TRA",
+            "GDQVEQSPSALSLHEGTGSALRCNFTTTMRAVQWFRKNSRGSLINLFYLASGTKENGRLKSAFDSKERYSTLHIRDAQLEDS\
+             GTYFCAA",
+            "TTMRA",
+            "LASGT",
+        ),
+        (
+            "3tjhD TRBV13-1",
+            "TRB",
+            "EAAVTQSPRNKVTVTGGNVTLSCRQTNSHNYMYWYRQDTGHGLRLIHYSYGAGNLQIGDVPDGYKATRTTQEDFFLLLELAS\
+             PSQTSLYFCAS",
+            "NSHNY",
+            "SYGAGN",
+        ),
+        (
+            "3mffB TRBV29",
+            "TRB",
+            "MKVTQMPRYLIKRMGENVLLECGQDMSHETMYWYRQDPGLGLQLIYISYDVDSNSEGDIPKGYRVSRKKREHFSLILDSAKT\
+             NQTSVYFCAS",
+            "MSHET",
+            "SYDVDS",
+        ),
+    ];
+    for (name, chain_type, seq, c1, c2) in examples.iter() {
+        let aa = format!("MXXXXXXXXXXXXXXXXXXX{}", seq).as_bytes().to_vec();
+        assert_eq!(
+            strme(&cdr1(&aa, chain_type, false).unwrap()),
+            *c1,
+            "CDR1 wrong for {}",
+            name
+        );
+        assert_eq!(
+            strme(&cdr2(&aa, chain_type, false).unwrap()),
+            *c2,
+            "CDR2 wrong for {}",
+            name
+        );
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 4. Test the leader and FWR1 heuristics, see conditions 11, 12 and 15 in denovo.rs.
+
+#[test]
+fn test_leader_heuristics() {
+    // Example from the lscore1 documentation, capped at 7.
+
+    assert_eq!(lscore1(b"MDWIWRILFLVGAATGAHS"), 7);
+    assert_eq!(lscore1(b"MDMRVPAQLLGLLLLWLRGARC"), 6);
+    assert_eq!(lscore1(b"MDWSHEGQS"), 0);
+
+    // Leader lengths 19 and 22 are common, 27 has never been seen.
+
+    assert_eq!(lscore2(&[b'X'; 19]), 1256);
+    assert_eq!(lscore2(&[b'X'; 22]), 142);
+    assert_eq!(lscore2(&[b'X'; 27]), 0);
+
+    // The human IGHV1-69D leader and FWR1 satisfy the length bounds.
+
+    let aa = b"MDWTWRFLFVVAAATGVQSQVQLVQSGAEVKKPGSSVKVSCKASGGTFSSYAISWVRQAPGQGLEWMGGIIPIFGTANYAQ\
+        KFQGRVTITADESTSTAYMELSSLRSEDTAVYYCAR"
+        .to_vec();
+    let ldr = &aa[0..fr1_start(&aa, "IGH")];
+    assert!(ldr.len() >= 17 && ldr.len() <= 28);
+    assert!(lscore1(ldr) >= 4);
+    let f1 = fwr1(&aa, "IGH", false).unwrap();
+    assert_eq!(strme(&f1), "QVQLVQSGAEVKKPGSSVKVSCKAS");
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 5. Test reverse complementation, including handling of N.
+
+#[test]
+fn test_reverse_complement() {
+    let mut x = b"ACGTTN".to_vec();
+    reverse_complement(&mut x);
+    assert_eq!(strme(&x), "NAACGT");
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 6. Validate the region API on all of the structural truth data, reporting per-region
+// accuracy.  The sequences are full V..J protein sequences without leader.  At the time of
+// writing, all 832 chains are correct for CDR1, CDR2 and the CDR3 start.
+
+#[test]
+fn test_regions_vs_truth_data() {
+    let f = std::fs::read_to_string("../enclone_tools/src/bin/cdr_truth_data.fasta").unwrap();
+    let lines = f.lines().collect::<Vec<&str>>();
+
+    // Each chain is given by a record whose name is the V gene, followed by records for
+    // CDR1, CDR2 and CDR3.
+
+    let mut chains = Vec::<(String, String, Vec<String>)>::new();
+    for i in (0..lines.len()).step_by(2) {
+        let fields = lines[i].after(">").split('|').collect::<Vec<&str>>();
+        if fields[2].starts_with("TR") {
+            let chain_type = if fields[1] == "VA" { "TRA" } else { "TRB" };
+            chains.push((
+                fields[0].to_string(),
+                chain_type.to_string(),
+                vec![lines[i + 1].to_string()],
+            ));
+        } else {
+            chains.last_mut().unwrap().2.push(lines[i + 1].to_string());
+        }
+    }
+    let names = ["CDR1", "CDR2", "CDR3 start"];
+    let mut correct = [0; 3];
+    for (id, chain_type, seqs) in chains.iter() {
+        assert_eq!(seqs.len(), 4, "truth data incomplete for {}", id);
+        let r = match v_regions(seqs[0].as_bytes(), chain_type, false) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        if r.region_seq("CDR1") == Some(seqs[1].as_bytes()) {
+            correct[0] += 1;
+        }
+        if r.region_seq("CDR2") == Some(seqs[2].as_bytes()) {
+            correct[1] += 1;
+        }
+        if seqs[0][r.cdr3_start..].starts_with(&seqs[3]) {
+            correct[2] += 1;
+        }
+    }
+    let n = chains.len();
+    println!("\nregion accuracy on {} chains:", n);
+    for i in 0..names.len() {
+        println!(
+            "{} = {} = {:.1}%",
+            names[i],
+            correct[i],
+            100.0 * correct[i] as f64 / n as f64
+        );
+    }
+    for i in 0..names.len() {
+        assert!(
+            correct[i] as f64 >= 0.99 * n as f64,
+            "accuracy for {} is too low",
+            names[i]
+        );
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 7. Test the region API on bases, offset from the reading frame, and on an amino acid sequence
+// without leader, or missing the start of FWR1.  The boundaries should agree with test 2.  Codons are chosen so that the
+// other two frames contain stop codons.
+
+#[test]
+fn test_regions_from_bases() {
+    let aa = "MDWTWRFLFVVAAATGVQSQVQLVQSGAEVKKPGSSVKVSCKASGGTFSSYAISWVRQAPGQGLEWMGGIIPIFGTANYAQ\
+              KFQGRVTITADESTSTAYMELSSLRSEDTAVYYCAR";
+    let bounds = [19, 44, 51, 70, 76, 115];
+    let codon = |c: char| match c {
+        'A' => "GCT",
+        'C' => "TGC",
+        'D' => "GAC",
+        'E' => "GAG",
+        'F' => "TTC",
+        'G' => "GGT",
+        'I' => "ATC",
+        'K' => "AAG",
+        'L' => "CTG",
+        'M' => "ATG",
+        'N' => "AAC",
+        'P' => "CCC",
+        'Q' => "CAG",
+        'R' => "AGA",
+        'S' => "AGT",
+        'T' => "ACT",
+        'V' => "GTA",
+        'W' => "TGG",
+        'Y' => "TAC",
+        _ => panic!("unexpected amino acid {}", c),
+    };
+    let mut bases = "GA".to_string();
+    for c in aa.chars() {
+        bases += codon(c);
+    }
+    let r = v_regions_from_bases(bases.as_bytes(), "IGH", true).unwrap();
+    assert_eq!(r.frame, 2);
+    assert_eq!(strme(&r.aa), aa);
+    let mut found = r.regions.iter().map(|x| x.start).collect::<Vec<usize>>();
+    found.push(r.cdr3_start);
+    assert_eq!(found, bounds);
+    for x in r.regions.iter() {
+        assert!(x.confidence >= 0.0 && x.confidence <= 1.0);
+    }
+
+    // Now remove the leader.
+
+    let r = v_regions(&aa.as_bytes()[19..], "IGH", false).unwrap();
+    let mut found = r.regions.iter().map(|x| x.start).collect::<Vec<usize>>();
+    found.push(r.cdr3_start);
+    assert_eq!(found, bounds.iter().map(|x| x - 19).collect::<Vec<usize>>());
+
+    // And the first two amino acids of FWR1, so that FWR1 would start before the sequence.
+
+    let r = v_regions(&aa.as_bytes()[21..], "IGH", false).unwrap();
+    assert!(r.region("FWR1").is_none());
+    let mut found = r.regions.iter().map(|x| x.start).collect::<Vec<usize>>();
+    found.push(r.cdr3_start);
+    assert_eq!(
+        found,
+        bounds[1..].iter().map(|x| x - 21).collect::<Vec<usize>>()
+    );
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 8. Test that the V genes planted in the synthetic V locus are found, with the correct leader
+// exon, intron and V exon, and that the end of FWR3 is found.  This uses only the built-in FWR3
+// frequency table, and not the v_segments directory.
+
+#[test]
+fn test_synthetic_v_genes() {
+    let refx = read_genome_fasta("test_data/synthetic_v_locus.fasta").unwrap();
+    let opts = DenovoOpts {
+        species: "synthetic".to_string(),
+        use_v: true,
+        store_fasta: true,
+        ..Default::default()
+    };
+    let fasta = denovo(&opts, refx).fasta;
+    let mut found = Vec::<String>::new();
+    let lines = strme(&fasta).lines().collect::<Vec<&str>>();
+    for i in (0..lines.len()).step_by(2) {
+        if !lines[i].ends_with("UTR") {
+            found.push(lines[i + 1].to_string());
+        }
+    }
+    found.sort();
+    let truth = std::fs::read_to_string("test_data/synthetic_v_locus_truth.fasta").unwrap();
+    let mut truth = truth
+        .lines()
+        .filter(|x| !x.starts_with('>'))
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    truth.sort();
+    assert_eq!(found, truth);
+
+    // Check FWR3 for each gene, in the order IGHV1-69, IGHV1-2, IGHV3-23, IGHV3-30, IGHV4-34,
+    // IGHV5-51.
+
+    let fwr3s = [
+        "ANYAQKFQGRVTITADESTSTAYMELSSLRSEDTAVYYC",
+        "TNYAQKFQGRVTMTRDTSISTAYMELSRLRSDDTAVYYC",
+        "TYYADSVKGRFTISRDNSKNTLYLQMNSLRAEDTAVYYC",
+        "KYYADSVKGRFTISRDNSKNTLYLQMNSLRAEDTAVYYC",
+        "TNYNPSLKSRVTISVDTSKNQFSLKLSSVTAADTAVYYC",
+        "TRYSPSFQGQVTISADKSISTAYLQWSSLKASDTAMYYC",
+    ];
+    let mut found_fwr3s = Vec::<String>::new();
+    for x in found.iter() {
+        let aa = aa_seq(x.as_bytes(), 0);
+        found_fwr3s.push(strme(&fwr3(&aa, "IGH", false).unwrap()).to_string());
+    }
+    found_fwr3s.sort();
+    let mut fwr3s = fwr3s.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+    fwr3s.sort();
+    assert_eq!(found_fwr3s, fwr3s);
+}
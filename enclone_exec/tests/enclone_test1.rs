@@ -12,6 +12,7 @@ use anyhow::Error;
 use enclone_core::*;
 use enclone_proto::proto_io::{read_proto, ClonotypeIter};
 use enclone_proto::types::EncloneOutputs;
+use enclone_testlist::test_spec::TestSpec;
 use enclone_testlist::TEST_FILES_VERSION;
 use enclone_tools::html::*;
 use enclone_tools::run_test::*;
@@ -459,7 +460,7 @@ fn test_cpu() {
     // Speed test 1.
 
     let it = 1;
-    let test = "BI=10 NCROSS NGEX NOPRINT PRINT_CPU NCORES BUILT_IN EXPECT_OK EXPECT_NULL NO_PRE";
+    let test = "BI=10 NCROSS NGEX NOPRINT PRINT_CPU BUILT_IN";
     let expect = 16638;
     let percent_dev = 6.0;
     println!("\nSpeed test 1");
@@ -467,9 +468,6 @@ fn test_cpu() {
         "\nThis tests cpu cycles.  If the code is parallelized better, this test may get \n\
         slower.  Such changes should be accepted if they reduce wallclock."
     );
-    let mut out = String::new();
-    let mut ok = false;
-    let mut log = String::new();
     let mut cpu_all_start = 0;
     {
         let f = open_for_read!["/proc/stat"];
@@ -483,17 +481,14 @@ fn test_cpu() {
             break;
         }
     }
-    run_test(
-        env!("CARGO_BIN_EXE_enclone"),
-        it,
-        "",
-        &test,
-        "cpu",
-        &mut ok,
-        &mut log,
-        &mut out,
-        0,
-    );
+    let spec = TestSpec::new("cpu", it, "", test)
+        .expect_ok()
+        .no_pre()
+        .ncores();
+    let out = match run_spec(env!("CARGO_BIN_EXE_enclone"), 0, &spec) {
+        Ok(res) => res.stdout,
+        Err(res) => panic!("{}", res.log),
+    };
     let this_used = out.before("\n").force_usize();
     let mut cpu_all_stop = 0;
     {
@@ -524,8 +519,7 @@ fn test_cpu() {
     // Speed test 2.
 
     let it = 2;
-    let test =
-        "BI=1-2,5-12 MIX_DONORS NOPRINT PRINT_CPU NCORES BUILT_IN EXPECT_OK EXPECT_NULL NO_PRE";
+    let test = "BI=1-2,5-12 MIX_DONORS NOPRINT PRINT_CPU BUILT_IN";
     let expect = 137.6;
     let percent_dev = 6.0;
     println!("Speed test 2");
@@ -536,20 +530,13 @@ fn test_cpu() {
         after 60 seconds.\n"
     );
     let t = Instant::now();
-    let mut out = String::new();
-    let mut ok = false;
-    let mut log = String::new();
-    run_test(
-        env!("CARGO_BIN_EXE_enclone"),
-        it,
-        "",
-        &test,
-        "cpu",
-        &mut ok,
-        &mut log,
-        &mut out,
-        0,
-    );
+    let spec = TestSpec::new("cpu", it, "", test)
+        .expect_ok()
+        .no_pre()
+        .ncores();
+    if let Err(res) = run_spec(env!("CARGO_BIN_EXE_enclone"), 0, &spec) {
+        panic!("{}", res.log);
+    }
     let this_used = t.elapsed().as_secs_f64();
     let dev = 100.0 * (this_used as f64 - expect as f64) / (expect as f64);
     println!(
@@ -13,6 +13,7 @@ use enclone_core::*;
 use enclone_proto::proto_io::{read_proto, ClonotypeIter};
 use enclone_proto::types::EncloneOutputs;
 use enclone_testlist::main_testlist::*;
+use enclone_testlist::test_spec::TestSpec;
 use enclone_testlist::*;
 use enclone_tools::html::*;
 use enclone_tools::run_test::*;
@@ -42,101 +43,123 @@ const LOUPE_OUT_FILENAME: &str = "testx/__test_proto";
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
-// 10. The following is a single test, containing many subtests, each of which is a regression test
-// for a given enclone command line.
+// 10. The main regression tests, each for a given enclone command line.  These are declared in
+// enclone_testlist/src/main_testlist.rs, and each becomes a test here, named test_enclone_<n>.
 //
-// If you ever need to change the output of all tests, use the main program
-// update_all_main_tests.rs in enclone/src/bin.  Note that there is some duplicated code there.
-//
-// If this code is modified to also include the DTESTS, the test time is almost exactly the same.
-
-#[cfg(not(feature = "cpu"))]
-#[test]
-fn test_enclone() {
-    run_tests(env!("CARGO_BIN_EXE_enclone"), "test", 0, &TESTS);
+// If you ever need to change the output of many tests, run them with UPDATE_GOLDEN=1, which
+// rewrites the golden files that changed, and then review the changes, which are shown when
+// cargo test is run with --nocapture, or by git diff.
+
+macro_rules! declared_tests {
+    ($($name:ident => $spec:expr),* $(,)?) => {
+        $(
+            #[cfg(not(feature = "cpu"))]
+            #[test]
+            fn $name() {
+                run_declared_test(env!("CARGO_BIN_EXE_enclone"), &$spec);
+            }
+        )*
+    };
 }
 
+main_tests!(declared_tests);
+
 #[cfg(not(feature = "cpu"))]
 #[test]
 /// Tests that are affected by the D region alignment algorithm.
 fn test_enclone_d() {
     run_tests(
         env!("CARGO_BIN_EXE_enclone"),
-        "dtest",
         40,
         &[
-            (
+            TestSpec::new(
+                "dtest",
                 1,
                 "test ALIGN_2ND<n>",
                 r###"BCR=123085 CDR3=CKVMLYDSRGSDYYYVMDVW ALIGN_2ND1 CVARS=d1_name"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 2,
                 "test JALIGN_2ND<n>",
                 r###"BCR=123085 CDR3=CKVMLYDSRGSDYYYVMDVW JALIGN_2ND1 CVARS=d2_name"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 3,
                 "test ALIGN_JALIGN_CONSISTENCY",
                 r###"BCR=123085 CELLS=1 CHAINS=2 ALIGN1 JALIGN1 ALIGN_JALIGN_CONSISTENCY AMINO=cdr3
-             PLAIN NOPAGER EXPECT_OK"###,
-            ),
-            (
+             PLAIN NOPAGER"###,
+            )
+            .expect_ok(),
+            TestSpec::new(
+                "dtest",
                 4,
                 "test D_INCONSISTENT, and lock number of inconsistencies",
                 r###"BCR=123085 D_INCONSISTENT CVARS=d1_name COMPLETE NGROUP"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 5,
                 "the JALIGN1 in this example had a boundary location that was off by one",
                 r###"BCR=165807 JALIGN1 AMINO=cdr3 CVARS=d1_score,d2_score CDR3=CAKEYYDFWSGYSDVRGVIPNIDYW"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 6,
                 "the JALIGN1 in this example had a boundary location that was off by one",
                 r###"BCR=123085 CELLS=2 JALIGN1 AMINO=cdr3 CVARS=d1_name CDR3=CAKAGPTESGYYVWYFDLW"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 7,
                 "test d_inconsistent_{%,n}",
                 r###"BCR=123085 GVARS=d_inconsistent_%,d_inconsistent_n NOPRINT SUMMARY SUMMARY_CLEAN"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 8,
                 "test ALIGN<n>",
                 r###"BCR=123085 CDR3=CKVMLYDSRGSDYYYVMDVW ALIGN1 CVARS=d1_name"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 9,
                 "test ALIGN<n> and JALIGN<n>, case where there's a D segment",
                 r###"BCR=85333 ALIGN1 JALIGN1 CDR3=CARGYDFWSGYLVGNWAGDYYYYMDVW"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 10,
                 "test ALIGN<n> and JALIGN<n>, case where there is no D segment",
                 r###"BCR=85333 ALIGN1 JALIGN1 CDR3=CAKGKGFRNYYYYMDVW"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 11,
                 "test d1 etc.",
                 r###"BCR=123085 CVARS=d1_name,d2_name,d_Δ,d_delta AMINO=cdr3 CDR3=CARVRDILTGDYGMDVW"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 12,
                 "test GROUP_VDJ_REFNAME_HEAVY (deprecated but supported)",
                 r###"BCR=86237 GROUP_VDJ_REFNAME_HEAVY CDR3="CAKAVAGKAVAGGWDYW|CAKVSTGIAVAGPGDYW" COMPLETE"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 13,
                 "test GROUP_VJ_REFNAME_HEAVY (deprecated but supported)",
                 r###"BCR=86237 GROUP_VJ_REFNAME_HEAVY CDR3="CARGVLWFGELGAFDIW|CARAGLGVVLAARGAFDIW""###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 14,
                 "test placement of indel, needed shifting right",
                 r###"BCR=123085 CELLS=1 CHAINS=2 AMINO=cdr3 JALIGN2 CDR3=CAKDKSRPPTHYYGSGSYYSRILDNW"###,
             ),
-            (
+            TestSpec::new(
+                "dtest",
                 15,
                 "test placement of indel, needed shifting left",
                 r###"BCR=123085 CELLS=1 CHAINS=2 AMINO=cdr3 JALIGN2 CDR3=CARMAQFYSGSGTYYIGPYYFEYW"###,
@@ -151,131 +174,150 @@ fn test_enclone_d() {
 fn test_grouping() {
     run_tests(
         env!("CARGO_BIN_EXE_enclone"),
-        "gtest",
         0,
         &[
-            (
+            TestSpec::new(
+                "gtest",
                 1,
                 "test 5/8 for newline correctness (this grouping option deprecated but supported)",
-                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN SET_IN_STONE"###,
-            ),
-            (
+                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN"###,
+            ).set_in_stone(),
+            TestSpec::new(
+                "gtest",
                 2,
                 "test 6/8 for newline correctness (this grouping option deprecated but supported)",
-                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN NGROUP SET_IN_STONE"###,
-            ),
-            (
+                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN NGROUP"###,
+            ).set_in_stone(),
+            TestSpec::new(
+                "gtest",
                 3,
                 "test 7/8 for newline correctness (this grouping option deprecated but supported)",
-                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN HTML SET_IN_STONE"###,
-            ),
-            (
+                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN HTML"###,
+            ).set_in_stone(),
+            TestSpec::new(
+                "gtest",
                 4,
                 "test 8/8 for newline correctness (this grouping option deprecated but supported)",
-                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN HTML NGROUP SET_IN_STONE"###,
-            ),
-            (
+                r###"BCR=85333 GROUP_VJ_REFNAME MIN_GROUP=2 AMINO= PLAIN HTML NGROUP"###,
+            ).set_in_stone(),
+            TestSpec::new(
+                "gtest",
                 5,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=vj_refname,cdr3_aa_heavy≥80%,cdr3_aa_light≥80% CVARS=cdr3_len
              AMINO=cdr3 CDR3="CARHLQWELP.*W""###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 6,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=vj_refname,len,cdr3_len MIN_GROUP=2 MIN_CHAINS=2 CDR3="CQQSY.*TLATF"
              CVARS=cdr3_len"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 7,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=cdr3_aa_heavy≥100% MIN_GROUP=2 MIN_CHAINS=2 CVARS=cdr3_len
              CDR3=CARPKSDYIIDAFDIW"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 8,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=cdr3_aa_light≥100% MIN_GROUP=2 MIN_CHAINS=2 CVARS=cdr3_len
              CDR3=CQTWGTGPWVF"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 9,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=vj_refname,aa_heavy≥100% MIN_GROUP=2 MIN_CHAINS=2 CVARS=cdr3_len
              CDR3=CARVPYYYDRSYYYYGMDVW"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 10,
                 "test of AGROUP",
                 r###"BCR=123085 AGROUP AG_CENTER=from_filters CDR3=CARHSYSSGWYDEWDYW
              AG_DIST_FORMULA=cdr3_edit_distance AG_DIST_BOUND=top=2"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 11,
                 "test of AGROUP",
                 r###"BCR=123085 AGROUP AG_CENTER=from_filters CDR3=CAKDGGEHYYDSSGYYASYYFDYW 
              AG_DIST_FORMULA=cdr3_edit_distance AG_DIST_BOUND=max=14"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 12,
                 "test of AGROUP",
                 r###"BCR=123085 AGROUP AG_CENTER=from_filters CDR3=CAKDGGEHYYDSSGYYASYYFDYW 
              AG_DIST_FORMULA=cdr3_edit_distance AG_DIST_BOUND=max=13"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 13,
                 "test of AGROUP",
                 r###"BCR=123085 AGROUP AG_CENTER=copy_filters MIN_CELLS=2 MAX_CELLS=2
              AG_DIST_FORMULA=cdr3_edit_distance AG_DIST_BOUND=max=3 MIN_GROUP=2"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 14,
                 "test symmetric grouping stats",
                 r###"BCR=123085 GROUP=vj_refname,cdr3_aa_heavy≥80%,cdr3_aa_light≥80% NOPRINT
              SUMMARY SUMMARY_CLEAN"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 15,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=cdr3_heavy≥100% MIN_GROUP=2 MIN_CHAINS=2 CVARS=cdr3_len
              CDR3=CARPKSDYIIDAFDIW"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 16,
                 "test of GROUP",
                 r###"BCR=123085 GROUP="cdr3_light>=100%" MIN_GROUP=2 MIN_CHAINS=2 CVARS=cdr3_len
              CDR3=CQTWGTGPWVF"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 17,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=vj_refname,heavy≥96.6% MIN_GROUP=2 MIN_CHAINS=2 
              CDR3="CARVIVGPKKLEGRLYSSSLHFDCW|CARVIVGPEKQEGRLYSSSLHFDYW" POUT=stdout PCOLS=vj_seq1"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 18,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=vj_heavy_refname,cdr3_heavy_len,cdr3_heavy≥80% LVARS=n,donors,dref 
              CVARS=const,cdr3_len AMINO=cdr3 CHAINS=2 MIN_GROUP=2
              CDR3="CARDLHGYDPYGMDVW|CARELRHYDTYGMDVW""###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 19,
                 "test of GROUP",
                 r###"BCR=123085 GROUP=vj_refname,cdr3_light_len LVARS=n,donors,dref CVARS=const,cdr3_len 
              AMINO=cdr3 CHAINS=2 MIN_GROUP=2 CDR3="CARESAVAGDMDVW|CARDYGDYRWWVDGMDVW""###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 20,
                 "test of group",
                 r###"BCR=123085 GROUP=vj_refname GROUP_CDR3=CACFGRIGVVVRAAHYW"###,
             ),
-            (
+            TestSpec::new(
+                "gtest",
                 21,
                 "test of group, asserted at one time",
-                r###"BCR=123085 GROUP=vj_refname,cdr3_len MIN_GROUP=3 HONEY=out=stdout,color=var,u1 
-             EXPECT_OK"###,
-            ),
+                r###"BCR=123085 GROUP=vj_refname,cdr3_len MIN_GROUP=3 HONEY=out=stdout,color=var,u1"###,
+            ).expect_ok(),
         ],
     );
 }
@@ -288,41 +330,38 @@ fn test_grouping() {
 fn test_extended() {
     run_tests(
         env!("CARGO_BIN_EXE_enclone"),
-        "ext_test",
         0,
         &[
-            (1, "Make sure that POUT works on full dataset. If we experience failures on other PUBLIC ids, we can add them to this list.",
-            r###"BCR=86237 RE POUT=/dev/null NOPRINT EXPECT_OK NO_PRE"###),
-            (2, "tests nd2",
-            r###"BCR=47199,47200,47212 AMINO=cdr3 NCROSS LVARS=nd2 CDR3=CVKGKSGSFWYYFENW
-             NO_PRE"###),
-            (3, "test sec and mem [requires samtools]",
+            TestSpec::new("ext_test", 1, "Make sure that POUT works on full dataset. If we experience failures on other PUBLIC ids, we can add them to this list.",
+            r###"BCR=86237 RE POUT=/dev/null NOPRINT"###).expect_ok().no_pre(),
+            TestSpec::new("ext_test", 2, "tests nd2",
+            r###"BCR=47199,47200,47212 AMINO=cdr3 NCROSS LVARS=nd2 CDR3=CVKGKSGSFWYYFENW"###).no_pre(),
+            TestSpec::new("ext_test", 3, "test sec and mem [requires samtools]",
             r###"BCR=123085 GEX=123217 LVARSP=sec,mem CDR3=CVKDRVTGTITELDYW"###),
-            (4, "crashed at one point",
-            r###"BCR=128037,128040 GEX=127798,127801 LVARSP=pe1 NOPRINT EXPECT_OK NO_PRE"###),
+            TestSpec::new("ext_test", 4, "crashed at one point",
+            r###"BCR=128037,128040 GEX=127798,127801 LVARSP=pe1 NOPRINT"###).expect_ok().no_pre(),
             //
-            (5, "this added because it got better when a bug in bads detection was fixed",
-            r###"TCR=163914 CDR3=CASRLGGEETQYF NO_PRE"###),
-            (6, "Test PCHAINS=max.  For this we need a clonotype having at least five chains, and the \
+            TestSpec::new("ext_test", 5, "this added because it got better when a bug in bads detection was fixed",
+            r###"TCR=163914 CDR3=CASRLGGEETQYF"###).no_pre(),
+            TestSpec::new("ext_test", 6, "Test PCHAINS=max.  For this we need a clonotype having at least five chains, and the \
                 question is whether the header line represents cvars for all the chains.  The output of
                 this is expected to change whenever variables are added.",
             r###"BCR=123085,123089,124547 NWEAK_CHAINS NDOUBLET MIN_CHAINS=5 POUT=stdout PCHAINS=max
-             NOPRINT RE NO_PRE"###),
-            (7, "test MIN_GROUP_DONORS",
-            r###"BCR="40953;43899" MIX_DONORS MIN_GROUP=2 NO_PRE
-             GROUP="cdr3_len,cdr3_aa_heavy>=85%,cdr3_aa_light>=85%,vj_refname" MIN_GROUP_DONORS=2"###),
-            (8, "this asserted at one point",
+             NOPRINT RE"###).no_pre(),
+            TestSpec::new("ext_test", 7, "test MIN_GROUP_DONORS",
+            r###"BCR="40953;43899" MIX_DONORS MIN_GROUP=2
+             GROUP="cdr3_len,cdr3_aa_heavy>=85%,cdr3_aa_light>=85%,vj_refname" MIN_GROUP_DONORS=2"###).no_pre(),
+            TestSpec::new("ext_test", 8, "this asserted at one point",
             r###"BUILT_IN GROUP=vj_refname,cdr3_aa_heavy≥90% MIN_CHAINS_EXACT=2 MIN_GROUP=2 
-             KEEP_CLONO_IF_CELL_MEAN="cdr3_len1>=18" BCR=1018096-1018098 JALIGN1 NO_PRE
-             EXPECT_OK"###),
-            (9, "this clonotype included a junk chain before we made a change, and test /outs",
-            r###"TCR=163911/outs CDR3=CAPSAGDKIIF AMINO=donor NO_PRE"###),
-            (10, "test case where digit rows are just barely present",
-            r###"TCR=163911 CDR3=CASSLVQPSTDTQYF AMINO=donor NO_PRE"###),
-            (11, "this added because it got better when a noise filter was added, also tests u_max",
-            r###"TCR=163914 CDR3=CASSLVQPSTDTQYF CVARSP=u_max NO_PRE"###),
-            (12, "this added because it got better when a noise filter was added; also test FASTA",
-            r###"TCR=163914 CDR3=CAFRGGSYIPTF FASTA=stdout NO_PRE"###),
+             KEEP_CLONO_IF_CELL_MEAN="cdr3_len1>=18" BCR=1018096-1018098 JALIGN1"###).expect_ok().no_pre(),
+            TestSpec::new("ext_test", 9, "this clonotype included a junk chain before we made a change, and test /outs",
+            r###"TCR=163911/outs CDR3=CAPSAGDKIIF AMINO=donor"###).no_pre(),
+            TestSpec::new("ext_test", 10, "test case where digit rows are just barely present",
+            r###"TCR=163911 CDR3=CASSLVQPSTDTQYF AMINO=donor"###).no_pre(),
+            TestSpec::new("ext_test", 11, "this added because it got better when a noise filter was added, also tests u_max",
+            r###"TCR=163914 CDR3=CASSLVQPSTDTQYF CVARSP=u_max"###).no_pre(),
+            TestSpec::new("ext_test", 12, "this added because it got better when a noise filter was added; also test FASTA",
+            r###"TCR=163914 CDR3=CAFRGGSYIPTF FASTA=stdout"###).no_pre(),
         ],
     );
 }
@@ -362,21 +401,21 @@ fn test_crash() {
     .iter()
     .map(|(num, crash_set)| {
         (
-            num,
-            "crash test",
-            format!(
-            "BCR=\"45977;123085;testx/inputs/flaky\" {crash_set} NOPRINT BUILT_IN EXPECT_OK NO_PRE",
-        ),
+            *num,
+            format!("BCR=\"45977;123085;testx/inputs/flaky\" {crash_set} NOPRINT BUILT_IN"),
         )
     })
     .collect();
     run_tests(
         env!("CARGO_BIN_EXE_enclone"),
-        "crash_test",
         40,
         &crash_tests
             .iter()
-            .map(|(num, comment, args)| (**num, *comment, args.as_str()))
+            .map(|(num, args)| {
+                TestSpec::new("crash_test", *num, "crash test", args)
+                    .expect_ok()
+                    .no_pre()
+            })
             .collect_vec(),
     );
 }
@@ -388,15 +427,14 @@ fn test_crash() {
 fn test_internal() {
     run_tests(
         env!("CARGO_BIN_EXE_enclone"),
-        "internal_test",
         40,
         &[
-            (1, "gave wrong result",
+            TestSpec::new("internal_test", 1, "gave wrong result",
             r###"123085 CDR3=CARDRIAGRFGYGMDVW"###),
-            (2, "test human + IMGT; note that specifying by number forces BCR+TCR reference checks",
-            r###"123085 REQUIRE_UNBROKEN_OK IMGT ACCEPT_BROKEN EXPECT_NULL"###),
-            (3, "this crashed; it is not exactly an internal feature test but uses an internal feature (IMGT) to exhibit the phenomenon",
-            r###"BCR=123085 IMGT RE ACCEPT_BROKEN POUT=stdout PCELL BARCODE=AGCAGCCCATTAGGCT-1 EXPECT_OK"###),
+            TestSpec::new("internal_test", 2, "test human + IMGT; note that specifying by number forces BCR+TCR reference checks",
+            r###"123085 REQUIRE_UNBROKEN_OK IMGT ACCEPT_BROKEN"###).expect_null(),
+            TestSpec::new("internal_test", 3, "this crashed; it is not exactly an internal feature test but uses an internal feature (IMGT) to exhibit the phenomenon",
+            r###"BCR=123085 IMGT RE ACCEPT_BROKEN POUT=stdout PCELL BARCODE=AGCAGCCCATTAGGCT-1"###).expect_ok(),
         ],
     );
 }
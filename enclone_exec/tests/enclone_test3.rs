@@ -6,9 +6,13 @@ use ansi_escape::*;
 use anyhow::Error;
 use enclone_core::defs::*;
 use enclone_core::*;
+use enclone_help::help_examples::{
+    help_example_args, help_example_drift, help_page_examples, site_examples,
+};
 use enclone_proto::proto_io::{read_proto, ClonotypeIter};
 use enclone_proto::types::EncloneOutputs;
 use enclone_testlist::main_testlist::*;
+use enclone_testlist::test_spec::TestSpec;
 use enclone_testlist::*;
 use enclone_tools::html::*;
 use enclone_tools::run_test::*;
@@ -34,26 +38,25 @@ const LOUPE_OUT_FILENAME: &str = "testx/__test_proto";
 // NOT BASIC
 
 // Test site examples to make sure they are what they claim to be, and that the
-// merged html files are correct.
+// merged html files are correct.  They are declared in enclone_help/src/help_examples.rs.
 
 #[cfg(not(feature = "basic"))]
 #[cfg(not(feature = "cpu"))]
 #[test]
 fn test_site_examples() {
+    let examples = site_examples().collect::<Vec<_>>();
     let mut results = Vec::<(usize, bool, String)>::new();
-    for i in 0..SITE_EXAMPLES.len() {
+    for i in 0..examples.len() {
         results.push((i, false, String::new()));
     }
     results.par_iter_mut().for_each(|res| {
         let i = res.0;
-        let example_name = SITE_EXAMPLES[i].0;
-        let test = SITE_EXAMPLES[i].1;
+        let example_name = examples[i].file;
         let in_file = format!("../{}", example_name);
         let mut f = File::open(&in_file).unwrap_or_else(|_| panic!("couldn't find {}", in_file));
         let mut in_stuff = Vec::<u8>::new();
         f.read_to_end(&mut in_stuff).unwrap();
-        let mut args = parse_bsv(test);
-        args.push("NO_KILL");
+        let args = help_example_args(examples[i], "");
         let new = Command::new(env!("CARGO_BIN_EXE_enclone"))
             .args(&args)
             .output()
@@ -163,53 +166,51 @@ fn test_site_examples() {
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
-// 16. Test that examples are what we claim they are.
+// 16. Test that examples in the help pages are what we claim they are.  They are declared in
+// enclone_help/src/help_examples.rs.
 
 #[cfg(not(feature = "cpu"))]
 #[test]
 fn test_enclone_examples() {
-    let examples = [
-        (1, r###"BCR=123089 CDR3=CARRYFGVVADAFDIW"###),
-        (
-            2,
-            r###"BCR=123085 GEX=123217 LVARSP=gex,IGHV2-5_g_μ CDR3=CALMGTYCSGDNCYSWFDPW"###,
-        ),
-    ];
-    for (num, args) in examples {
-        let out_file = format!("../enclone_help/src/example{}", num);
-        let old = read_to_string(&out_file).unwrap();
-        let mut new = Command::new(env!("CARGO_BIN_EXE_enclone"));
-        let mut new = new.arg(format!(
-            "PRE=../enclone-data/big_inputs/version{}",
-            TEST_FILES_VERSION
-        ));
-        for arg in args.split(' ') {
-            new = new.arg(arg);
-        }
-        let new = new
-            .arg("NO_KILL")
+    let pre = format!("../enclone-data/big_inputs/version{}", TEST_FILES_VERSION);
+    let mut fail = false;
+    for ex in help_page_examples() {
+        let new = Command::new(env!("CARGO_BIN_EXE_enclone"))
+            .args(&help_example_args(ex, &pre))
             .output()
             .expect("failed to execute test_enclone_examples");
-        let new2 = stringme(&new.stdout);
         if new.status.code() != Some(0) {
             eprint!(
-                "\nenclone_test_examples: example{} failed to execute, stderr =\n{}",
-                num,
+                "\nenclone_test_examples: example {} failed to execute, stderr =\n{}",
+                ex.page().unwrap(),
                 strme(&new.stderr),
             );
             eprintln!("If it's not clear what is happening, make sure you've run ./build.\n");
-            panic!("failed");
+            fail = true;
+            continue;
         }
-        if old != new2 {
+        let new2 = stringme(&new.stdout);
+        if let Some(drift) = help_example_drift(ex, &new2) {
+            let save = format!("testx/outputs/{}", ex.file.rev_after("/"));
+            {
+                let mut f = open_for_write_new![&save];
+                fwrite!(f, "{}", new2);
+            }
             eprintln!(
-                "\nenclone_test_examples: the file enclone_help/src/example{} is not up to date\n",
-                num
+                "\nenclone_test_examples: the file {} is not up to date:\n{}\
+                 Please diff {} enclone_exec/{}.\n",
+                ex.file, drift, ex.file, save
             );
-            eprintln!("old output =\n{}", old);
-            eprintln!("new output =\n{}\n", new2);
-            panic!("failed");
+            fail = true;
         }
     }
+    if fail {
+        eprintln!(
+            "If you're satisfied with the new output, you can update using\n\n\
+             target/debug/update_help_examples\n"
+        );
+        panic!("failed");
+    }
 }
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
@@ -366,9 +367,10 @@ fn test_enclone_prebuild() {
         remove_file(&mb).unwrap();
     }
 
-    let (test_num, _comment, args) = TESTS[47];
-    assert_eq!(48, test_num);
-    let out_file = format!("testx/inputs/outputs/enclone_test{}_output", test_num);
+    let spec = &TESTS[47];
+    assert_eq!(48, spec.num);
+    let args = spec.args;
+    let out_file = spec.golden_file();
     let old = read_to_string(&out_file).unwrap();
     let mut new = Command::new(env!("CARGO_BIN_EXE_enclone"));
     let mut new = new.arg(format!(
@@ -554,13 +556,15 @@ fn test_proto_write() -> Result<(), Error> {
 #[cfg(not(feature = "cpu"))]
 #[test]
 fn test_annotated_example() -> Result<(), String> {
-    run_test(
+    run_spec(
         env!("CARGO_BIN_EXE_enclone"),
-        "annotated_example_test",
         0,
-        1,
-        "",
-        "BCR=123085 CDR3=CTRDRDLRGATDAFDIW",
+        &TestSpec::new(
+            "annotated_example_test",
+            1,
+            "",
+            "BCR=123085 CDR3=CTRDRDLRGATDAFDIW",
+        ),
     )
     .map_err(|res| {
         format!(
@@ -738,3 +742,52 @@ fn test_ref_only() {
         panic!("failed");
     }
 }
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// 42. Test that enclone completions writes the PRE directories into the script, from PRE,
+// ENCLONE_PRE and PREPOST.
+
+#[cfg(not(feature = "cpu"))]
+#[test]
+fn test_completions_pre() {
+    let cases = [
+        (
+            vec!["bash", "PRE=/x/pre1,/x/pre2"],
+            None,
+            "_enclone_pre=('/x/pre1' '/x/pre2')",
+        ),
+        (vec!["zsh"], Some("/x/pre3"), "_enclone_pre=('/x/pre3')"),
+        (
+            vec!["fish", "PRE=/x/pre1", "PREPOST=sub"],
+            None,
+            "set -g __enclone_pre '/x/pre1' '/x/pre1/sub'",
+        ),
+    ];
+    for (args, env_pre, expected) in cases.iter() {
+        let mut new = Command::new(env!("CARGO_BIN_EXE_enclone"));
+        let mut new = new.arg("completions").args(args).env_remove("ENCLONE_PRE");
+        if let Some(pre) = env_pre {
+            new = new.env("ENCLONE_PRE", pre);
+        }
+        let new = new
+            .output()
+            .expect("failed to execute test_completions_pre");
+        if new.status.code() != Some(0) {
+            eprintln!(
+                "\nenclone completions {} failed:\n{}",
+                args.join(" "),
+                strme(&new.stderr)
+            );
+            panic!("failed");
+        }
+        if !strme(&new.stdout).contains(expected) {
+            eprintln!(
+                "\nenclone completions {} did not contain\n{}\n",
+                args.join(" "),
+                expected
+            );
+            panic!("failed");
+        }
+    }
+}
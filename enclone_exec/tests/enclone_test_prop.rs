@@ -0,0 +1,397 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Property-based tests.  These run enclone on generated argument lists, including PRE, PREPOST
+// and SOURCE, and on small generated datasets, and check that enclone either succeeds or exits
+// with an error message, but does not panic.  The datasets are derived from the test inputs
+// flaky (BCR) and flaky6 (TCR), by subsetting and editing their contigs, so no other data are
+// needed.
+//
+// The number of cases per test is 32, and may be changed by setting PROPTEST_CASES.  A failing
+// case is shrunk by proptest to a small one, which is printed, and saved to be rerun first the
+// next time.  Each case is run in its own directory, under the system temporary directory, so
+// that files written by enclone, e.g. by POUT=file, go there, and the directory is deleted
+// when the case is done.
+
+use io_utils::{open_maybe_compressed, path_exists};
+use proptest::prelude::*;
+use proptest::sample::{select, Index};
+use serde_json::{Map, Value};
+use std::env;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io::Read;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::sync::OnceLock;
+use string_utils::strme;
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Arguments are generated from these.
+
+const SIMPLE_ARGS: [&str; 24] = [
+    "PLAIN",
+    "HTML",
+    "SVG",
+    "SPLIT",
+    "NCELL",
+    "REPROD",
+    "NGEX",
+    "NOPRINT",
+    "SUMMARY",
+    "TREE",
+    "NEWICK",
+    "CONP",
+    "CONX",
+    "FULL_SEQC",
+    "MIX_DONORS",
+    "BARCODES",
+    "PER_CELL",
+    "CHAINS_EXACT",
+    "CELLRANGER",
+    "STABLE_DOC",
+    "LONG_HELP",
+    "help",
+    "BUILT_IN",
+    "NOPRETTY",
+];
+const KEYS: [&str; 30] = [
+    "PRE",
+    "PREPOST",
+    "SOURCE",
+    "BCR",
+    "TCR",
+    "GEX",
+    "CDR3",
+    "LVARS",
+    "LVARSP",
+    "CVARS",
+    "CVARSP",
+    "PCOLS",
+    "POUT",
+    "PCHAINS",
+    "MIN_CELLS",
+    "MAX_CELLS",
+    "MIN_CHAINS",
+    "CHAINS",
+    "AMINO",
+    "BARCODE",
+    "SEG",
+    "SEGN",
+    "TREE",
+    "GROUP",
+    "MIN_GROUP",
+    "MAX_CORES",
+    "F",
+    "HTML",
+    "PLOT",
+    "HONEY",
+];
+const VALUES: [&str; 30] = [
+    "",
+    "0",
+    "1",
+    "-1",
+    "3.5",
+    "1e9",
+    "x",
+    ",",
+    ":",
+    ";",
+    "=",
+    "\"\"",
+    "~",
+    "..",
+    "/",
+    "gex",
+    "u1,u2",
+    "n,cdr3_aa1",
+    "v_name1,const1,nchains",
+    "CAR.*",
+    "stdout",
+    "stdouth",
+    "IGH",
+    "TRB",
+    "flaky",
+    "flaky6",
+    "1,2;3",
+    "cdr3_len>10",
+    "vj_refname,cdr3_aa_heavy≥90%",
+    "symmetric",
+];
+
+fn value() -> impl Strategy<Value = String> {
+    prop_oneof![
+        select(&VALUES[..]).prop_map(str::to_string),
+        "[0-9]{1,3}",
+        "[a-z_0-9]{1,8}",
+        "[ -~]{0,6}",
+    ]
+}
+
+fn arg() -> impl Strategy<Value = String> {
+    prop_oneof![
+        select(&SIMPLE_ARGS[..]).prop_map(str::to_string),
+        (select(&KEYS[..]), value()).prop_map(|(k, v)| format!("{}={}", k, v)),
+        "[A-Z_]{1,8}(=[ -~]{0,4})?",
+    ]
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+fn cases() -> u32 {
+    env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(32)
+}
+
+fn inputs() -> String {
+    format!("{}/testx/inputs", env!("CARGO_MANIFEST_DIR"))
+}
+
+// A new directory to run enclone in, which is deleted when the case is done.
+
+struct WorkDir(String);
+
+impl WorkDir {
+    fn new() -> WorkDir {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = format!(
+            "{}/enclone_test_prop/{}_{}",
+            env::temp_dir().display(),
+            std::process::id(),
+            COUNT.fetch_add(1, SeqCst)
+        );
+        create_dir_all(&dir).unwrap();
+        WorkDir(dir)
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
+
+// Run enclone, and check that it exits with status zero or one.
+
+fn run_enclone(args: &[String], dir: &str) -> Result<(), TestCaseError> {
+    let mut argsx = args.to_vec();
+    argsx.push("NOPAGER".to_string());
+    argsx.push("NO_KILL".to_string());
+    let o = Command::new(env!("CARGO_BIN_EXE_enclone"))
+        .args(&argsx)
+        .current_dir(dir)
+        .output()
+        .unwrap_or_else(|_| panic!("{}", "failed to execute enclone".to_string()));
+    let status = o.status.code();
+    prop_assert!(
+        status == Some(0) || status == Some(1),
+        "\nenclone {} exited with status {:?}, stderr =\n{}",
+        argsx.join(" "),
+        status,
+        strme(&o.stderr)
+    );
+    Ok(())
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// The contigs of a test dataset.
+
+fn contigs(name: &str) -> Vec<Value> {
+    let mut json = format!("{}/{}/outs/all_contig_annotations.json", inputs(), name);
+    if !path_exists(&json) {
+        json = format!("{}.lz4", json);
+    }
+    let mut contents = String::new();
+    open_maybe_compressed(&json)
+        .read_to_string(&mut contents)
+        .unwrap();
+    serde_json::from_str::<Vec<Value>>(&contents).unwrap()
+}
+
+// An edit of a contig, given by a kind, and what it is applied to.  The edits keep the contigs
+// well-formed, in that every field keeps its type.
+
+#[derive(Clone, Debug)]
+struct Edit {
+    contig: Index,
+    kind: u8,
+    other: Index,
+    value: u32,
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    (any::<Index>(), 0..6_u8, any::<Index>(), 0..1000_u32).prop_map(
+        |(contig, kind, other, value)| Edit {
+            contig,
+            kind,
+            other,
+            value,
+        },
+    )
+}
+
+// Truncate a contig to a given length, and remove the features that no longer fit in it, so
+// that no position in the contig is past its end.
+
+fn truncate_contig(c: &mut Map<String, Value>, n: usize) {
+    let n = c
+        .get("sequence")
+        .and_then(Value::as_str)
+        .map_or(n, |s| n.min(s.len()));
+    for f in ["sequence", "quals"] {
+        if let Some(Value::String(s)) = c.get(f) {
+            let s = s[..n.min(s.len())].to_string();
+            c.insert(f.to_string(), Value::String(s));
+        }
+    }
+    let past = |x: Option<&Value>, extra: usize| {
+        x.and_then(Value::as_u64)
+            .is_some_and(|p| p as usize + extra > n)
+    };
+    if let Some(Value::Array(anns)) = c.get_mut("annotations") {
+        anns.retain(|a| !past(a.get("contig_match_end"), 0));
+    }
+    if past(c.get("cdr3_stop"), 0) || past(c.get("cdr3_start"), 0) {
+        for f in ["cdr3", "cdr3_seq", "cdr3_start", "cdr3_stop"] {
+            c.insert(f.to_string(), Value::Null);
+        }
+    }
+    for f in ["fwr1", "cdr1", "fwr2", "cdr2", "fwr3", "fwr4"] {
+        if past(c.get(f).and_then(|r| r.get("stop")), 0) {
+            c.insert(f.to_string(), Value::Null);
+        }
+    }
+    for f in ["start_codon_pos", "stop_codon_pos"] {
+        if past(c.get(f), 3) {
+            c.insert(f.to_string(), Value::Null);
+        }
+    }
+}
+
+fn apply_edit(contigs: &mut [Value], e: &Edit) {
+    let i = e.contig.index(contigs.len());
+    let other = contigs[e.other.index(contigs.len())].clone();
+    let c = contigs[i].as_object_mut().unwrap();
+    let pick = |x: &[&'static str]| x[e.value as usize % x.len()];
+    match e.kind {
+        // Null out an optional field.
+        0 => {
+            let f = pick(&["cdr3", "cdr3_seq", "cdr3_start", "cdr3_stop"]);
+            c.insert(f.to_string(), Value::Null);
+        }
+        // Flip a flag.
+        1 => {
+            let f = pick(&["productive", "is_cell", "high_confidence", "filtered"]);
+            if let Some(Value::Bool(b)) = c.get(f) {
+                let b = !b;
+                c.insert(f.to_string(), Value::Bool(b));
+            }
+        }
+        // Change a count.
+        2 => {
+            let f = pick(&["umi_count", "read_count"]);
+            c.insert(f.to_string(), Value::from(e.value % 20));
+        }
+        // Move the contig to the barcode of another contig.
+        3 => {
+            c.insert("barcode".to_string(), other["barcode"].clone());
+        }
+        // Truncate the contig.
+        4 => truncate_contig(c, e.value as usize),
+        // Remove the annotations.
+        _ => {
+            c.insert("annotations".to_string(), Value::Array(Vec::new()));
+        }
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(cases()))]
+
+    // Run enclone on a test dataset, with generated arguments.
+
+    #[cfg(not(feature = "cpu"))]
+    #[test]
+    fn test_enclone_prop_args(tcr in any::<bool>(), args in prop::collection::vec(arg(), 0..6)) {
+        let dataset = if tcr { "TCR=flaky6" } else { "BCR=flaky" };
+        let mut argsx = vec![dataset.to_string(), format!("PRE={}", inputs())];
+        argsx.extend(args);
+        run_enclone(&argsx, &WorkDir::new().0)?;
+    }
+
+    // Run enclone with generated PRE, PREPOST and SOURCE, where the SOURCE file has generated
+    // arguments.
+
+    #[cfg(not(feature = "cpu"))]
+    #[test]
+    fn test_enclone_prop_source(
+        pre in prop::collection::vec(select(&["", ".", "inputs", "x"][..]), 0..3),
+        prepost in prop::option::of(select(&["", "outs", "..", "x/y"][..])),
+        lines in prop::collection::vec(prop::collection::vec(arg(), 0..4), 0..4),
+        comment in any::<bool>(),
+    ) {
+        let dir = WorkDir::new();
+        let mut source = String::new();
+        if comment {
+            source += "# generated\n";
+        }
+        for line in lines.iter() {
+            source += &format!("{}\n", line.join(" "));
+        }
+        write(format!("{}/source", dir.0), source).unwrap();
+        let pre = pre
+            .iter()
+            .map(|p| if *p == "inputs" { inputs() } else { p.to_string() })
+            .collect::<Vec<_>>();
+        let mut args = vec!["BCR=flaky".to_string(), "SOURCE=source".to_string()];
+        if !pre.is_empty() {
+            args.push(format!("PRE={}", pre.join(",")));
+        }
+        if let Some(prepost) = prepost {
+            args.push(format!("PREPOST={}", prepost));
+        }
+        run_enclone(&args, &dir.0)?;
+    }
+
+    // Run enclone on a generated dataset, consisting of some of the contigs of a test dataset,
+    // with edits, and generated arguments.
+
+    #[cfg(not(feature = "cpu"))]
+    #[test]
+    fn test_enclone_prop_json(
+        tcr in any::<bool>(),
+        picks in prop::collection::vec(any::<Index>(), 1..40),
+        edits in prop::collection::vec(edit(), 0..20),
+        args in prop::collection::vec(arg(), 0..3),
+    ) {
+        static BCR: OnceLock<Vec<Value>> = OnceLock::new();
+        static TCR: OnceLock<Vec<Value>> = OnceLock::new();
+        let all = if tcr {
+            TCR.get_or_init(|| contigs("flaky6"))
+        } else {
+            BCR.get_or_init(|| contigs("flaky"))
+        };
+        let mut x = picks.iter().map(|p| p.get(all).clone()).collect::<Vec<_>>();
+        for e in edits.iter() {
+            apply_edit(&mut x, e);
+        }
+        let dir = WorkDir::new();
+        create_dir_all(format!("{}/data/outs", dir.0)).unwrap();
+        write(
+            format!("{}/data/outs/all_contig_annotations.json", dir.0),
+            serde_json::to_string_pretty(&x).unwrap(),
+        )
+        .unwrap();
+        let mut argsx = vec![
+            format!("{}=data", if tcr { "TCR" } else { "BCR" }),
+            "BUILT_IN".to_string(),
+        ];
+        argsx.extend(args);
+        run_enclone(&argsx, &dir.0)?;
+    }
+}
@@ -0,0 +1,459 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute "enclone completions <shell>", which prints a shell completion script for bash, zsh or
+// fish.  The script completes argument names, variable names inside the lists given to LVARS,
+// LVARSP, CVARS, CVARSP, PCOLS and GVARS, the values of arguments that take one of a fixed set
+// of values, file paths for arguments that take a file, and dataset ids for BCR, TCR, GEX,
+// BCR_GEX and TCR_GEX, by listing the directories under PRE.
+//
+// Everything except file paths and dataset ids is computed now, from the help catalog (see
+// help_catalog.rs), and written into the script, so the script should be regenerated when
+// enclone is updated.  The PRE directories are also written into the script, so they are those
+// in effect when it is generated: the default directories under ~/enclone, replaced by PRE if
+// given, or by ENCLONE_PRE, and then extended by PREPOST.  PRE and PREPOST may be given after the
+// shell, e.g. "enclone completions bash PRE=/mnt/data".
+//
+// To install:
+// bash: enclone completions bash > ~/.local/share/bash-completion/completions/enclone
+// zsh:  enclone completions zsh > ~/.zfunc/_enclone, with ~/.zfunc in fpath before compinit
+// fish: enclone completions fish > ~/.config/fish/completions/enclone.fish
+
+use crate::help_catalog::catalog;
+use enclone_core::defs::EncloneControl;
+
+// Arguments whose values are comma-separated lists of variables, and the lists of variables they
+// accept.
+
+const VAR_LISTS: [(&str, &[&str]); 6] = [
+    ("LVARS", &["lvars"]),
+    ("LVARSP", &["lvars"]),
+    ("CVARS", &["cvars"]),
+    ("CVARSP", &["cvars"]),
+    ("PCOLS", &["lvars", "cvars", "pvars"]),
+    ("GVARS", &["gvars"]),
+];
+
+// Arguments whose values are dataset ids, found under the PRE directories.
+
+const DATASET_ARGS: [&str; 5] = ["BCR", "TCR", "GEX", "BCR_GEX", "TCR_GEX"];
+
+// Everything that goes into a completion script.
+
+#[derive(Default)]
+struct Completions {
+    // arguments that take a value, without the =
+    opts: Vec<String>,
+    // arguments that do not take a value
+    flags: Vec<String>,
+    // variables, by kind
+    lvars: Vec<String>,
+    cvars: Vec<String>,
+    pvars: Vec<String>,
+    gvars: Vec<String>,
+    // arguments that take a file path
+    paths: Vec<String>,
+    // arguments that take one of a fixed set of values, and those values
+    enums: Vec<(String, Vec<String>)>,
+    pre: Vec<String>,
+}
+
+// Return true if a documented name can be completed as is, rather than being a pattern like
+// g<d> or a description.
+
+fn literal(x: &str) -> bool {
+    !x.is_empty() && !x.contains(|c: char| "<>{}[]() ,;'\"\\$`".contains(c))
+}
+
+fn sort_dedup(x: &mut Vec<String>) {
+    x.sort();
+    x.dedup();
+}
+
+fn gather(ctl: &EncloneControl) -> Result<Completions, String> {
+    let mut c = Completions::default();
+    for e in catalog(ctl)?.into_iter() {
+        if !literal(&e.name) {
+            continue;
+        }
+        match e.kind.as_str() {
+            "argument" => {
+                if e.syntax.iter().any(|x| x.contains('=')) {
+                    c.opts.push(e.name.clone());
+                }
+                if e.syntax.iter().any(|x| !x.contains('=')) {
+                    c.flags.push(e.name.clone());
+                }
+                let special = VAR_LISTS.iter().any(|x| x.0 == e.name)
+                    || DATASET_ARGS.contains(&e.name.as_str());
+                if special {
+                    continue;
+                }
+                if e.value_type == "path" {
+                    c.paths.push(e.name.clone());
+                }
+                let values = e
+                    .allowed_values
+                    .iter()
+                    .filter(|x| literal(x))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !values.is_empty() {
+                    c.enums.push((e.name.clone(), values));
+                }
+            }
+            "lvar" => c.lvars.push(e.name),
+            "cvar" => c.cvars.push(e.name),
+            "parseable" => c.pvars.push(e.name),
+            "gvar" => c.gvars.push(e.name),
+            _ => {}
+        }
+    }
+    for x in [
+        &mut c.opts,
+        &mut c.flags,
+        &mut c.lvars,
+        &mut c.cvars,
+        &mut c.pvars,
+        &mut c.gvars,
+        &mut c.paths,
+    ] {
+        sort_dedup(x);
+    }
+    c.enums.sort();
+    c.enums.dedup_by(|a, b| a.0 == b.0);
+    c.pre = ctl.gen_opt.pre.clone();
+    Ok(c)
+}
+
+// Quote a word for bash or zsh, and for fish.
+
+fn quote(x: &str) -> String {
+    format!("'{}'", x.replace('\'', "'\\''"))
+}
+
+fn quote_fish(x: &str) -> String {
+    format!("'{}'", x.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn quote_all(x: &[String], fish: bool) -> String {
+    x.iter()
+        .map(|x| if fish { quote_fish(x) } else { quote(x) })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Return a case pattern matching any of the given keys, using the separator for the shell.
+// An empty list gives a pattern that matches no argument.
+
+fn pattern<S: AsRef<str>>(keys: &[S], sep: &str) -> String {
+    if keys.is_empty() {
+        return "_".to_string();
+    }
+    keys.iter()
+        .map(|x| x.as_ref())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+// Return the arrays, as (name, values).
+
+fn arrays(c: &Completions) -> Vec<(&'static str, &Vec<String>)> {
+    vec![
+        ("opts", &c.opts),
+        ("flags", &c.flags),
+        ("lvars", &c.lvars),
+        ("cvars", &c.cvars),
+        ("pvars", &c.pvars),
+        ("gvars", &c.gvars),
+        ("pre", &c.pre),
+    ]
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Bash splits words at = and :, so the completion is computed for the whole word, as found in
+// COMP_LINE, and then the part before the current bash word is removed.
+
+const BASH: &str = r#"# bash completion for enclone, generated by "enclone completions bash"
+
+@ARRAYS@
+_enclone_values() {
+    case "$1" in
+@VALUES@
+    esac
+}
+
+_enclone() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local word="${line##*[[:space:]]}"
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    [[ "$cur" == "=" || "$cur" == ":" ]] && cur=""
+    local -a cands=()
+    local x
+    if [[ "$word" == *=* ]]; then
+        local key="${word%%=*}" val="${word#*=}" item head d
+        case "$key" in
+            @LISTS@)
+                item="${val##*,}"
+                head="${val%"$item"}"
+                while IFS= read -r x; do
+                    [[ "$x" == "$item"* ]] && cands+=("$key=$head$x")
+                done < <(_enclone_values "$key")
+                compopt -o nospace 2>/dev/null ;;
+            @DATASETS@)
+                item="${val##*[,:;]}"
+                head="${val%"$item"}"
+                for d in "${_enclone_pre[@]}"; do
+                    for x in "$d/$item"*; do
+                        [[ -d "$x" ]] && cands+=("$key=$head${x##*/}")
+                    done
+                done
+                compopt -o nospace 2>/dev/null ;;
+            @PATHS@)
+                while IFS= read -r x; do
+                    cands+=("$key=$x")
+                done < <(compgen -f -- "$val")
+                compopt -o nospace 2>/dev/null ;;
+            *)
+                while IFS= read -r x; do
+                    [[ "$x" == "$val"* ]] && cands+=("$key=$x")
+                done < <(_enclone_values "$key") ;;
+        esac
+    else
+        while IFS= read -r x; do
+            cands+=("$x")
+        done < <(compgen -W "${_enclone_opts[*]/%/=} ${_enclone_flags[*]}" -- "$word")
+        [[ ${#cands[@]} -eq 1 && "${cands[0]}" == *= ]] && compopt -o nospace 2>/dev/null
+    fi
+    local n=$(( ${#word} - ${#cur} ))
+    COMPREPLY=()
+    for x in "${cands[@]}"; do
+        COMPREPLY+=("${x:n}")
+    done
+}
+
+complete -F _enclone enclone
+"#;
+
+fn bash(c: &Completions) -> String {
+    let mut arrays_text = String::new();
+    for (name, values) in arrays(c) {
+        arrays_text += &format!("_enclone_{}=({})\n", name, quote_all(values, false));
+    }
+    let mut values = Vec::<String>::new();
+    for (key, lists) in VAR_LISTS.iter() {
+        let lists = lists
+            .iter()
+            .map(|x| format!("\"${{_enclone_{}[@]}}\"", x))
+            .collect::<Vec<_>>();
+        values.push(format!(
+            "        {}) printf '%s\\n' {} ;;",
+            key,
+            lists.join(" ")
+        ));
+    }
+    for (key, vals) in c.enums.iter() {
+        values.push(format!(
+            "        {}) printf '%s\\n' {} ;;",
+            key,
+            quote_all(vals, false)
+        ));
+    }
+    let lists = VAR_LISTS.iter().map(|x| x.0).collect::<Vec<_>>();
+    BASH.replace("@ARRAYS@", &arrays_text)
+        .replace("@VALUES@", &values.join("\n"))
+        .replace("@LISTS@", &pattern(&lists, "|"))
+        .replace("@DATASETS@", &pattern(&DATASET_ARGS, "|"))
+        .replace("@PATHS@", &pattern(&c.paths, "|"))
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// The zsh script works both when autoloaded from fpath, and when sourced.
+
+const ZSH: &str = r#"#compdef enclone
+# zsh completion for enclone, generated by "enclone completions zsh"
+
+@ARRAYS@
+_enclone() {
+    local key d
+    local -a ids
+    if [[ $PREFIX == *=* ]]; then
+        key=${PREFIX%%=*}
+        compset -P 1 '*='
+        case $key in
+@VALUES@
+            @DATASETS@)
+                compset -P '*[,:;]'
+                for d in $_enclone_pre; do
+                    ids+=($d/*(N/:t))
+                done
+                compadd -S '' -a ids ;;
+            @PATHS@)
+                _files ;;
+        esac
+    else
+        compadd -S '=' -a _enclone_opts
+        compadd -a _enclone_flags
+    fi
+}
+
+if [[ $zsh_eval_context[-1] == loadautofunc ]]; then
+    _enclone "$@"
+else
+    compdef _enclone enclone
+fi
+"#;
+
+fn zsh(c: &Completions) -> String {
+    let mut arrays_text = String::new();
+    for (name, values) in arrays(c) {
+        arrays_text += &format!("_enclone_{}=({})\n", name, quote_all(values, false));
+    }
+    let mut values = Vec::<String>::new();
+    for (key, lists) in VAR_LISTS.iter() {
+        let lists = lists
+            .iter()
+            .map(|x| format!("_enclone_{}", x))
+            .collect::<Vec<_>>();
+        values.push(format!(
+            "            {})\n                compset -P '*,'\n                \
+             compadd -S '' -a {} ;;",
+            key,
+            lists.join(" ")
+        ));
+    }
+    for (key, vals) in c.enums.iter() {
+        values.push(format!(
+            "            {})\n                compadd -- {} ;;",
+            key,
+            quote_all(vals, false)
+        ));
+    }
+    ZSH.replace("@ARRAYS@", &arrays_text)
+        .replace("@VALUES@", &values.join("\n"))
+        .replace("@DATASETS@", &pattern(&DATASET_ARGS, "|"))
+        .replace("@PATHS@", &pattern(&c.paths, "|"))
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+const FISH: &str = r#"# fish completion for enclone, generated by "enclone completions fish"
+
+@ARRAYS@
+function __enclone_complete
+    set -l word (commandline -ct)
+    if not string match -q -- '*=*' "$word"
+        for x in $__enclone_opts
+            echo "$x="
+        end
+        printf '%s\n' $__enclone_flags
+        return
+    end
+    set -l key (string split -m 1 -- = "$word")[1]
+    set -l val (string split -m 1 -- = "$word")[2]
+    switch $key
+@VALUES@
+        case @DATASETS@
+            set -l head (string replace -r -- '[^,:;]*$' '' "$val")
+            for d in $__enclone_pre
+                for x in $d/*/
+                    echo "$key=$head"(basename "$x")
+                end
+            end
+        case @PATHS@
+            for x in (__fish_complete_path "$val")
+                echo "$key=$x"
+            end
+    end
+end
+
+complete -c enclone -f -a '(__enclone_complete)'
+"#;
+
+fn fish(c: &Completions) -> String {
+    let mut arrays_text = String::new();
+    for (name, values) in arrays(c) {
+        arrays_text += &format!("set -g __enclone_{} {}\n", name, quote_all(values, true));
+    }
+    let mut values = Vec::<String>::new();
+    for (key, lists) in VAR_LISTS.iter() {
+        let lists = lists
+            .iter()
+            .map(|x| format!("$__enclone_{}", x))
+            .collect::<Vec<_>>();
+        values.push(format!(
+            "        case {}\n            \
+             set -l head (string replace -r -- '[^,]*$' '' \"$val\")\n            \
+             for x in {}\n                echo \"$key=$head$x\"\n            end",
+            key,
+            lists.join(" ")
+        ));
+    }
+    for (key, vals) in c.enums.iter() {
+        values.push(format!(
+            "        case {}\n            for x in {}\n                \
+             echo \"$key=$x\"\n            end",
+            key,
+            quote_all(vals, true)
+        ));
+    }
+    FISH.replace("@ARRAYS@", &arrays_text)
+        .replace("@VALUES@", &values.join("\n"))
+        .replace("@DATASETS@", &pattern(&DATASET_ARGS, " "))
+        .replace("@PATHS@", &pattern(&c.paths, " "))
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Return the completion script for a shell.
+
+pub fn completions(shell: &str, ctl: &EncloneControl) -> Result<String, String> {
+    let make = match shell {
+        "bash" => bash,
+        "zsh" => zsh,
+        "fish" => fish,
+        _ => {
+            return Err(format!(
+                "\nUnknown shell {}.  The shell for enclone completions must be bash, zsh \
+                 or fish.\n",
+                shell
+            ))
+        }
+    };
+    Ok(make(&gather(ctl)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completions() {
+        assert!(literal("cdr3_aa"));
+        assert!(literal("PLOT_GENE_BY"));
+        assert!(!literal("g<d>"));
+        assert!(!literal("x1,...,xn"));
+        assert_eq!(quote("it's"), "'it'\\''s'");
+        assert_eq!(quote_fish("it's"), "'it\\'s'");
+        assert_eq!(pattern(&["BCR", "TCR"], "|"), "BCR|TCR");
+        assert_eq!(pattern::<&str>(&[], "|"), "_");
+        let c = Completions {
+            opts: vec!["LVARS".to_string(), "POUT".to_string()],
+            flags: vec!["PLAIN".to_string()],
+            lvars: vec!["u".to_string()],
+            paths: vec!["POUT".to_string()],
+            enums: vec![("PLOT_LAYOUT".to_string(), vec!["random".to_string()])],
+            pre: vec!["/mnt/pre".to_string()],
+            ..Default::default()
+        };
+        for script in [bash(&c), zsh(&c), fish(&c)] {
+            assert!(!script.contains("@VALUES@") && !script.contains("@PATHS@"));
+            assert!(script.contains("PLOT_LAYOUT"));
+            assert!(script.contains("'random'"));
+            assert!(script.contains("pre '/mnt/pre'") || script.contains("pre=('/mnt/pre')"));
+        }
+        assert!(bash(&c).contains("            POUT)\n"));
+        assert!(fish(&c).contains("        case POUT\n"));
+    }
+}
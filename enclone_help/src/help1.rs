@@ -2,6 +2,7 @@
 
 // Test for help request.
 
+use crate::help_registry::ArgType;
 use crate::help_utils::HelpDesk;
 
 pub fn help1(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
@@ -75,6 +76,14 @@ pub fn help1(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
             "how enclone uses color, and related things",
         );
         h.doc("enclone help faq", "frequently asked questions (long)");
+        h.ldoc(
+            "enclone help search X",
+            "list arguments and variables matching the regular expression X",
+        );
+        h.ldoc(
+            "enclone completions X",
+            "print a completion script for the shell X = bash, zsh or fish",
+        );
         h.ldoc_greenish(
             "enclone help all",
             "concatenation of all the help pages (long, wide)",
@@ -230,6 +239,20 @@ pub fn help1(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
             "To address these challenges, the enclone algorithm has several steps, which we \
              outline:\n\n",
         )?;
+        h.declare_args(&[
+            ("MULT_POW", ArgType::Number, "80"),
+            ("CDR3_NORMAL_LEN", ArgType::Integer, "42"),
+            ("JOIN_CDR3_IDENT", ArgType::Number, "85"),
+            ("MAX_SCORE", ArgType::Number, "100000"),
+            ("MAX_LOG_SCORE", ArgType::Number, "5"),
+            ("AUTO_SHARES", ArgType::Integer, "15"),
+            ("JALIGN1", ArgType::Flag, ""),
+            ("MAX_DEGRADATION", ArgType::Integer, "2"),
+            ("EASY", ArgType::Flag, ""),
+            ("OLD_LIGHT", ArgType::Flag, ""),
+            ("CDR3_MULT", ArgType::Number, "5"),
+            ("JOIN_BASIC", ArgType::Number, ""),
+        ]);
         h.print(
             "\\boldred{1}.  Input data.  \
              enclone gets its information from the file all_contig_annotations.json that is \
@@ -385,6 +408,12 @@ pub fn help1(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
 
     if (args.len() == 3 && args[1] == "help" && args[2] == "command") || h.help_all {
         h.begin_doc("command")?;
+        h.declare_args(&[
+            ("ENCLONE_PRE", ArgType::List, ""),
+            ("SOURCE", ArgType::Path, ""),
+            ("PLAIN", ArgType::Flag, ""),
+            ("NOPAGER", ArgType::Flag, ""),
+        ]);
         h.print("\n")?;
         h.print("\\bold{information about enclone command-line argument processing}\n\n")?;
         h.print("\\bold{1. Order of processing}\n\n")?;
@@ -2,20 +2,13 @@
 
 // Test for help request.
 
+use crate::help_examples::HELP_EXAMPLES;
+use crate::help_registry::ArgType;
 use crate::help_utils::{explain_alt_versions, HelpDesk};
 use enclone_core::defs::EncloneControl;
 use itertools::Itertools;
 use string_utils::strme;
 
-// List of examples in documentation.
-
-pub const EXAMPLES: [&str; 2] = [
-    // 1.
-    r###"BCR=123089 CDR3=CARRYFGVVADAFDIW"###,
-    // 2.
-    r###"BCR=123085 GEX=123217 LVARSP=gex,IGHV2-5_g_μ CDR3=CALMGTYCSGDNCYSWFDPW"###,
-];
-
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
 pub fn help2(args: &Vec<String>, _ctl: &EncloneControl, h: &mut HelpDesk) -> Result<(), String> {
@@ -28,11 +21,11 @@ pub fn help2(args: &Vec<String>, _ctl: &EncloneControl, h: &mut HelpDesk) -> Res
     if (args.len() == 3 && args[1] == "help" && args[2] == "example1") || h.help_all {
         h.begin_doc("example1")?;
         h.print("\nShown below is the output of the command:\n")?;
-        h.print(&format!("\n\\bold{{enclone {}}}\n", EXAMPLES[0]))?;
+        h.print(&format!("\n\\bold{{enclone {}}}\n", HELP_EXAMPLES[0].args))?;
         if !h.plain {
-            h.print_plain(include_str!("example1"))?;
+            h.print_plain(HELP_EXAMPLES[0].output().unwrap())?;
         } else {
-            let s = include_str!("example1").as_bytes();
+            let s = HELP_EXAMPLES[0].output().unwrap().as_bytes();
             let mut x = Vec::<u8>::new();
             let mut escaped = false;
             for l in 0..s.len() {
@@ -95,7 +88,7 @@ pub fn help2(args: &Vec<String>, _ctl: &EncloneControl, h: &mut HelpDesk) -> Res
         h.begin_doc("example2")?;
         h.print("\nShown below is the output of the command:\n")?;
 
-        let ex2_args = EXAMPLES[1].split(' ').collect::<Vec<&str>>();
+        let ex2_args = HELP_EXAMPLES[1].args.split(' ').collect::<Vec<&str>>();
 
         // Proceed.
 
@@ -104,9 +97,9 @@ pub fn help2(args: &Vec<String>, _ctl: &EncloneControl, h: &mut HelpDesk) -> Res
             ex2_args.iter().format(" ")
         ))?;
         if !h.plain {
-            h.print_plain_unchecked(include_str!("example2"));
+            h.print_plain_unchecked(HELP_EXAMPLES[1].output().unwrap());
         } else {
-            let s = include_str!("example2").as_bytes();
+            let s = HELP_EXAMPLES[1].output().unwrap().as_bytes();
             let mut x = Vec::<u8>::new();
             let mut escaped = false;
             for l in 0..s.len() {
@@ -149,6 +142,26 @@ pub fn help2(args: &Vec<String>, _ctl: &EncloneControl, h: &mut HelpDesk) -> Res
              the particular files that are needed may be found by typing \
              \\bold{enclone help input_tech}.\n\n",
         )?;
+        h.declare_args(&[
+            (
+                "PRE",
+                ArgType::List,
+                "~/enclone/datasets_me,~/enclone/datasets,~/enclone/datasets2",
+            ),
+            ("PREPOST", ArgType::String, ""),
+            ("TCR", ArgType::List, ""),
+            ("BCR", ArgType::List, ""),
+            ("GEX", ArgType::List, ""),
+            ("BCR_GEX", ArgType::List, ""),
+            ("TCR_GEX", ArgType::List, ""),
+            ("BC", ArgType::List, ""),
+            ("BC_JOINT", ArgType::Path, ""),
+            ("META", ArgType::Path, ""),
+            ("METAX", ArgType::String, ""),
+            ("INFO", ArgType::Path, ""),
+            ("INFO_RESOLVE", ArgType::Flag, ""),
+            ("PLOT", ArgType::String, ""),
+        ]);
         h.print_with_box(
             "If you use the argument \\bold{PRE=p} then \\bold{p/} will be prepended to all \
              pipeline paths.  A comma-separated list is also allowed \\bold{PRE=p1,...,pn}, in \
@@ -597,6 +610,11 @@ pub fn help2(args: &Vec<String>, _ctl: &EncloneControl, h: &mut HelpDesk) -> Res
         h.print_tab2()?;
         h.print("\n")?;
         explain_alt_versions(h)?;
+        h.declare_args(&[
+            ("CVARS", ArgType::List, "u,const,notes"),
+            ("CVARSP", ArgType::List, ""),
+            ("JC1", ArgType::Flag, ""),
+        ]);
         h.print(
             "\nAt least one variable must be listed.  The default is \\bold{u,const,notes}.  \
              \\bold{CVARSP}: same as \\bold{CVARS} but appends.\n\n",
@@ -2,7 +2,9 @@
 //
 // Test for help request, under development.
 
+use crate::help_registry::{ArgDoc, ArgType};
 use crate::help_utils::HelpDesk;
+use crate::tail_args::ANNDATA_ARGS;
 use tables::print_tabular_vbox;
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
@@ -72,6 +74,16 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
              Parseable output is targeted primarily at R and Python users, because of the ease of \
              wrangling CSV files with these languages.\n\n",
         )?;
+        h.declare_args(&[
+            ("POUT", ArgType::Path, ""),
+            ("PCHAINS", ArgType::String, ""),
+            ("PCOLS", ArgType::List, ""),
+            ("PNO_HEADER", ArgType::Flag, ""),
+            ("PCELL", ArgType::Flag, ""),
+            ("NOPRINT", ArgType::Flag, ""),
+            ("FASTA", ArgType::Path, ""),
+            ("FASTA_AA", ArgType::Path, ""),
+        ]);
         h.print_with_box(
             "Parseable output is invoked by using the argument\n\
              \\bold{POUT=filename}\n\
@@ -126,6 +138,21 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
              FASTA file.",
             true,
         )?;
+        h.print(
+            "\\bold{AnnData output.}  This is also a separate feature.  It writes a table having \
+             one row for each cell, containing the clonotype group, clonotype and exact \
+             subclonotype ids of the cell, and the values of chosen variables for it.  The table \
+             is written as a CSV file, and as the obs of an AnnData file, which may be merged \
+             onto the obs of an AnnData object having the same barcodes.  If there is more than \
+             one dataset, the table is indexed by barcode-dataset, rather than by barcode.  All \
+             values are written as strings.\n\n",
+        )?;
+        h.rows.clear();
+        for a in ANNDATA_ARGS.iter() {
+            h.doc_arg(a);
+        }
+        h.print_tab2()?;
+        h.print("\n")?;
         h.print(
             "\\boldred{───────────────────────}\n\
              \\boldred{parseable output fields}\n\
@@ -272,50 +299,69 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
 
         // doc *CELLS
 
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "MIN_CELLS=n",
+            ArgType::Integer,
             "only show clonotypes having at least n cells",
-        );
-        h.doc("MAX_CELLS=n", "only show clonotypes having at most n cells");
-        h.doc("CELLS=n", "only show clonotypes having exactly n cells");
+        ));
+        h.doc_arg(&ArgDoc::new(
+            "MAX_CELLS=n",
+            ArgType::Integer,
+            "only show clonotypes having at most n cells",
+        ));
+        h.doc_arg(&ArgDoc::new(
+            "CELLS=n",
+            ArgType::Integer,
+            "only show clonotypes having exactly n cells",
+        ));
 
         // doc MIN_UMIS
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MIN_UMIS=n",
+            ArgType::Integer,
             "only show clonotypes having ≳ n UMIs on some chain on some cell",
-        );
+        ));
 
         // doc *CHAINS
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MIN_CHAINS=n",
+            ArgType::Integer,
             "only show clonotypes having at least n chains",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "MAX_CHAINS=n",
+            ArgType::Integer,
             "only show clonotypes having at most n chains",
-        );
-        h.doc("CHAINS=n", "only show clonotypes having exactly n chains");
+        ));
+        h.doc_arg(&ArgDoc::new(
+            "CHAINS=n",
+            ArgType::Integer,
+            "only show clonotypes having exactly n chains",
+        ));
 
         // doc CDR3
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "CDR3=<pattern>",
+            ArgType::String,
             "only show clonotypes having a CDR3 amino acid seq that matches",
-        );
+        ));
         h.doc("", "the given pattern*, from beginning to end");
 
         // doc SEG and SEGN
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "SEG=\"s_1|...|s_n\"",
+            ArgType::List,
             "only show clonotypes using one of the given reference segment names",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "SEGN=\"s_1|...|s_n\"",
+            ArgType::List,
             "only show clonotypes using one of the given reference segment numbers",
-        );
+        ));
         h.doc2("both: looks for V, D, J and C segments; double quote only");
         h.doc2("needed if n > 1");
         h.doc(
@@ -326,90 +372,107 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
 
         // doc NSEG and NSEGN
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "NSEG=\"s_1|...|s_n\"",
+            ArgType::List,
             "do not show clonotypes using one of the given reference segment names",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "NSEGN=\"s_1|...|s_n\"",
+            ArgType::List,
             "do not show clonotypes using one of the given reference segment numbers",
-        );
+        ));
         h.doc2("Otherwise similar to SEG and SEGN.");
 
         // doc MAX_EXACTS and MIN_EXACTS
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MAX_EXACTS=n",
+            ArgType::Integer,
             "only show clonotypes having at most n exact subclonotypes",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "MIN_EXACTS=n",
+            ArgType::Integer,
             "only show clonotypes having at least n exact subclonotypes",
-        );
+        ));
 
         // doc VJ
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "VJ=seq",
+            ArgType::String,
             "only show clonotypes using exactly the given V..J sequence",
-        );
+        ));
         h.doc2("(string in alphabet ACGT)");
 
         // doc MIN_DATASETS and MAX_DATASETS and MIN_DATASET_RATIO and DATASET
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MIN_DATASETS=n",
+            ArgType::Integer,
             "only show clonotypes containing cells from at least n datasets",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "MAX_DATASETS=n",
+            ArgType::Integer,
             "only show clonotypes containing cells from at most n datasets",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "MIN_DATASET_RATIO=n",
+            ArgType::Number,
             "only show clonotypes having at least n cells and for which the ratio",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "DATASET=\"d1|...|dn\"",
+            ArgType::List,
             "only show clonotypes having at least one of the listed datasets",
-        );
+        ));
         h.doc2("of the number of cells in the must abundant dataset to the next most");
         h.doc2("abundant one is at least n");
 
         // doc MIN_ORIGINS
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MIN_ORIGINS=n",
+            ArgType::Integer,
             "only show clonotypes containing cells from at least n origins",
-        );
+        ));
 
         // doc MIN_DONORS
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MIN_DONORS=n",
+            ArgType::Integer,
             "only show clonotypes containing cells from at least n donors",
-        );
+        ));
         h.doc2("If n ≥ 2, this automatically turns on MIX_DONORS, as otherwise cells from");
         h.doc2("two or more donors would not be combined into the same clonotype.");
 
         // doc CDIFF
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "CDIFF",
+            ArgType::Flag,
             "only show clonotypes having a difference in constant region with the",
-        );
+        ));
         h.doc2("universal reference");
 
         // doc DEL
 
-        h.ldoc("DEL", "only show clonotypes exhibiting a deletion");
+        h.ldoc_arg(&ArgDoc::new(
+            "DEL",
+            ArgType::Flag,
+            "only show clonotypes exhibiting a deletion",
+        ));
 
         // doc BARCODE
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "BARCODE=bc1,...,bcn",
+            ArgType::List,
             "only show clonotypes that use one of the given barcodes; note that such",
-        );
+        ));
         h.docpr(
             "",
             "clonotypes will typically contain cells that are \\bold{not} in your",
@@ -421,31 +484,39 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
         );
         // doc INKT and MAIT
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "INKT",
+            ArgType::Flag,
             "only show clonotypes for which some exact subclonotype is annotated as",
-        );
+        ));
         h.docpr(
             "",
             "having some iNKT evidence, see \\green{bit.ly/enclone} for details",
         );
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "MAIT",
+            ArgType::Flag,
             "only show clonotypes for which some exact subclonotype is annotated as",
-        );
+        ));
         h.docpr(
             "",
             "having some MAIT evidence, see \\green{bit.ly/enclone} for details",
         );
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "D_INCONSISTENT",
+            ArgType::Flag,
             "only show clonotypes having an inconsistent assignment of D genes",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "D_NONE",
+            ArgType::Flag,
             "only show clonotypes having a null D gene assignment",
-        );
-        h.doc("D_SECOND", "only show VDDJ clonotypes");
+        ));
+        h.doc_arg(&ArgDoc::new(
+            "D_SECOND",
+            ArgType::Flag,
+            "only show VDDJ clonotypes",
+        ));
 
         // print main table
 
@@ -538,6 +609,11 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
 
         // bounds
 
+        h.declare_args(&[
+            ("KEEP_CLONO_IF_CELL_MEAN", ArgType::String, ""),
+            ("KEEP_CLONO_IF_CELL_MIN", ArgType::String, ""),
+            ("KEEP_CLONO_IF_CELL_MAX", ArgType::String, ""),
+        ]);
         h.print(
             "\\bold{filtering by linear conditions}\n\n\
              enclone has the capability to filter by bounding variables, using \
@@ -577,6 +653,10 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
 
         // feature scanning
 
+        h.declare_args(&[
+            ("SCAN", ArgType::String, ""),
+            ("SCAN_EXACT", ArgType::Flag, ""),
+        ]);
         h.print(
             "\\bold{feature scanning}\n\n\
             If gene expression and/or feature barcode data have been generated, \
@@ -627,6 +707,7 @@ pub fn help3(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
 
     if (args.len() == 3 && args[1] == "help" && args[2] == "amino") || h.help_all {
         h.begin_doc("amino")?;
+        h.declare_args(&[("AMINO", ArgType::List, "cdr3,var,share,donor")]);
         h.print(
             "\nThere is a complex per-chain column to the left of other \
              per-chain columns, defined by\n\
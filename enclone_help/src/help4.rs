@@ -2,6 +2,7 @@
 //
 // Test for help request, under development.
 
+use crate::help_registry::{ArgDoc, ArgType};
 use crate::help_utils::{explain_alt_versions, HelpDesk};
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
@@ -23,67 +24,84 @@ pub fn help4(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
              that are not noise filters.\n\n",
         )?;
 
-        h.docf2(
-            "NALL",
-            "Turn off all the noise filters shown below.  This may yield quite a mess.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NALL",
+                ArgType::Flag,
+                "Turn off all the noise filters shown below.  This may yield quite a mess.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NCELL",
-            "Use contigs found by Cell Ranger even if they were not in a called cell, \
-            or not called high confidence.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NCELL",
+                ArgType::Flag,
+                "Use contigs found by Cell Ranger even if they were not in a called cell, \
+                or not called high confidence.",
+            ),
             55,
         )?;
 
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "NALL_CELL",
+            ArgType::Flag,
             "Turn off all the noise filters except for the cell filter.",
-        );
+        ));
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "NMAX",
+            ArgType::Flag,
             "Allow barcodes for which more than four contigs were identified.",
-        );
+        ));
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NGEX",
-            "If gene expression and/or feature barcode data are provided, if a barcode \
-            is called a cell by the VDJ part of the Cell Ranger pipeline, but not \
-            called a cell by the gene expression and/or feature barcode part, then the \
-            default behavior of enclone is to remove such cells from clonotypes.  This \
-            option disables that behavior.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NGEX",
+                ArgType::Flag,
+                "If gene expression and/or feature barcode data are provided, if a barcode \
+                is called a cell by the VDJ part of the Cell Ranger pipeline, but not \
+                called a cell by the gene expression and/or feature barcode part, then the \
+                default behavior of enclone is to remove such cells from clonotypes.  This \
+                option disables that behavior.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NCROSS",
-            "If you specify that two or more libraries arose from the same origin (i.e. \
-            cells from the same tube or tissue), then by default enclone will \
-            \"cross filter\" so as to remove expanded exact subclonotypes that are \
-            present in one library but not another, in a fashion that would be highly \
-            improbable, assuming random draws of cells from the tube.  These are \
-            believed to arise when a plasma or plasmablast cell breaks up during during \
-            or after pipetting from the tube, and the resulting fragments seed GEMs, \
-            yielding expanded 'fake' clonotypes that are residues of real single plasma \
-            cells.  The NCROSS options turns off this filter, which could be useful so \
-            long as you interpret the restored clonotypes as representing what are \
-            probably single cells.  There may also be other situations where the filter \
-            should be turned off, and in particular the filter can do weird things if \
-            inputs are somehow mis-specified to enclone.  Note that for purposes of \
-            this option, enclone defines an origin by the pair \
-            (origin name, donor name).",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NCROSS",
+                ArgType::Flag,
+                "If you specify that two or more libraries arose from the same origin (i.e. \
+                cells from the same tube or tissue), then by default enclone will \
+                \"cross filter\" so as to remove expanded exact subclonotypes that are \
+                present in one library but not another, in a fashion that would be highly \
+                improbable, assuming random draws of cells from the tube.  These are \
+                believed to arise when a plasma or plasmablast cell breaks up during during \
+                or after pipetting from the tube, and the resulting fragments seed GEMs, \
+                yielding expanded 'fake' clonotypes that are residues of real single plasma \
+                cells.  The NCROSS options turns off this filter, which could be useful so \
+                long as you interpret the restored clonotypes as representing what are \
+                probably single cells.  There may also be other situations where the filter \
+                should be turned off, and in particular the filter can do weird things if \
+                inputs are somehow mis-specified to enclone.  Note that for purposes of \
+                this option, enclone defines an origin by the pair \
+                (origin name, donor name).",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NUMI",
-            "Filter out B cells based on low BCR UMI counts.  The heuristics",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NUMI",
+                ArgType::Flag,
+                "Filter out B cells based on low BCR UMI counts.  The heuristics",
+            ),
             65,
         )?;
 
@@ -91,10 +109,11 @@ pub fn help4(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
             "",
             "for this are described on the enclone site at \\green{bit.ly/enclone}.",
         );
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "NUMI_RATIO",
+            ArgType::Flag,
             "Filter out B cells based on low BCR UMI counts relative to another",
-        );
+        ));
         h.doc2("cell in a given clonotype.  The heuristics for this");
         h.docpr(
             "",
@@ -102,189 +121,238 @@ pub fn help4(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
         );
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NGRAPH_FILTER",
-            "By default, enclone filters to remove exact subclonotypes that by virtue of \
-            their relationship to other exact subclonotypes, appear to arise from \
-            background mRNA or a phenotypically similar phenomenon.  The \
-            NGRAPH_FILTER option turns off this filtering.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NGRAPH_FILTER",
+                ArgType::Flag,
+                "By default, enclone filters to remove exact subclonotypes that by virtue of \
+                their relationship to other exact subclonotypes, appear to arise from \
+                background mRNA or a phenotypically similar phenomenon.  The \
+                NGRAPH_FILTER option turns off this filtering.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NQUAL",
-            "By default, enclone filters out exact subclonotypes having a base in V..J \
-            that looks like it might be wrong.  More specifically, enclone finds bases \
-            which are not Q60 for a barcode, not Q40 for two barcodes, are not \
-            supported by other exact subclonotypes, are variant within the clonotype, \
-            and which disagree with the donor reference.  NQUAL turns this off.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NQUAL",
+                ArgType::Flag,
+                "By default, enclone filters out exact subclonotypes having a base in V..J \
+                that looks like it might be wrong.  More specifically, enclone finds bases \
+                which are not Q60 for a barcode, not Q40 for two barcodes, are not \
+                supported by other exact subclonotypes, are variant within the clonotype, \
+                and which disagree with the donor reference.  NQUAL turns this off.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NWEAK_CHAINS",
-            "By default, enclone filters chains from clonotypes that are \
-            weak and appear to be artifacts, perhaps arising from a stray mRNA molecule \
-            that floated into a GEM.  The NWEAK_CHAINS option turns off this filter.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NWEAK_CHAINS",
+                ArgType::Flag,
+                "By default, enclone filters chains from clonotypes that are \
+                weak and appear to be artifacts, perhaps arising from a stray mRNA molecule \
+                that floated into a GEM.  The NWEAK_CHAINS option turns off this filter.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NWEAK_ONESIES",
-            "By default, enclone disintegrates certain untrusted clonotypes into single cell \
-            clonotypes.  The untrusted clonotypes are onesies \
-            that are light chain or TRA and whose number of cells is less than 0.1% of the total \
-            number of cells.  This operation reduces the likelihood of creating clonotypes \
-            containing cells that arose from different recombination events.  NWEAK_ONESIES turns \
-            this operation off.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NWEAK_ONESIES",
+                ArgType::Flag,
+                "By default, enclone disintegrates certain untrusted clonotypes into single cell \
+                clonotypes.  The untrusted clonotypes are onesies that are light chain or TRA and \
+                whose number of cells is less than 0.1% of the total number of cells.  This \
+                operation reduces the likelihood of creating clonotypes containing cells that \
+                arose from different recombination events.  NWEAK_ONESIES turns this operation \
+                off.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NMERGE_ONESIES",
-            "enclone merges certain onesie clonotypes into clonotypes having two or more chains.  \
-            By default, this merger is prevented if the number of cells in the onesie is less \
-            than 0.01% of the total number of cells.  NMERGE_ONESIES causes these merges to \
-            happen anyway.  The naming of this option is confusing.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NMERGE_ONESIES",
+                ArgType::Flag,
+                "enclone merges certain onesie clonotypes into clonotypes having two or more \
+                chains.  By default, this merger is prevented if the number of cells in the onesie \
+                is less than 0.01% of the total number of cells.  NMERGE_ONESIES causes these \
+                merges to happen anyway.  The naming of this option is confusing.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NFOURSIE_KILL",
-            "Under certain circumstances, enclone will delete foursie exact subclonotypes.  \
-            Please see 10xgenomics.github.io/enclone/pages/auto/default_filters.html.  \
-            The foursies that are killed are believed to be artifacts arising \
-            from repeated cell doublets or GEMs that contain two cells and multiple gel \
-            beads.  The argument NFOURSIE_KILL turns off this filtering.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NFOURSIE_KILL",
+                ArgType::Flag,
+                "Under certain circumstances, enclone will delete foursie exact subclonotypes.  \
+                Please see 10xgenomics.github.io/enclone/pages/auto/default_filters.html.  \
+                The foursies that are killed are believed to be artifacts arising \
+                from repeated cell doublets or GEMs that contain two cells and multiple gel \
+                beads.  The argument NFOURSIE_KILL turns off this filtering.",
+            ),
             62,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NDOUBLET",
-            "Under certain circumstances, enclone will delete exact subclonotypes that appear \
-            to represent doublets.  \
-            Please see 10xgenomics.github.io/enclone/pages/auto/default_filters.html.  \
-            The argument NDOUBLET turns off this filtering.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NDOUBLET",
+                ArgType::Flag,
+                "Under certain circumstances, enclone will delete exact subclonotypes that appear \
+                to represent doublets.  Please see \
+                10xgenomics.github.io/enclone/pages/auto/default_filters.html.  The argument \
+                NDOUBLET turns off this filtering.",
+            ),
             65,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NSIG",
-            "Under certain circumstances, enclone will delete exact subclonotypes that appear \
-            to be contaminants, based on their chain signature.  \
-            Please see 10xgenomics.github.io/enclone/pages/auto/default_filters.html.  \
-            The argument NSIG turns off this filtering.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NSIG",
+                ArgType::Flag,
+                "Under certain circumstances, enclone will delete exact subclonotypes that appear \
+                to be contaminants, based on their chain signature.  Please see \
+                10xgenomics.github.io/enclone/pages/auto/default_filters.html.  The argument NSIG \
+                turns off this filtering.",
+            ),
             65,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NWHITEF",
-            "By default, enclone filters out rare artifacts arising from contamination \
-            of oligos on gel beads.  The NWHITEF option turns off this filter.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NWHITEF",
+                ArgType::Flag,
+                "By default, enclone filters out rare artifacts arising from contamination \
+                of oligos on gel beads.  The NWHITEF option turns off this filter.",
+            ),
             55,
         )?;
 
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "NBC_DUP",
+            ArgType::Flag,
             "By default, enclone filters out duplicated barcodes within an exact",
-        );
+        ));
         h.doc(
             "",
             "subclonotype.  The NBC_DUP option turns off this filter.",
         );
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "MIX_DONORS",
-            "By default, enclone will prevent cells from different donors from being \
-            placed in the same clonotype.  The MIX_DONORS option turns off this \
-            behavior, thus allowing cells from different donors to be placed in the \
-            same clonotype.  The main use of this option is for specificity testing, \
-            in which data from different donors are deliberately combined in an attempt \
-            to find errors.  Use of the bc field for META input specification \
-            automatically turns on this option.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "MIX_DONORS",
+                ArgType::Flag,
+                "By default, enclone will prevent cells from different donors from being \
+                placed in the same clonotype.  The MIX_DONORS option turns off this \
+                behavior, thus allowing cells from different donors to be placed in the \
+                same clonotype.  The main use of this option is for specificity testing, \
+                in which data from different donors are deliberately combined in an attempt \
+                to find errors.  Use of the bc field for META input specification \
+                automatically turns on this option.",
+            ),
             55,
         )?;
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "NIMPROPER",
-            "enclone filters out exact subclonotypes having more than one chain, but all of the \
-            same type.  For example, the filter removes all exact subclonotypes having two TRA \
-            chains and no other chains.  The NIMPROPER option turns off this filter.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "NIMPROPER",
+                ArgType::Flag,
+                "enclone filters out exact subclonotypes having more than one chain, but all of \
+                the same type.  For example, the filter removes all exact subclonotypes having two \
+                TRA chains and no other chains.  The NIMPROPER option turns off this filter.",
+            ),
             55,
         )?;
 
         // Documentation section.
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "MIN_CHAINS_EXACT=n",
-            "Delete any exact subclonotype having less than n chains.  You can use this \
-            to \"purify\" a clonotype so as to display only exact subclonotypes having \
-            all their chains.",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "MIN_CHAINS_EXACT=n",
+                ArgType::Integer,
+                "Delete any exact subclonotype having less than n chains.  You can use this \
+                to \"purify\" a clonotype so as to display only exact subclonotypes having \
+                all their chains.",
+            ),
             55,
         )?;
 
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "CHAINS_EXACT=n",
+            ArgType::Integer,
             "Delete any exact subclonotype not having exactly n chains.",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "MIN_CELLS_EXACT=n",
+            ArgType::Integer,
             "Delete any exact subclonotype having less than n cells.  You might",
-        );
+        ));
         h.doc2("want to use this if you have a very large and complex expanded.");
         h.doc2("clonotype.");
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "COMPLETE",
+            ArgType::Flag,
             "delete any exact subclonotype that has less chains than the",
-        );
+        ));
         h.doc2("clonotype for which you would like to see a simplified view.");
-        h.docf2(
-            "CONST_IGH=\"<pattern>\"",
-            "for BCR, keep only exact subclonotypes having a heavy chain whose constant region \
-            gene name matches the given pattern (meaning regular expression, see \
-            \"enclone help filter\")",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "CONST_IGH=\"<pattern>\"",
+                ArgType::String,
+                "for BCR, keep only exact subclonotypes having a heavy chain whose constant region \
+                gene name matches the given pattern (meaning regular expression, see \"enclone \
+                help filter\")",
+            ),
             55,
         )?;
-        h.docf2(
-            "CONST_IGKL=\"<pattern>\"",
-            "for BCR, keep only exact subclonotypes having a light chain whose constant region \
-            gene name matches the given pattern (meaning regular expression, see \
-            \"enclone help filter\")",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "CONST_IGKL=\"<pattern>\"",
+                ArgType::String,
+                "for BCR, keep only exact subclonotypes having a light chain whose constant region \
+                gene name matches the given pattern (meaning regular expression, see \"enclone \
+                help filter\")",
+            ),
             55,
         )?;
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "MAX_HEAVIES=1",
+            ArgType::Integer,
             "ignore any cell having more than one IGH or TRB chain",
-        );
+        ));
 
         // Documentation section.
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "KEEP_CELL_IF=constraint",
-            "Let \"constraint\" be any constraint involving arithmetic and \
-            boolean operators, and variables that are specified as fields using the BC option \
-            (or equivalently, using bc, via META), see \"enclone help input\", or feature \
-            variables: <gene>_g or <antibody>_ab or <crispr>_cr or <custom>_cu, as described at \
-            \"enclone help lvars\" (but without regular expressions, as these would conflict \
-            with arithmetic operators).  This \
-            option filters out all barcodes that do not satisfy the given constraint.  \
-            Note that for purposes of testing the constraint, if the value for a \
-            particular barcode has not been specified, then its value is \
-            taken to be null.  Also multiple instances of KEEP_CELL_IF may be used to impose \
-            multiple filters.  See the examples below, and be very careful about syntax, \
-            which should match the given examples exactly.  In particular,",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "KEEP_CELL_IF=constraint",
+                ArgType::String,
+                "Let \"constraint\" be any constraint involving arithmetic and boolean operators, \
+                and variables that are specified as fields using the BC option (or equivalently, \
+                using bc, via META), see \"enclone help input\", or feature variables: <gene>_g or \
+                <antibody>_ab or <crispr>_cr or <custom>_cu, as described at \"enclone help \
+                lvars\" (but without regular expressions, as these would conflict with arithmetic \
+                operators).  This option filters out all barcodes that do not satisfy the given \
+                constraint.  Note that for purposes of testing the constraint, if the value for a \
+                particular barcode has not been specified, then its value is taken to be null.  \
+                Also multiple instances of KEEP_CELL_IF may be used to impose multiple filters.  \
+                See the examples below, and be very careful about syntax, which should match the \
+                given examples exactly.  In particular,",
+            ),
             55,
         )?;
         h.doc2("• use == for equality, and not =");
@@ -339,6 +407,10 @@ pub fn help4(args: &Vec<String>, h: &mut HelpDesk) -> Result<(), String> {
             "See also \"enclone help cvars\" and the inventory of all variables at
             https://10xgenomics.github.io/enclone/pages/auto/inventory.html.\n\n",
         )?;
+        h.declare_args(&[
+            ("LVARS", ArgType::List, "datasets,n"),
+            ("LVARSP", ArgType::List, ""),
+        ]);
         h.print(
             "Lead variables are specified using \\bold{LVARS=x1,...,xn} \
              where each xi is one of:\n\n",
@@ -2,7 +2,9 @@
 //
 // Test for help request, under development.
 
+use crate::help_registry::{ArgDoc, ArgType};
 use crate::help_utils::{colored_codon_table, HelpDesk};
+use crate::tail_args::PLOT_ARGS;
 use ansi_escape::{best_color_order, emit_end_escape, emit_green_escape, print_color};
 use enclone_build::version_string;
 use enclone_core::defs::EncloneControl;
@@ -21,10 +23,11 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
     if (args.len() == 3 && args[1] == "help" && args[2] == "display") || h.help_all {
         h.begin_doc("display")?;
         h.print("\n\\bold{other options that control clonotype display}\n\n")?;
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "PER_CELL",
+            ArgType::Flag,
             "expand out each exact clonotype line, showing one line per cell,",
-        );
+        ));
         h.doc(
             "",
             "for each such line, displaying the barcode name, the number of UMIs assigned,",
@@ -33,62 +36,77 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
             "",
             "and the gene expression UMI count, if applicable, under gex_med",
         );
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "BARCODES",
+            ArgType::Flag,
             "print list of all barcodes of the cells in each clonotype, in a",
-        );
+        ));
         h.doc(
             "",
             "single line near the top of the printout for a given clonotype",
         );
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "SEQC",
+            ArgType::Flag,
             "print V..J sequence for each chain in the first exact subclonotype, near",
-        );
+        ));
         h.doc("", "the top of the printout for a given clonotype");
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "FULL_SEQC",
+            ArgType::Flag,
             "print full sequence for each chain in the first exact subclonotype,",
-        );
+        ));
         h.doc("", "near the top of the printout for a given clonotype");
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "SUM",
+            ArgType::Flag,
             "print sum row for each clonotype (sum is across cells)",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "MEAN",
+            ArgType::Flag,
             "print mean row for each clonotype (mean is across cells)",
-        );
+        ));
 
         h.rows.push(vec!["\\hline".to_string(); 2]);
-        h.docf2(
-            "DIFF_STYLE=C1",
-            "instead of showing an x for each amino acid column containing a difference, \
-             show a C if the column lies within a complementarity-determining region, \
-             and F if it lies in a framework region, and an L if it lies in the leader",
+        h.docf2_arg(
+            &ArgDoc::new(
+                "DIFF_STYLE=C1",
+                ArgType::String,
+                "instead of showing an x for each amino acid column containing a difference, \
+                 show a C if the column lies within a complementarity-determining region, \
+                 and F if it lies in a framework region, and an L if it lies in the leader",
+            )
+            .with_values(&["C1", "C2"]),
             75,
         )?;
-        h.doc(
-            "DIFF_STYLE=C2",
-            "instead of showing an x for each amino acid column containing a difference,",
+        h.doc_arg(
+            &ArgDoc::new(
+                "DIFF_STYLE=C2",
+                ArgType::String,
+                "instead of showing an x for each amino acid column containing a difference,",
+            )
+            .with_values(&["C1", "C2"]),
         );
         h.docpr(
             "",
             "show a \\boldred{◼} if the column lies within a complementarity-determining region,",
         );
         h.docpr("", "and otherwise show a \\bold{▮}.");
-        h.ldoc(
+        h.ldoc_arg(&ArgDoc::new(
             "CONX",
+            ArgType::Flag,
             "add an additional row to each clonotype table, showing the amino acid",
-        );
+        ));
         h.doc(
             "",
             "consensus across the clonotype, with X for each variant residue",
         );
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "CONP",
+            ArgType::Flag,
             "add an additional row to each clonotype table, showing the amino acid",
-        );
+        ));
         h.doc(
             "",
             "consensus across the clonotype, with a property symbol whenever two different",
@@ -121,6 +139,13 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
             ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━}\n\n",
         )?;
 
+        h.declare_args(&[
+            ("GROUP", ArgType::List, ""),
+            ("AGROUP", ArgType::Flag, ""),
+            ("AG_CENTER", ArgType::String, ""),
+            ("AG_DIST_FORMULA", ArgType::String, ""),
+            ("AG_DIST_BOUND", ArgType::String, ""),
+        ]);
         h.print(
             "\\bold{options that control clonotype grouping}\n\n\
              By default, enclone organizes clonotypes into groups, and each group contains \
@@ -302,36 +327,53 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
             and asymmetric cases:\n\n",
         )?;
         h.rows.clear();
-        h.doc(
-            "MIN_GROUP",
-            "minimum number of clonotypes in group to print (default = 1)",
-        );
-        h.doc(
-            "MIN_GROUP_DONORS",
-            "minimum number of donors for a group to be printed (default = 1)",
-        );
-        h.doc(
+        h.doc_arg(
+            &ArgDoc::new(
+                "MIN_GROUP",
+                ArgType::Integer,
+                "minimum number of clonotypes in group to print",
+            )
+            .with_default("1"),
+        );
+        h.doc_arg(
+            &ArgDoc::new(
+                "MIN_GROUP_DONORS",
+                ArgType::Integer,
+                "minimum number of donors for a group to be printed",
+            )
+            .with_default("1"),
+        );
+        h.doc_arg(&ArgDoc::new(
             "GROUP_CDR3H_LEN_VAR",
+            ArgType::Flag,
             "only print groups having variable heavy chain CDR3 length",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "GROUP_CDR3=x",
+            ArgType::String,
             "only print groups containing the CDR3 amino acid sequence x",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "GROUP_DONOR=d",
+            ArgType::String,
             "only print groups containing a cell from the given donor;",
-        );
+        ));
         h.doc2("multiple instances may be used to jointly restrict");
-        h.doc(
+        h.doc_arg(&ArgDoc::new(
             "GROUP_NAIVE",
+            ArgType::Flag,
             "only show groups having an exact subclonotype with dref = 0",
-        );
-        h.doc(
+        ));
+        h.doc_arg(&ArgDoc::new(
             "GROUP_NO_NAIVE",
+            ArgType::Flag,
             "only show groups lacking an exact subclonotype with dref = 0",
-        );
-        h.ldoc("NGROUP", "don't display grouping messages");
+        ));
+        h.ldoc_arg(&ArgDoc::new(
+            "NGROUP",
+            ArgType::Flag,
+            "don't display grouping messages",
+        ));
         h.print_tab2()?;
         h.print("\n")?;
 
@@ -340,6 +382,7 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
             ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━}\n\n",
         )?;
 
+        h.declare_args(&[("DVARS", ArgType::List, "")]);
         h.print(
             "\\bold{options that display dataset variables}\n\n\
              enclone has some variables that are computed for each dataset, and whose values may \
@@ -365,6 +408,7 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
             ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━}\n\n",
         )?;
 
+        h.declare_args(&[("GVARS", ArgType::List, "")]);
         h.print(
             "\\bold{options that control global variables}\n\n\
              enclone has some global variables that can be computed, with values printed in the \
@@ -375,6 +419,52 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
              Please see https://10xgenomics.github.io/enclone/pages/auto/d_genes.html for more \
              information.\n\n",
         )?;
+
+        h.print(
+            "\\red{━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\
+            ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━}\n\n",
+        )?;
+
+        h.declare_args(&[("PLOT_XY", ArgType::String, "")]);
+        h.print(
+            "\\bold{options that make plots}\n\n\
+             These options make plots, in addition to the scatter plot made by \
+             \\bold{PLOT_XY=xvar,yvar,filename}, and the honeycomb plot made by \\bold{PLOT}, \
+             which is described at https://10xgenomics.github.io/enclone/pages/auto/plot.html.  \
+             Each plot is written to the given file as SVG, or as vector PDF if the filename ends \
+             in .pdf, except that HTML_PLOT writes an html page.\n\n",
+        )?;
+        h.rows.clear();
+        for (i, args) in PLOT_ARGS.iter().enumerate() {
+            if i > 0 {
+                h.rows.push(vec!["\\hline".to_string(); 2]);
+            }
+            for a in args.iter() {
+                h.docf2_arg(a, 40)?;
+            }
+        }
+        h.print_tab2()?;
+        h.print("\n")?;
+        h.print(
+            "The value of \\bold{PLOTS} has one of the forms\n\
+             honey:out=filename[,width],color-spec[,legend=none][,split=origin|dataset][,quad]\n\
+             xy:xvar,yvar,filename[,sym]\n\
+             sim_mat:filename,var1,...,varn\n\
+             which make a honeycomb plot, a scatter plot, as for PLOT_XY, and a similarity matrix \
+             plot.  The color-spec is one of color=dataset, color=isotype, \
+             color=var,[abbr:]name[,turbo][,minmax,min,max] and \
+             color=catvar,var1+...+varn,maxcat:n, and if it is omitted, cells are colored as for \
+             PLOT.  As for PLOT_XY, xvar and yvar may have the form log10(var).  Plots that are \
+             written to the same pdf file become its pages, in the order given.\n\n",
+        )?;
+        h.print(
+            "The layout \\bold{PLOT_LAYOUT=frontchain} packs the clusters of cells in honeycomb \
+             plots using the front-chain algorithm of Wang et al., which is faster than the \
+             default random layout, and yields a tighter pack.  For the random layout, seed=n initializes \
+             the random number generator, and for frontchain, it determines the orientation of \
+             the pack.  With stats, the ratio of the total area of the clusters to the area of \
+             the smallest circle that encloses them is printed for each plot.\n\n",
+        )?;
         h.end_doc();
     }
 
@@ -419,6 +509,7 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
 
     if (args.len() == 3 && args[1] == "help" && args[2] == "color") || h.help_all {
         h.begin_doc("color")?;
+        h.declare_args(&[("COLOR", ArgType::String, "codon")]);
         h.print("\nHere is the color palette that enclone uses for amino acids:\n\n")?;
         let mut pal = String::new();
         for i in 0..7 {
@@ -533,6 +624,18 @@ pub fn help5(args: &Vec<String>, ctl: &EncloneControl, h: &mut HelpDesk) -> Resu
 
     if (args.len() == 3 && args[1] == "help" && args[2] == "faq") || h.help_all {
         h.begin_doc("faq")?;
+        h.declare_args(&[
+            ("HTML", ArgType::String, ""),
+            ("DONOR_REF_FILE", ArgType::Path, ""),
+            ("MOUSE", ArgType::Flag, ""),
+            ("REF", ArgType::Path, ""),
+            ("RE", ArgType::Flag, ""),
+            ("NWARN", ArgType::Flag, ""),
+            ("SUMMARY", ArgType::Flag, ""),
+            ("MAX_CORES", ArgType::Integer, ""),
+            ("ACCEPT_REUSE", ArgType::Flag, ""),
+            ("SUBSET_JSON", ArgType::Path, ""),
+        ]);
         h.print("\n\\boldred{Frequently Asked Questions}\n\n")?;
         h.print(
             "We're sorry you're having difficulty! Please see the answers below or check out \
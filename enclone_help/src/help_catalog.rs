@@ -0,0 +1,230 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute "enclone help catalog JSON", which prints a machine-readable catalog of the top-level
+// arguments, and the lead, chain, global and parseable variables.
+//
+// The catalog is generated from the help registry (see help_registry.rs), which provides the
+// help page, documented syntax, description, examples, and the type, default and allowed values
+// that are declared with each argument, and from the lists of allowed variables in enclone_core,
+// which define the variables that are accepted.  Variables that are accepted but not documented
+// are included with an empty description.
+//
+// Each entry has these fields:
+// name           the argument or variable, e.g. MIN_CELLS or cdr3_aa
+// kind           argument, lvar, cvar, gvar or parseable
+// syntax         the forms in which it is documented, e.g. MIN_CELLS=n
+// type           for arguments: flag, integer, number, path, list or string; for variables:
+//                variable
+// allowed_values values, if it takes one of a fixed set of them, else empty
+// default        default value, if declared, else null
+// page           help page, e.g. filter, for "enclone help filter", or empty
+// description    text from the help page
+// needs_gex_fb   for variables, whether gene expression or feature barcode data are needed;
+//                null for arguments
+// examples       example commands from the help pages that use it
+
+use crate::help_registry::{help_registry, HelpEntry};
+use crate::help_utils::{is_arg, HelpDesk};
+use enclone_args::proc_args_check::involves_gex_fb;
+use enclone_build::version_string;
+use enclone_core::allowed_vars::{
+    CVARS_ALLOWED, CVARS_ALLOWED_PCELL, GVARS_ALLOWED, LVARS_ALLOWED,
+};
+use enclone_core::defs::EncloneControl;
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub kind: String,
+    pub syntax: Vec<String>,
+    pub value_type: String,
+    pub allowed_values: Vec<String>,
+    pub default: Option<String>,
+    pub page: String,
+    pub description: String,
+    pub needs_gex_fb: Option<bool>,
+    pub examples: Vec<String>,
+}
+
+impl CatalogEntry {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "kind": self.kind,
+            "syntax": self.syntax,
+            "type": self.value_type,
+            "allowed_values": self.allowed_values,
+            "default": self.default,
+            "page": self.page,
+            "description": self.description,
+            "needs_gex_fb": self.needs_gex_fb,
+            "examples": self.examples,
+        })
+    }
+}
+
+// Determine the kind of a registry entry, if it is an argument or variable.
+
+fn entry_kind(e: &HelpEntry) -> Option<&'static str> {
+    if is_arg(e.key()) {
+        return Some("argument");
+    }
+    match e.page.as_str() {
+        "lvars" => Some("lvar"),
+        "cvars" | "amino" => Some("cvar"),
+        "parseable" => Some("parseable"),
+        _ => None,
+    }
+}
+
+// Build the catalog.
+
+pub fn catalog(ctl: &EncloneControl) -> Result<Vec<CatalogEntry>, String> {
+    // Merge the registry entries for each argument or variable.
+
+    let registry = help_registry(ctl)?;
+    let mut entries = Vec::<CatalogEntry>::new();
+    for e in registry.iter() {
+        let kind = match entry_kind(e) {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let key = e.key();
+        let p = match entries.iter().position(|x| x.kind == kind && x.name == key) {
+            Some(p) => p,
+            None => {
+                let value_type = if kind != "argument" {
+                    "variable"
+                } else if let Some(t) = e.value_type {
+                    t.as_str()
+                } else {
+                    return Err(format!(
+                        "\nThe type of the argument {} is not declared in the help pages.\n",
+                        e.name
+                    ));
+                };
+                entries.push(CatalogEntry {
+                    name: key.to_string(),
+                    kind: kind.to_string(),
+                    value_type: value_type.to_string(),
+                    page: e.page.clone(),
+                    description: e.description.clone(),
+                    needs_gex_fb: if kind == "argument" {
+                        None
+                    } else {
+                        Some(involves_gex_fb(key))
+                    },
+                    ..Default::default()
+                });
+                entries.len() - 1
+            }
+        };
+        let c = &mut entries[p];
+        if !c.syntax.contains(&e.name) {
+            c.syntax.push(e.name.clone());
+        }
+        if c.default.is_none() && !e.default.is_empty() {
+            c.default = Some(e.default.clone());
+        }
+        for x in e.values.iter() {
+            if !c.allowed_values.contains(x) {
+                c.allowed_values.push(x.clone());
+            }
+        }
+        for x in e.examples.iter() {
+            if !c.examples.contains(x) {
+                c.examples.push(x.clone());
+            }
+        }
+    }
+
+    // Add variables that are accepted but not documented.
+
+    let lists: Vec<(&str, &str, Vec<&str>)> = vec![
+        ("lvar", "lvars", LVARS_ALLOWED.iter().copied().collect()),
+        ("cvar", "cvars", CVARS_ALLOWED.iter().copied().collect()),
+        (
+            "cvar",
+            "cvars",
+            CVARS_ALLOWED_PCELL.iter().copied().collect(),
+        ),
+        ("gvar", "display", GVARS_ALLOWED.iter().copied().collect()),
+    ];
+    for (kind, page, vars) in lists.iter() {
+        for var in vars.iter() {
+            if !entries.iter().any(|x| x.kind == *kind && x.name == *var) {
+                entries.push(CatalogEntry {
+                    name: var.to_string(),
+                    kind: kind.to_string(),
+                    value_type: "variable".to_string(),
+                    page: page.to_string(),
+                    needs_gex_fb: Some(involves_gex_fb(var)),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+pub fn help_catalog(
+    args: &Vec<String>,
+    ctl: &EncloneControl,
+    h: &mut HelpDesk,
+) -> Result<(), String> {
+    if args.len() < 3 || args[1] != "help" || args[2] != "catalog" {
+        return Ok(());
+    }
+    if args.len() != 4 || args[3] != "JSON" {
+        return Err(
+            "\nThe only supported catalog format is JSON, as in \"enclone help catalog JSON\".\n"
+                .to_string(),
+        );
+    }
+    let entries = catalog(ctl)?
+        .iter()
+        .map(CatalogEntry::to_json)
+        .collect::<Vec<_>>();
+    let catalog = json!({ "version": version_string(), "entries": entries });
+    println!("{}", serde_json::to_string_pretty(&catalog).unwrap());
+    h.ok = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        let entries = catalog(&EncloneControl::default()).unwrap();
+        let find = |x: &str| {
+            entries
+                .iter()
+                .find(|e| e.kind == "argument" && e.name == x)
+                .unwrap()
+        };
+        assert_eq!(find("MIN_CELLS").value_type, "integer");
+        assert_eq!(find("POUT").value_type, "path");
+        assert_eq!(find("PLAIN").value_type, "flag");
+        assert_eq!(find("LVARS").default, Some("datasets,n".to_string()));
+        assert_eq!(
+            find("DIFF_STYLE").syntax,
+            vec!["DIFF_STYLE=C1", "DIFF_STYLE=C2"]
+        );
+        assert_eq!(find("DIFF_STYLE").allowed_values, vec!["C1", "C2"]);
+        assert_eq!(find("ANNDATA").page, "parseable");
+        assert_eq!(find("ANNDATA_VARS").value_type, "list");
+        assert_eq!(find("ALLUVIAL_TOP").page, "display");
+        assert_eq!(find("ALLUVIAL_TOP").value_type, "integer");
+        assert_eq!(find("ALLUVIAL_TOP").default, Some("10".to_string()));
+        assert_eq!(
+            find("LOGO_BY").allowed_values,
+            vec!["all", "group", "clonotype"]
+        );
+        assert!(entries
+            .iter()
+            .any(|e| e.kind == "lvar" && e.name == "nchains"));
+    }
+}
@@ -0,0 +1,383 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Examples that are shown in the help pages together with their output.  Each is declared here,
+// once, with its command and expected output.  The help pages show them from here, the test
+// test_enclone_examples in enclone_exec runs each of them against the test datasets and reports
+// any drift, and target/debug/update_help_examples regenerates the outputs.
+//
+// The outputs are generated with PRE set to the test datasets, see help_example_args.
+//
+// The examples on the site are declared here too, as ExampleKind::Site.  Their outputs are html,
+// svg or png files in the repository, which are not compiled in.  The test test_site_examples checks
+// them, and merge_html BUILD regenerates them.
+//
+// Notes on how to add a site example:
+//
+// Be very careful: there are svg and html examples below.  Mimic one or the other.
+//
+// 1. cargo b
+// 2. merge_html BUILD
+// 3. ./build
+
+use enclone_core::parse_bsv;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExampleKind {
+    // shown by a help page, e.g. page = example1 for "enclone help example1", with its expected
+    // output
+    Help {
+        page: &'static str,
+        output: &'static str,
+    },
+    // only on the site
+    Site,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HelpExample {
+    pub kind: ExampleKind,
+    // arguments to enclone
+    pub args: &'static str,
+    // file that has the expected output, relative to the top level of the repo
+    pub file: &'static str,
+}
+
+impl HelpExample {
+    // Help page that shows the example, if any.
+
+    pub fn page(&self) -> Option<&'static str> {
+        match self.kind {
+            ExampleKind::Help { page, .. } => Some(page),
+            ExampleKind::Site => None,
+        }
+    }
+
+    // Expected output, if it is compiled in.
+
+    pub fn output(&self) -> Option<&'static str> {
+        match self.kind {
+            ExampleKind::Help { output, .. } => Some(output),
+            ExampleKind::Site => None,
+        }
+    }
+}
+
+pub const HELP_EXAMPLES: [HelpExample; 30] = [
+    // 1.
+    HelpExample {
+        kind: ExampleKind::Help {
+            page: "example1",
+            output: include_str!("example1"),
+        },
+        args: r###"BCR=123089 CDR3=CARRYFGVVADAFDIW"###,
+        file: "enclone_help/src/example1",
+    },
+    // 2.
+    HelpExample {
+        kind: ExampleKind::Help {
+            page: "example2",
+            output: include_str!("example2"),
+        },
+        args: r###"BCR=123085 GEX=123217 LVARSP=gex,IGHV2-5_g_μ CDR3=CALMGTYCSGDNCYSWFDPW"###,
+        file: "enclone_help/src/example2",
+    },
+    // site 1.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CDR3=CTRDRDLRGATDAFDIW GEX=123217 LVARSP=gex,IGHV3-49_g NUMI \
+         HTML=\"enclone example with gex\"",
+        file: "pages/auto/clonotype_with_gex.html",
+    },
+    // site 2.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=128037,128040 NCROSS CDR3=CARGGTTTYFISW NGROUP NUMI NUMI_RATIO \
+         HTML=\"illusory clonotype expansion 1\"",
+        file: "pages/auto/illusory1.html",
+    },
+    // site 3.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=128037,128040 CDR3=CARGGTTTYFISW NGROUP NUMI NUMI_RATIO \
+      HTML=\"illusory clonotype expansion 2\"",
+        file: "pages/auto/illusory2.html",
+    },
+    // site 4.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=128040 GEX=127801 CDR3=CARGGTTTYFISW NGROUP NUMI NUMI_RATIO \
+         HTML=\"illusory clonotype expansion 3\"",
+        file: "pages/auto/illusory3.html",
+    },
+    // site 5.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=128040 GEX=127801 CDR3=CARGGTTTYFISW PER_CELL LVARSP=gex,cred MIN_CHAINS_EXACT=2 NUMI \
+         NUMI_RATIO NGROUP HTML=\"illusory clonotype expansion 4\"",
+        file: "pages/auto/illusory4.html",
+    },
+    // site 6.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=128040 GEX=127801 BC=testx/inputs/128024_cells.csv \
+         CDR3=CARGGTTTYFISW PER_CELL NUMI NUMI_RATIO \
+         LVARSP=gex,cred,T CHAINS_EXACT=2 NGROUP HTML=\"illusory clonotype expansion 5\"",
+        file: "pages/auto/illusory5.html",
+    },
+    // site 7.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085:123089 MIN_CELLS=10 PLOT=\"stdout,s1->blue,s2->red\" NOPRINT \
+         LEGEND=blue,123085,red,123089",
+        file: "img/samples.svg",
+    },
+    // site 8.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085,123089 MIN_CELLS=5 MIN_CHAINS_EXACT=2 NOPRINT PLOT_BY_ISOTYPE=stdout",
+        file: "img/iso.svg",
+    },
+    // site 9.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 TREE COMPLETE CDR3=CARDQNFDESSGYDAFDIW LVARSP=dref HTML",
+        file: "pages/auto/tree_example.html",
+    },
+    // site 10.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "TCR=101287 LVARSP=mait CDR3=CSAGQGDTEAFF HTML",
+        file: "pages/auto/mait_example.html",
+    },
+    // site 11.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CDR3=CARRYFGVVADAFDIW NFOURSIE_KILL HTML",
+        file: "pages/auto/foursie1.html",
+    },
+    // site 12.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CDR3=CARRYFGVVADAFDIW HTML",
+        file: "pages/auto/foursie2.html",
+    },
+    // site 13.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085:123089 PLOT=\"stdout,s1->blue,s2->red\" QUAD_HIVE NOPRINT",
+        file: "img/quad_hive.svg",
+    },
+    // site 14.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 GEX=123217 NOPRINT PLOTXY_EXACT=HLA-A_g,CD74_g,stdout",
+        file: "img/two_genes.svg",
+    },
+    // site 15.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CDR3=CALMGTYCSGDNCYSWFDPW PER_CELL POUT=stdouth PCELL PCOLS=barcode,u1,u_cell1 \
+         HTML=\"variable demo\"",
+        file: "pages/auto/variable_demo.html",
+    },
+    // site 16.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CDR3=CALMGTYCSGDNCYSWFDPW POUT=stdouth PCOLS=barcodes,u1 \
+         HTML=\"variable demo2\"",
+        file: "pages/auto/variable_demo2.html",
+    },
+    // site 17.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CVARS=d1_name,d2_name,d_Δ CDR3=CTRDRDLRGATDAFDIW \
+         HTML=\"D gene example1\"",
+        file: "pages/auto/d_gene_example1.html",
+    },
+    // site 18.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 CVARS=d1_name,d2_name,d_Δ CDR3=CAREGGVGVVTATDWYFDLW COMPLETE \
+         HTML=\"D gene example1b\"",
+        file: "pages/auto/d_gene_example1b.html",
+    },
+    // site 19.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 GVARS=d_inconsistent_%,d_inconsistent_n NOPRINT SUMMARY SUMMARY_CLEAN \
+         HTML=\"D gene example2\"",
+        file: "pages/auto/d_gene_example2.html",
+    },
+    // site 20.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 ALIGN1 CDR3=CARYIVVVVAATINVGWFDPW CVARSP=d1_name \
+         HTML=\"ALIGN example\"",
+        file: "pages/auto/align_example.html",
+    },
+    // site 21.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 JALIGN1 CDR3=CARYIVVVVAATINVGWFDPW CVARSP=d1_name \
+         HTML=\"JALIGN example\"",
+        file: "pages/auto/jun_align_example.html",
+    },
+    // site 22.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=165808 JALIGN1 CDR3=CARAYDILTGYYERGYSYGWGFDYW \
+         HTML=\"VDDJ example\"",
+        file: "pages/auto/vddj.html",
+    },
+    // site 23.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 GEX=123217 SIM_MAT_PLOT=stdout,CDKN1A_g,CDKN1B_g,RBX1_g,IGLC1_g,IGLV3-21_g \
+         NOPRINT",
+        file: "img/sim_mat_plot.svg",
+    },
+    // site 24.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085:123089 PLOT_BY_ISOTYPE=stdout SPLIT_PLOT_BY_ORIGIN NOPRINT",
+        file: "img/twin_plot.svg",
+    },
+    // site 25.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 MIN_CELLS=10 HONEY=out=stdout.png,color=var,u_cell1 NOPRINT NO_NEWLINE",
+        file: "img/var.png",
+    },
+    // site 26.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085,123089,124547 MIN_CELLS=5 HONEY=out=stdout,color=dataset NOPRINT",
+        file: "img/by_dataset.svg",
+    },
+    // site 27.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: r###"BCR=86237 GEX=85679 VAR_DEF="sum:CD19_ab + CD25_ab" LVARSP=CD19_ab,CD25_ab,sum CDR3=CARSFFGDTAMVMFQAFDPW PER_CELL FOLD_HEADERS HTML"###,
+        file: "pages/auto/var_def.html",
+    },
+    // site 28.
+    HelpExample {
+        kind: ExampleKind::Site,
+        args: "BCR=123085 HONEY=out=stdout,color=catvar,v_name1+v_name2,maxcat:10 NOPRINT CHAINS_EXACT=2",
+        file: "img/cat_var.svg",
+    },
+];
+
+// The examples that are shown in the help pages, and the examples that are only on the site.
+
+pub fn help_page_examples() -> impl Iterator<Item = &'static HelpExample> {
+    HELP_EXAMPLES.iter().filter(|ex| ex.page().is_some())
+}
+
+pub fn site_examples() -> impl Iterator<Item = &'static HelpExample> {
+    HELP_EXAMPLES
+        .iter()
+        .filter(|ex| ex.kind == ExampleKind::Site)
+}
+
+// Return the full argument list for running an example, given the directory that has the test
+// datasets.  Site examples are run as they are written, using the default PRE.
+
+pub fn help_example_args(ex: &HelpExample, pre: &str) -> Vec<String> {
+    if ex.kind == ExampleKind::Site {
+        let mut args = parse_bsv(ex.args)
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        args.push("NO_KILL".to_string());
+        return args;
+    }
+    let mut args = vec![format!("PRE={}", pre)];
+    args.extend(ex.args.split(' ').map(str::to_string));
+    args.push("NO_KILL".to_string());
+    args
+}
+
+// Compare the output of an example in the help pages to its expected output.  Return None if
+// they agree, and otherwise a description of the first difference.
+
+pub fn help_example_drift(ex: &HelpExample, new: &str) -> Option<String> {
+    let ExampleKind::Help { page, output } = ex.kind else {
+        panic!("{} is not shown in the help pages", ex.file);
+    };
+    if new == output {
+        return None;
+    }
+    let (old_lines, new_lines) = (
+        output.lines().collect::<Vec<_>>(),
+        new.lines().collect::<Vec<_>>(),
+    );
+    let mut msg = format!(
+        "the output of \"enclone {}\", shown by \"enclone help {}\", has changed\n\
+         old output has {} lines; new output has {} lines\n",
+        ex.args,
+        page,
+        old_lines.len(),
+        new_lines.len()
+    );
+    for i in 0..old_lines.len().max(new_lines.len()) {
+        let (old, new) = (old_lines.get(i), new_lines.get(i));
+        if old != new {
+            msg += &format!(
+                "first different line is line {}\nold = {}\nnew = {}\n",
+                i + 1,
+                old.unwrap_or(&"(none)"),
+                new.unwrap_or(&"(none)")
+            );
+            break;
+        }
+    }
+    Some(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_help_examples() {
+        // The help pages show the first two examples.
+
+        assert_eq!(HELP_EXAMPLES[0].page(), Some("example1"));
+        assert_eq!(HELP_EXAMPLES[1].page(), Some("example2"));
+        for ex in help_page_examples() {
+            assert!(!ex.output().unwrap().is_empty());
+            assert_eq!(help_example_drift(ex, ex.output().unwrap()), None);
+        }
+
+        // Each example has its own output file, and the outputs of the site examples are in
+        // the repo.
+
+        let mut files = HELP_EXAMPLES.iter().map(|ex| ex.file).collect::<Vec<_>>();
+        files.sort_unstable();
+        files.dedup();
+        assert_eq!(files.len(), HELP_EXAMPLES.len());
+        for ex in site_examples() {
+            assert_eq!(ex.output(), None);
+            assert!(ex.file.starts_with("pages/auto/") || ex.file.starts_with("img/"));
+            assert!(
+                Path::new("..").join(ex.file).exists(),
+                "{} is missing",
+                ex.file
+            );
+        }
+
+        let ex = &HELP_EXAMPLES[0];
+        let args = help_example_args(ex, "x");
+        assert_eq!(args[0], "PRE=x");
+        assert_eq!(args[1], "BCR=123089");
+        assert_eq!(args.last().unwrap(), "NO_KILL");
+        let drift = help_example_drift(ex, &ex.output().unwrap().replacen('1', "2", 1)).unwrap();
+        assert!(drift.contains("first different line"));
+        let ex = site_examples().last().unwrap();
+        let args = help_example_args(ex, "x");
+        assert_eq!(args[0], "BCR=123085");
+        assert_eq!(args.last().unwrap(), "NO_KILL");
+    }
+}
@@ -0,0 +1,570 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute "enclone help export man DIR" and "enclone help export markdown DIR", which write the
+// help pages to the directory DIR, as troff man pages or as Markdown files.
+//
+// As for the registry (see help_registry.rs), all the help pages are run, exactly as for
+// "enclone help all", but here with the HelpDesk in export mode, so that instead of printing, it
+// records the content of each page as a sequence of blocks: text with markup such as \bold{...},
+// preformatted text, tables, and boxed text.  These blocks are then rendered.  Bold is kept and
+// colors are dropped.  Line breaks within a paragraph are kept, but long lines are not folded,
+// since man and Markdown viewers do that.
+//
+// The main page (what you get by typing "enclone") becomes enclone.1 or enclone.md, and every
+// other page, e.g. "enclone help filter", becomes enclone-filter.1 or enclone-filter.md.
+//
+// Tables in the man pages use tbl, which man runs by default.
+
+use crate::help1::help1;
+use crate::help2::help2;
+use crate::help3::help3;
+use crate::help4::help4;
+use crate::help5::help5;
+use crate::help_utils::HelpDesk;
+use enclone_core::defs::EncloneControl;
+use std::fs::{create_dir_all, write};
+
+// A block of content on a help page.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HelpBlock {
+    // text, with markup
+    Text(String),
+    // preformatted text
+    Plain(String),
+    // table rows, with markup, including \hline rows
+    Table(Vec<Vec<String>>),
+    // text, with markup, that is shown in a box
+    Boxed(String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HelpPage {
+    // name, e.g. filter for "enclone help filter", or main or setup
+    pub name: String,
+    pub blocks: Vec<HelpBlock>,
+}
+
+// Run all the help pages, in export mode.
+
+pub fn help_pages(ctl: &EncloneControl) -> Result<Vec<HelpPage>, String> {
+    let args = vec!["enclone".to_string(), "help".to_string(), "all".to_string()];
+    let mut h = HelpDesk::new(true, true, true, false);
+    h.export = true;
+    help1(&args, &mut h)?;
+    help2(&args, ctl, &mut h)?;
+    help3(&args, &mut h)?;
+    help4(&args, &mut h)?;
+    help5(&args, ctl, &mut h)?;
+    Ok(h.pages)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Parsing of text with markup.  A line is a list of (bold, text) pieces.
+
+type Line = Vec<(bool, String)>;
+
+fn strip_escapes(x: &str) -> String {
+    let mut s = String::new();
+    let mut escaped = false;
+    for c in x.chars() {
+        if c == '\x1b' {
+            escaped = true;
+        } else if escaped {
+            if c == 'm' {
+                escaped = false;
+            }
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
+
+// Split text into (bold, text) pieces.  Markup that is not closed is kept as is.
+
+fn spans(x: &str) -> Line {
+    const TAGS: [(&str, bool); 6] = [
+        ("\\bold{", true),
+        ("\\boldred{", true),
+        ("\\boldblue{", true),
+        ("\\red{", false),
+        ("\\blue{", false),
+        ("\\green{", false),
+    ];
+    let x = strip_escapes(x);
+    let mut pieces = Line::new();
+    let mut plain = String::new();
+    let mut rest = x.as_str();
+    'outer: while let Some(c) = rest.chars().next() {
+        for (tag, bold) in TAGS.iter() {
+            if let Some(after) = rest.strip_prefix(tag) {
+                if let Some(end) = after.find('}') {
+                    if !plain.is_empty() {
+                        pieces.push((false, std::mem::take(&mut plain)));
+                    }
+                    pieces.push((*bold, after[..end].to_string()));
+                    rest = &after[end + 1..];
+                    continue 'outer;
+                }
+            }
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        pieces.push((false, plain));
+    }
+    pieces
+}
+
+fn lines(x: &str) -> Vec<Line> {
+    let mut lines = vec![Line::new()];
+    for (bold, s) in spans(x) {
+        for (i, piece) in s.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Line::new());
+            }
+            if !piece.is_empty() {
+                lines.last_mut().unwrap().push((bold, piece.to_string()));
+            }
+        }
+    }
+    lines
+}
+
+// Break text into paragraphs, which are separated by blank lines.
+
+fn paragraphs(x: &str) -> Vec<Vec<Line>> {
+    let mut pars = Vec::<Vec<Line>>::new();
+    let mut par = Vec::<Line>::new();
+    for line in lines(x) {
+        if line.iter().all(|(_, s)| s.trim().is_empty()) {
+            if !par.is_empty() {
+                pars.push(std::mem::take(&mut par));
+            }
+        } else {
+            par.push(line);
+        }
+    }
+    if !par.is_empty() {
+        pars.push(par);
+    }
+    pars
+}
+
+// Return the text of a table cell, on one line.
+
+fn cell(x: &str) -> Line {
+    spans(x)
+        .into_iter()
+        .map(|(bold, s)| (bold, s.replace('\n', " ")))
+        .collect()
+}
+
+fn unmarked(x: &str) -> String {
+    spans(x).into_iter().map(|x| x.1).collect::<String>()
+}
+
+// Clean up table rows: drop leading, trailing and repeated \hline rows, which are returned as
+// None, and merge each row whose first entry is empty into the row before it.
+
+fn table_rows(rows: &[Vec<String>]) -> Vec<Option<Vec<String>>> {
+    let mut out = Vec::<Option<Vec<String>>>::new();
+    for row in rows.iter() {
+        if row.iter().any(|x| x == "\\hline") {
+            if matches!(out.last(), Some(Some(_))) {
+                out.push(None);
+            }
+        } else if unmarked(&row[0]).trim().is_empty() && matches!(out.last(), Some(Some(_))) {
+            let prev = out.last_mut().unwrap().as_mut().unwrap();
+            for (k, x) in row.iter().enumerate() {
+                if k < prev.len() && !unmarked(x).trim().is_empty() {
+                    prev[k] = format!("{} {}", prev[k].trim_end(), x.trim_start());
+                }
+            }
+        } else {
+            out.push(Some(row.clone()));
+        }
+    }
+    if out.last() == Some(&None) {
+        out.pop();
+    }
+    out
+}
+
+// Return the command that shows a help page.
+
+fn command(name: &str) -> String {
+    match name {
+        "main" => "enclone".to_string(),
+        "setup" => "enclone help".to_string(),
+        _ => format!("enclone help {}", name),
+    }
+}
+
+fn file_stem(name: &str) -> String {
+    if name == "main" {
+        "enclone".to_string()
+    } else {
+        format!("enclone-{}", name)
+    }
+}
+
+// Return a one line summary of a page, from its entry in the table on the main page.
+
+fn summary(pages: &[HelpPage], name: &str) -> String {
+    if name == "main" {
+        return "find and display the clonotypes within single cell VDJ datasets".to_string();
+    }
+    let cmd = command(name);
+    for page in pages.iter().filter(|p| p.name == "main") {
+        for b in page.blocks.iter() {
+            if let HelpBlock::Table(rows) = b {
+                for row in table_rows(rows).into_iter().flatten() {
+                    if row.len() >= 2 && unmarked(&row[0]).trim() == cmd {
+                        return unmarked(&row[1]).trim().to_string();
+                    }
+                }
+            }
+        }
+    }
+    cmd
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Rendering as troff, using the man macros.
+
+fn troff_text(x: &str) -> String {
+    let s = x.replace('\\', "\\e");
+    if s.starts_with('.') || s.starts_with('\'') {
+        format!("\\&{}", s)
+    } else {
+        s
+    }
+}
+
+fn troff_line(line: &Line) -> String {
+    let mut s = String::new();
+    for (bold, x) in line.iter() {
+        let x = x.replace('\\', "\\e");
+        if *bold {
+            s += &format!("\\fB{}\\fR", x);
+        } else {
+            s += &x;
+        }
+    }
+    if s.starts_with('.') || s.starts_with('\'') {
+        s = format!("\\&{}", s);
+    }
+    s
+}
+
+fn troff_paragraphs(x: &str) -> String {
+    let mut s = String::new();
+    for par in paragraphs(x).iter() {
+        s += ".PP\n";
+        for (i, line) in par.iter().enumerate() {
+            if i > 0 {
+                s += ".br\n";
+            }
+            s += &format!("{}\n", troff_line(line));
+        }
+    }
+    s
+}
+
+fn troff_block(b: &HelpBlock) -> String {
+    match b {
+        HelpBlock::Text(x) => troff_paragraphs(x),
+        HelpBlock::Boxed(x) => format!(".RS\n{}.RE\n", troff_paragraphs(x)),
+        HelpBlock::Plain(x) => {
+            let x = strip_escapes(x);
+            let x = x.trim_matches('\n');
+            if x.trim().is_empty() {
+                return String::new();
+            }
+            let mut s = ".PP\n.nf\n".to_string();
+            for line in x.lines() {
+                s += &format!("{}\n", troff_text(line));
+            }
+            s + ".fi\n"
+        }
+        HelpBlock::Table(rows) => {
+            let rows = table_rows(rows);
+            let ncols = rows.iter().flatten().map(|r| r.len()).max().unwrap_or(0);
+            if ncols == 0 {
+                return String::new();
+            }
+            let mut s = format!(".PP\n.TS\nbox;\n{}lx.\n", "l ".repeat(ncols - 1));
+            for row in rows.iter() {
+                match row {
+                    None => s += "_\n",
+                    Some(row) => {
+                        let cells = (0..ncols)
+                            .map(|k| {
+                                let x = row.get(k).map(|x| cell(x)).unwrap_or_default();
+                                format!("T{{\n{}\nT}}", troff_line(&x))
+                            })
+                            .collect::<Vec<_>>();
+                        s += &format!("{}\n", cells.join("\t"));
+                    }
+                }
+            }
+            s + ".TE\n"
+        }
+    }
+}
+
+fn man_page(pages: &[HelpPage], page: &HelpPage) -> String {
+    let stem = file_stem(&page.name);
+    let mut s = format!(
+        "'\\\" t\n.TH {} 1 \"\" \"enclone {}\" \"enclone help\"\n",
+        stem.to_uppercase(),
+        env!("CARGO_PKG_VERSION")
+    );
+    s += &format!(
+        ".SH NAME\n{} \\- {}\n",
+        stem,
+        troff_text(&summary(pages, &page.name))
+    );
+    s += ".SH SYNOPSIS\n";
+    if page.name == "main" {
+        s += ".B enclone\n[\\fIargument\\fR ...]\n";
+    } else {
+        s += &format!(".B {}\n", command(&page.name));
+    }
+    s += ".SH DESCRIPTION\n";
+    for b in page.blocks.iter() {
+        s += &troff_block(b);
+    }
+    s += ".SH SEE ALSO\n";
+    if page.name == "main" {
+        let others = pages
+            .iter()
+            .filter(|p| p.name != "main")
+            .collect::<Vec<_>>();
+        for (i, p) in others.iter().enumerate() {
+            let comma = if i + 1 < others.len() { "," } else { "" };
+            s += &format!(".BR {} (1){}\n", file_stem(&p.name), comma);
+        }
+    } else {
+        s += ".BR enclone (1)\n";
+    }
+    s
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Rendering as Markdown.  Line breaks within a paragraph are kept as hard line breaks.
+
+fn md_escape(x: &str) -> String {
+    let mut s = String::new();
+    for c in x.chars() {
+        if "\\`*_<>[]|".contains(c) {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s
+}
+
+fn md_line(line: &Line) -> String {
+    let mut s = String::new();
+    for (bold, x) in line.iter() {
+        let t = x.trim();
+        if *bold && !t.is_empty() {
+            let lead = &x[..x.len() - x.trim_start().len()];
+            let trail = &x[x.trim_end().len()..];
+            s += &format!("{}**{}**{}", lead, md_escape(t), trail);
+        } else {
+            s += &md_escape(x);
+        }
+    }
+    let s = s.trim();
+    if let Some(item) = s.strip_prefix("• ") {
+        format!("- {}", item)
+    } else if s.starts_with(|c: char| "#+-=".contains(c)) || s.starts_with(char::is_numeric) {
+        format!("\\{}", s)
+    } else {
+        s.to_string()
+    }
+}
+
+fn md_paragraphs(x: &str) -> Vec<String> {
+    paragraphs(x)
+        .iter()
+        .map(|par| {
+            let mut s = String::new();
+            for line in par.iter().map(md_line).filter(|x| !x.is_empty()) {
+                if !s.is_empty() {
+                    // A list item starts its own line, so needs no hard break before it.
+                    s += if line.starts_with("- ") { "\n" } else { "\\\n" };
+                }
+                s += &line;
+            }
+            s
+        })
+        .filter(|x| !x.is_empty())
+        .collect()
+}
+
+fn md_block(b: &HelpBlock) -> Vec<String> {
+    match b {
+        HelpBlock::Text(x) => md_paragraphs(x),
+        HelpBlock::Boxed(x) => md_paragraphs(x)
+            .iter()
+            .map(|par| {
+                par.lines()
+                    .map(|line| format!("> {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect(),
+        HelpBlock::Plain(x) => {
+            let x = strip_escapes(x);
+            let x = x.trim_matches('\n');
+            if x.trim().is_empty() {
+                return Vec::new();
+            }
+            vec![format!("```\n{}\n```", x)]
+        }
+        HelpBlock::Table(rows) => {
+            let mut rows = table_rows(rows).into_iter().flatten().collect::<Vec<_>>();
+            let ncols = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+            if ncols == 0 {
+                return Vec::new();
+            }
+
+            // Use the first row as the header if it is all bold, as it usually is.
+
+            let is_header = rows[0].iter().all(|x| {
+                spans(x)
+                    .iter()
+                    .all(|(bold, s)| *bold || s.trim().is_empty())
+            });
+            let header = if is_header {
+                rows.remove(0)
+            } else {
+                vec![String::new(); ncols]
+            };
+            let md_row = |row: &[String]| {
+                let cells = (0..ncols)
+                    .map(|k| {
+                        let x = row.get(k).map(|x| cell(x)).unwrap_or_default();
+                        md_line(&x)
+                    })
+                    .collect::<Vec<_>>();
+                format!("| {} |", cells.join(" | "))
+            };
+            let mut s = vec![md_row(&header), format!("|{}", "---|".repeat(ncols))];
+            for row in rows.iter() {
+                s.push(md_row(row));
+            }
+            vec![s.join("\n")]
+        }
+    }
+}
+
+fn markdown_page(pages: &[HelpPage], page: &HelpPage) -> String {
+    let mut pars = vec![format!("# {}", command(&page.name))];
+    for b in page.blocks.iter() {
+        pars.append(&mut md_block(b));
+    }
+    pars.push("## See also".to_string());
+    if page.name == "main" {
+        let others = pages
+            .iter()
+            .filter(|p| p.name != "main")
+            .map(|p| {
+                format!(
+                    "- [{}]({}.md): {}",
+                    command(&p.name),
+                    file_stem(&p.name),
+                    md_escape(&summary(pages, &p.name))
+                )
+            })
+            .collect::<Vec<_>>();
+        pars.push(others.join("\n"));
+    } else {
+        pars.push("- [enclone](enclone.md)".to_string());
+    }
+    format!("{}\n", pars.join("\n\n"))
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+pub fn help_export(
+    args: &Vec<String>,
+    ctl: &EncloneControl,
+    h: &mut HelpDesk,
+) -> Result<(), String> {
+    if args.len() < 3 || args[1] != "help" || args[2] != "export" {
+        return Ok(());
+    }
+    if args.len() != 5 || (args[3] != "man" && args[3] != "markdown") {
+        return Err("\nUsage: enclone help export man|markdown DIR\n".to_string());
+    }
+    let (format, dir) = (&args[3], &args[4]);
+    create_dir_all(dir).map_err(|e| format!("\nUnable to create directory {}: {}.\n", dir, e))?;
+    let pages = help_pages(ctl)?;
+    for page in pages.iter() {
+        let (path, text) = if format == "man" {
+            (
+                format!("{}/{}.1", dir, file_stem(&page.name)),
+                man_page(&pages, page),
+            )
+        } else {
+            (
+                format!("{}/{}.md", dir, file_stem(&page.name)),
+                markdown_page(&pages, page),
+            )
+        };
+        write(&path, text).map_err(|e| format!("\nUnable to write {}: {}.\n", path, e))?;
+    }
+    h.ok = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_export() {
+        assert_eq!(
+            spans("a \\bold{POUT=filename} b \\red{c} \\bold{d"),
+            vec![
+                (false, "a ".to_string()),
+                (true, "POUT=filename".to_string()),
+                (false, " b ".to_string()),
+                (false, "c".to_string()),
+                (false, " \\bold{d".to_string()),
+            ]
+        );
+        assert_eq!(paragraphs("\n\nx\ny\n\n\nz\n").len(), 2);
+        let rows = [
+            vec!["\\bold{field}".to_string(), "\\bold{meaning}".to_string()],
+            vec!["\\hline".to_string(); 2],
+            vec!["a".to_string(), "first".to_string()],
+            vec!["".to_string(), "line".to_string()],
+            vec!["\\hline".to_string(); 2],
+        ];
+        let table = HelpBlock::Table(rows.to_vec());
+        assert_eq!(
+            md_block(&table),
+            vec!["| **field** | **meaning** |\n|---|---|\n| a | first line |".to_string()]
+        );
+        assert!(troff_block(&table).contains("_\nT{\na\nT}\tT{\nfirst line\nT}\n.TE\n"));
+        assert_eq!(
+            md_block(&HelpBlock::Text("x\n• cdr3_aa\n.x\n".to_string())),
+            vec!["x\n- cdr3\\_aa\\\n.x".to_string()]
+        );
+        assert_eq!(
+            troff_block(&HelpBlock::Text(".x \\bold{y}".to_string())),
+            ".PP\n\\&.x \\fBy\\fR\n"
+        );
+    }
+}
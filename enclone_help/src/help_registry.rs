@@ -0,0 +1,375 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Registry of the arguments and variables that are documented in the help pages, and
+// "enclone help search", which searches it.
+//
+// The registry is built by running all the help pages, exactly as for "enclone help all", but
+// with the HelpDesk in registry mode, so that instead of printing, it records each table row
+// that documents an argument or variable, together with the page it is on.  Thus the help pages
+// remain the one place where documentation is written.  The examples for an entry are the
+// example commands on any help page that use it.
+//
+// The type of each argument, and its default value, if it has one, are declared where the
+// argument is documented: in a table row, using an ArgDoc, from which the row is generated, or
+// for an argument that is only mentioned in text, using HelpDesk::declare_args, next to the text.
+
+use crate::help1::help1;
+use crate::help2::help2;
+use crate::help3::help3;
+use crate::help4::help4;
+use crate::help5::help5;
+use crate::help_utils::HelpDesk;
+use edit_distance::edit_distance;
+use enclone_core::defs::EncloneControl;
+use regex::Regex;
+
+// The type of the value of an argument.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgType {
+    Flag,
+    Integer,
+    Number,
+    Path,
+    List,
+    String,
+}
+
+impl ArgType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArgType::Flag => "flag",
+            ArgType::Integer => "integer",
+            ArgType::Number => "number",
+            ArgType::Path => "path",
+            ArgType::List => "list",
+            ArgType::String => "string",
+        }
+    }
+}
+
+// An argument, as documented in a table row: its name as shown, e.g. MIN_CELLS=n, the type of
+// its value, its default value, or empty, the values that it may take, or empty if they are not
+// restricted, and its description.  The default, if any, is shown at the end of the description.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ArgDoc {
+    pub name: &'static str,
+    pub value_type: ArgType,
+    pub default: &'static str,
+    pub values: &'static [&'static str],
+    pub description: &'static str,
+}
+
+impl ArgDoc {
+    pub const fn new(name: &'static str, value_type: ArgType, description: &'static str) -> Self {
+        ArgDoc {
+            name,
+            value_type,
+            default: "",
+            values: &[],
+            description,
+        }
+    }
+
+    pub const fn with_default(mut self, default: &'static str) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub const fn with_values(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+
+    // Return the name without its value, e.g. MIN_CELLS.
+
+    pub fn key(&self) -> &'static str {
+        self.name.split('=').next().unwrap()
+    }
+
+    // Return the description as shown in the help page.
+
+    pub fn text(&self) -> String {
+        if self.default.is_empty() {
+            self.description.to_string()
+        } else {
+            format!("{} (default = {})", self.description, self.default)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HelpEntry {
+    pub page: String,
+    // name, as shown in the help page, e.g. MIN_CELLS=n
+    pub name: String,
+    pub description: String,
+    // for an argument, the declared type of its value
+    pub value_type: Option<ArgType>,
+    // default value, as declared or given in the help page
+    pub default: String,
+    // the values that an argument may take, if they are restricted
+    pub values: Vec<String>,
+    pub examples: Vec<String>,
+}
+
+impl HelpEntry {
+    // Return the name without its value, e.g. MIN_CELLS.
+
+    pub fn key(&self) -> &str {
+        self.name.split('=').next().unwrap()
+    }
+
+    // Return true if an example command uses this entry, either as an argument, or as a
+    // variable in the value of an argument, e.g. LVARS=u,r.
+
+    fn used_by(&self, example: &str) -> bool {
+        let key = self.key();
+        example
+            .split(' ')
+            .skip(1)
+            .any(|arg| match arg.split_once('=') {
+                Some((k, v)) => k == key || v.split(',').any(|v| v == key),
+                None => arg == key,
+            })
+    }
+}
+
+// Build the registry.
+
+pub fn help_registry(ctl: &EncloneControl) -> Result<Vec<HelpEntry>, String> {
+    let args = vec!["enclone".to_string(), "help".to_string(), "all".to_string()];
+    let mut h = HelpDesk::new(true, true, true, false);
+    h.registry = true;
+    help1(&args, &mut h)?;
+    help2(&args, ctl, &mut h)?;
+    help3(&args, &mut h)?;
+    help4(&args, &mut h)?;
+    help5(&args, ctl, &mut h)?;
+    let mut entries = h.entries.clone();
+    for e in entries.iter_mut() {
+        for (_, x) in h.examples.iter() {
+            if e.used_by(x) && !e.examples.contains(x) {
+                e.examples.push(x.clone());
+            }
+        }
+    }
+
+    // An argument that is mentioned in text gets the type and default declared for it, either
+    // next to the text, or in a table row on another page.
+
+    for i in 0..entries.len() {
+        if entries[i].value_type.is_some() {
+            continue;
+        }
+        let key = entries[i].key().to_string();
+        let declared = h
+            .declared
+            .iter()
+            .find(|d| d.key() == key)
+            .map(|d| (Some(d.value_type), d.default.to_string(), Vec::new()))
+            .or_else(|| {
+                entries
+                    .iter()
+                    .find(|e| e.key() == key && e.value_type.is_some())
+                    .map(|e| (e.value_type, e.default.clone(), e.values.clone()))
+            });
+        if let Some((value_type, default, values)) = declared {
+            let e = &mut entries[i];
+            e.value_type = value_type;
+            if e.default.is_empty() {
+                e.default = default;
+            }
+            e.values = values;
+        }
+    }
+    Ok(entries)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Execute "enclone help search <regex>", which lists the entries whose name or description
+// matches the regular expression.
+
+pub fn help_search(
+    args: &Vec<String>,
+    ctl: &EncloneControl,
+    h: &mut HelpDesk,
+) -> Result<(), String> {
+    if args.len() < 3 || args[1] != "help" || args[2] != "search" {
+        return Ok(());
+    }
+    if args.len() != 4 {
+        return Err("\nUsage: enclone help search <regex>\n".to_string());
+    }
+    let pattern = &args[3];
+    let re = Regex::new(pattern).map_err(|_| {
+        format!(
+            "\nThe search pattern {} is not a valid regular expression.\n",
+            pattern
+        )
+    })?;
+    let entries = help_registry(ctl)?
+        .into_iter()
+        .filter(|e| re.is_match(&e.name) || re.is_match(&e.description))
+        .collect::<Vec<_>>();
+    h.begin_doc("search")?;
+    if entries.is_empty() {
+        h.print_plain(&format!(
+            "\nNothing in the help pages matches {}.  Please type \"enclone\" to see the help \
+             options.\n\n",
+            pattern
+        ))?;
+        h.end_doc();
+        return Ok(());
+    }
+
+    // Print one table for each page, keeping the order of the help pages.
+
+    let mut pages = Vec::<String>::new();
+    for e in entries.iter() {
+        if !pages.contains(&e.page) {
+            pages.push(e.page.clone());
+        }
+    }
+    h.print_plain("\n")?;
+    for page in pages.iter() {
+        h.print(&format!("\\bold{{enclone help {}}}\n", page))?;
+        h.rows.clear();
+        let page_entries = entries.iter().filter(|e| e.page == *page);
+        let width = page_entries.clone().map(|e| e.name.chars().count()).max();
+        let max_desc = 90_usize.saturating_sub(width.unwrap()).max(30);
+        for (i, e) in page_entries.enumerate() {
+            if i > 0 {
+                h.rows.push(vec!["\\hline".to_string(); 2]);
+            }
+            h.rows
+                .push(vec![e.name.clone(), abbreviate(&e.description, max_desc)]);
+        }
+        h.print_tab2()?;
+        h.print_plain("\n")?;
+    }
+    h.end_doc();
+    Ok(())
+}
+
+// Shorten a description to at most n characters, breaking at a space.
+
+fn abbreviate(x: &str, n: usize) -> String {
+    if x.chars().count() <= n {
+        return x.to_string();
+    }
+    let mut s = String::new();
+    for word in x.split(' ') {
+        if s.chars().count() + word.chars().count() + 4 > n {
+            break;
+        }
+        if !s.is_empty() {
+            s.push(' ');
+        }
+        s += word;
+    }
+    format!("{} ...", s)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Find the documented argument or variable that is closest to an unrecognized one, if there is
+// one that is close enough to be a plausible misspelling.  Return it, and the help page it is on.
+
+pub fn did_you_mean(arg: &str, registry: &[HelpEntry]) -> Option<(String, String)> {
+    let key = arg.split('=').next().unwrap();
+    if registry.iter().any(|e| e.key() == key) {
+        return None;
+    }
+    let n = key.chars().count();
+    let best = registry
+        .iter()
+        .map(|e| {
+            (
+                edit_distance(&key.to_uppercase(), &e.key().to_uppercase()),
+                e,
+            )
+        })
+        .min_by_key(|x| x.0);
+    match best {
+        Some((d, e)) if d < n && d <= (n / 3).max(2) => Some((e.key().to_string(), e.page.clone())),
+        _ => None,
+    }
+}
+
+// Given an error message from argument processing, if it complains about an unrecognized
+// argument, add a suggestion for what might have been meant.
+
+pub fn add_suggestion(err: String, args: &[String], ctl: &EncloneControl) -> String {
+    let lower = err.to_lowercase();
+    if !lower.contains("unrecognized") && !lower.contains("not recognized") {
+        return err;
+    }
+    let arg = match args.iter().skip(1).find(|x| err.contains(x.as_str())) {
+        Some(arg) => arg,
+        None => return err,
+    };
+    let registry = match help_registry(ctl) {
+        Ok(registry) => registry,
+        Err(_) => return err,
+    };
+    match did_you_mean(arg, &registry) {
+        Some((name, page)) => format!(
+            "{}\nDid you mean {}?  See \"enclone help {}\".\n",
+            err.trim_end(),
+            name,
+            page
+        ),
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::help_utils::is_arg;
+
+    #[test]
+    fn test_did_you_mean() {
+        let registry = ["MIN_CELLS=n", "MAX_CELLS=n", "PLAIN", "cdr3_aa"]
+            .iter()
+            .map(|x| HelpEntry {
+                page: "filter".to_string(),
+                name: x.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let suggest = |x: &str| did_you_mean(x, &registry).map(|x| x.0);
+        assert_eq!(suggest("MIN_CELL=3"), Some("MIN_CELLS".to_string()));
+        assert_eq!(suggest("PLANE"), Some("PLAIN".to_string()));
+        assert_eq!(suggest("CDR3_AA"), Some("cdr3_aa".to_string()));
+        assert_eq!(suggest("PLAIN"), None);
+        assert_eq!(suggest("FROG"), None);
+        assert!(registry[0].used_by("enclone BCR=123085 MIN_CELLS=5"));
+        assert!(registry[3].used_by("enclone BCR=123085 CVARS=u,cdr3_aa"));
+        assert!(!registry[2].used_by("enclone BCR=123085 PLAINER"));
+    }
+
+    #[test]
+    fn test_declared_types() {
+        let registry = help_registry(&EncloneControl::default()).unwrap();
+        for e in registry.iter() {
+            if is_arg(e.key()) {
+                assert!(e.value_type.is_some(), "no type is declared for {}", e.name);
+            }
+        }
+        let find = |x: &str| registry.iter().find(|e| e.key() == x).unwrap();
+        assert_eq!(find("MIN_GROUP").value_type, Some(ArgType::Integer));
+        assert_eq!(find("MIN_GROUP").default, "1");
+        assert_eq!(
+            find("MIN_GROUP").description,
+            "minimum number of clonotypes in group to print (default = 1)"
+        );
+        assert_eq!(find("POUT").value_type, Some(ArgType::Path));
+        assert_eq!(find("AMINO").default, "cdr3,var,share,donor");
+        assert_eq!(find("DIFF_STYLE").values, vec!["C1", "C2"]);
+        assert_eq!(find("NCROSS").value_type, Some(ArgType::Flag));
+    }
+}
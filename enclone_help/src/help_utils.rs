@@ -1,5 +1,7 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 
+use crate::help_export::{HelpBlock, HelpPage};
+use crate::help_registry::{ArgDoc, ArgType, HelpEntry};
 use ansi_escape::ansi_to_html::convert_text_with_ansi_escapes_to_html;
 use ansi_escape::{
     emit_blue_escape, emit_bold_escape, emit_end_escape, emit_green_escape, emit_red_escape,
@@ -8,7 +10,7 @@ use ansi_escape::{
 use enclone_core::print_tools::emit_codon_color_escape;
 use io_utils::fwrite;
 use std::io::Write;
-use string_utils::{stringme, strme};
+use string_utils::{stringme, strme, TextUtils};
 use tables::print_tabular_vbox;
 
 pub fn font_face_in_css() -> String {
@@ -44,6 +46,17 @@ pub struct HelpDesk {
     pub log: Vec<u8>,
     pub title: String,
     pub ok: bool,
+    // If registry is set, record the documented arguments and variables, and the example
+    // commands, rather than printing.
+    pub registry: bool,
+    pub entries: Vec<HelpEntry>,
+    pub examples: Vec<(String, String)>,
+    // the arguments that are documented only in text, with their types and defaults
+    pub declared: Vec<ArgDoc>,
+    noting: bool,
+    // If export is set, record the content of each page, rather than printing.
+    pub export: bool,
+    pub pages: Vec<HelpPage>,
 }
 
 impl HelpDesk {
@@ -57,12 +70,163 @@ impl HelpDesk {
             log: Vec::<u8>::new(),
             title: String::new(),
             ok: false,
+            registry: false,
+            entries: Vec::<HelpEntry>::new(),
+            examples: Vec::<(String, String)>::new(),
+            declared: Vec::<ArgDoc>::new(),
+            noting: false,
+            export: false,
+            pages: Vec::<HelpPage>::new(),
         }
     }
+
+    // Record a block of content on the current page, for export.  Consecutive text is merged,
+    // since a paragraph may be printed in pieces.
+
+    fn record(&mut self, b: HelpBlock) {
+        if let Some(page) = self.pages.last_mut() {
+            if let (Some(HelpBlock::Text(x)), HelpBlock::Text(y)) = (page.blocks.last_mut(), &b) {
+                *x += y;
+            } else {
+                page.blocks.push(b);
+            }
+        }
+    }
+
+    // Return the name of the current help page.
+
+    pub fn page(&self) -> String {
+        if self.title == "enclone help " {
+            "main".to_string()
+        } else {
+            self.title.after("enclone help ").to_string()
+        }
+    }
+
+    // Record a table row for the registry.  A row whose first entry is empty continues the
+    // description of the previous entry.  Rows that name something other than a single argument
+    // or variable, such as table headers, are not recorded.
+
+    fn note(&mut self, x1: &str, x2: &str) {
+        if !self.registry {
+            return;
+        }
+        let (name, desc) = (self.print_to(x1), self.print_to(x2));
+        let (name, desc) = (name.trim(), desc.trim());
+        if name.is_empty() {
+            if self.noting && !desc.is_empty() {
+                let e = self.entries.last_mut().unwrap();
+                e.description = format!("{} {}", e.description, desc).trim().to_string();
+            }
+        } else if x1.starts_with('\\') || name.contains(' ') {
+            self.noting = false;
+        } else {
+            self.entries.push(HelpEntry {
+                page: self.page(),
+                name: name.to_string(),
+                description: desc.to_string(),
+                ..Default::default()
+            });
+            self.noting = true;
+        }
+    }
+
+    // Record a three-column table row for the registry, in which the middle column is the
+    // default value.
+
+    fn note3(&mut self, x1: &str, x2: &str, x3: &str) {
+        self.note(x1, x3);
+        if self.registry && self.noting && !x1.is_empty() {
+            self.entries.last_mut().unwrap().default = self.print_to(x2).trim().to_string();
+        }
+    }
+
+    // Record the arguments that are shown in bold in text, e.g. \bold{POUT=filename}, for the
+    // registry, unless they are already documented on the page.  The description is the
+    // sentence in which the argument appears.
+
+    fn note_text(&mut self, x: &str) {
+        if !self.registry {
+            return;
+        }
+        let plain = self.print_to(x).replace('\n', " ");
+        for y in x.split("\\bold{").skip(1) {
+            let name = y.split('}').next().unwrap();
+            if !is_arg(name) || y == name {
+                continue;
+            }
+            let key = name.split('=').next().unwrap();
+            let page = self.page();
+            if self
+                .entries
+                .iter()
+                .any(|e| e.page == page && e.key() == key)
+            {
+                continue;
+            }
+            let sentence = plain
+                .split(".  ")
+                .find(|s| s.contains(name))
+                .unwrap_or(name)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.entries.push(HelpEntry {
+                page,
+                name: name.to_string(),
+                description: sentence,
+                ..Default::default()
+            });
+        }
+        self.noting = false;
+    }
+
+    // Record the type, default and values of the argument in the last table row, for the
+    // registry.
+
+    fn note_arg(&mut self, a: &ArgDoc) {
+        if self.registry && self.noting {
+            let e = self.entries.last_mut().unwrap();
+            e.value_type = Some(a.value_type);
+            e.default = a.default.to_string();
+            e.values = a.values.iter().map(|v| v.to_string()).collect();
+        }
+    }
+
+    // Declare the types and defaults of arguments that are documented in text, rather than in a
+    // table row.  Each is given as (name, type, default), where the default may be empty.
+
+    pub fn declare_args(&mut self, args: &[(&'static str, ArgType, &'static str)]) {
+        if self.registry {
+            for &(name, value_type, default) in args.iter() {
+                self.declared
+                    .push(ArgDoc::new(name, value_type, "").with_default(default));
+            }
+        }
+    }
+
+    // Document an argument in a table row, as for doc, ldoc and docf2.
+
+    pub fn doc_arg(&mut self, a: &ArgDoc) {
+        self.doc(a.name, &a.text());
+        self.note_arg(a);
+    }
+    pub fn ldoc_arg(&mut self, a: &ArgDoc) {
+        self.ldoc(a.name, &a.text());
+        self.note_arg(a);
+    }
+    pub fn docf2_arg(&mut self, a: &ArgDoc, n2: usize) -> Result<(), String> {
+        self.docf2(a.name, &a.text(), n2)?;
+        self.note_arg(a);
+        Ok(())
+    }
+
     pub fn doc(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         self.rows.push(vec![x1.to_string(), x2.to_string()]);
     }
     pub fn doc2(&mut self, x2: &str) {
+        self.note("", x2);
         self.rows.push(vec!["".to_string(), x2.to_string()]);
     }
 
@@ -115,10 +279,12 @@ impl HelpDesk {
     }
 
     pub fn ldoc(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         self.rows.push(vec!["\\hline".to_string(); 2]);
         self.rows.push(vec![x1.to_string(), x2.to_string()]);
     }
     pub fn doc3(&mut self, x1: &str, x2: &str, x3: &str) {
+        self.note3(x1, x2, x3);
         self.rows.push(vec![
             self.print_to(x1),
             self.print_to(x2),
@@ -126,18 +292,22 @@ impl HelpDesk {
         ]);
     }
     pub fn ldoc3(&mut self, x1: &str, x2: &str, x3: &str) {
+        self.note3(x1, x2, x3);
         self.rows.push(vec!["\\hline".to_string(); 3]);
         self.rows
             .push(vec![x1.to_string(), x2.to_string(), x3.to_string()]);
     }
     pub fn docpr(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         self.rows.push(vec![self.print_to(x1), self.print_to(x2)]);
     }
     pub fn ldocpr(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         self.rows.push(vec!["\\hline".to_string(); 2]);
         self.rows.push(vec![self.print_to(x1), self.print_to(x2)]);
     }
     pub fn ldoc3pr(&mut self, x1: &str, x2: &str, x3: &str) {
+        self.note3(x1, x2, x3);
         self.rows.push(vec!["\\hline".to_string(); 3]);
         self.rows.push(vec![
             self.print_to(x1),
@@ -146,6 +316,7 @@ impl HelpDesk {
         ]);
     }
     pub fn doc_red(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         if !self.plain {
             let r1 = format!("[31m{}[0m", x1);
             let r2 = format!("[31m{}[0m", x2);
@@ -155,6 +326,7 @@ impl HelpDesk {
         }
     }
     pub fn ldoc_red(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         self.rows.push(vec!["\\hline".to_string(); 2]);
         if !self.plain {
             let r1 = format!("[31m{}[0m", x1);
@@ -165,6 +337,7 @@ impl HelpDesk {
         }
     }
     pub fn doc_greenish(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         if !self.plain {
             let r1 = format!("[38;5;36m{}[0m", x1);
             let r2 = format!("[38;5;36m{}[0m", x2);
@@ -174,6 +347,7 @@ impl HelpDesk {
         }
     }
     pub fn ldoc_greenish(&mut self, x1: &str, x2: &str) {
+        self.note(x1, x2);
         self.rows.push(vec!["\\hline".to_string(); 2]);
         if !self.plain {
             let r1 = format!("[38;5;36m{}[0m", x1);
@@ -214,12 +388,20 @@ impl HelpDesk {
         Ok(())
     }
     pub fn print_tab2(&mut self) -> Result<(), String> {
+        if self.export {
+            self.record(HelpBlock::Table(self.rows.clone()));
+            return Ok(());
+        }
         let mut log = String::new();
         print_tabular_vbox(&mut log, &self.rows, 2, b"l|l".as_ref(), false, false);
         self.print_plain(&log.to_string())?;
         Ok(())
     }
     pub fn print_tab3(&mut self) -> Result<(), String> {
+        if self.export {
+            self.record(HelpBlock::Table(self.rows.clone()));
+            return Ok(());
+        }
         let mut log = String::new();
         print_tabular_vbox(&mut log, &self.rows, 2, b"l|l|l".as_ref(), false, false);
         self.print_plain(&log.to_string())?;
@@ -228,6 +410,13 @@ impl HelpDesk {
     pub fn begin_doc(&mut self, title: &str) -> Result<(), String> {
         self.title = format!("enclone help {}", title);
         self.rows.clear();
+        if self.export {
+            self.pages.push(HelpPage {
+                name: self.page(),
+                blocks: Vec::new(),
+            });
+            return Ok(());
+        }
         if self.help_all {
             let mut log = Vec::<u8>::new();
             if !self.plain {
@@ -274,6 +463,11 @@ impl HelpDesk {
         self.ok = true;
     }
     pub fn print_with_box(&mut self, x: &str, bold_box: bool) -> Result<(), String> {
+        self.note_text(x);
+        if self.export {
+            self.record(HelpBlock::Boxed(x.to_string()));
+            return Ok(());
+        }
         let y = self.print_to(x);
         let mut rows = Vec::<Vec<String>>::new();
         let lines = y.split('\n').collect::<Vec<&str>>();
@@ -286,13 +480,26 @@ impl HelpDesk {
         Ok(())
     }
     pub fn print(&mut self, x: &str) -> Result<(), String> {
+        self.note_text(x);
+        if self.export {
+            self.record(HelpBlock::Text(x.to_string()));
+            return Ok(());
+        }
         self.print_plain(&self.print_to(x))?;
         Ok(())
     }
     pub fn print_plain_unchecked(&mut self, x: &str) {
+        if self.export {
+            self.record(HelpBlock::Plain(x.to_string()));
+            return;
+        }
         fwrite!(self.log, "{}", &x);
     }
     pub fn print_plain(&mut self, x: &str) -> Result<(), String> {
+        if self.export {
+            self.record(HelpBlock::Plain(x.to_string()));
+            return Ok(());
+        }
         if !self.long_help {
             let mut count = 0;
             let mut escaped = false;
@@ -320,10 +527,21 @@ impl HelpDesk {
                 }
             }
         }
+        if self.registry {
+            for line in x.lines() {
+                let line = line.trim();
+                if line.starts_with("enclone ") && line.split(' ').skip(1).any(is_arg) {
+                    self.examples.push((self.page(), line.to_string()));
+                }
+            }
+        }
         fwrite!(self.log, "{}", &x);
         Ok(())
     }
     pub fn dump(&self) {
+        if self.registry || self.export {
+            return;
+        }
         if !self.html {
             print!("{}", strme(&self.log));
         } else {
@@ -344,6 +562,17 @@ impl HelpDesk {
     }
 }
 
+// Determine if a string looks like an argument, e.g. BCR=123085 or PLAIN.
+
+pub fn is_arg(x: &str) -> bool {
+    let key = x.split('=').next().unwrap();
+    key.len() > 1
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
 // This encodes the color codes for each possible codon of a given amino acid
 // that could be found in a BCR or TCR sequence.
 
@@ -400,7 +629,11 @@ impl HelpDesk {
     /// • Change \red{x} into a red string by issuing appropriate escape characters.
     /// • Change \boldred{x} into a bold red string by issuing appropriate escape characters.
     /// • Fold at 99 characters.
+    /// For export, the string is returned unchanged, and rendered later.
     fn print_to(&self, x: &str) -> String {
+        if self.export {
+            return x.to_string();
+        }
         let mut y = Vec::<char>::new();
         for c in x.chars() {
             y.push(c);
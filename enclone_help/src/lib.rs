@@ -1,8 +1,14 @@
 // Copyright (c) 2021 10x Genomics, Inc. All rights reserved.
 
+pub mod completions;
 pub mod help1;
 pub mod help2;
 pub mod help3;
 pub mod help4;
 pub mod help5;
+pub mod help_catalog;
+pub mod help_examples;
+pub mod help_export;
+pub mod help_registry;
 pub mod help_utils;
+pub mod tail_args;
@@ -0,0 +1,176 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+use crate::help_registry::{ArgDoc, ArgType};
+
+// The arguments that are processed by enclone_tail, rather than by proc_args.  These tables are
+// used both to parse them (see enclone_tail/src/tail_opts.rs) and to document them, on the
+// parseable page (ANNDATA_ARGS) and the display page (PLOT_ARGS, in which each group of related
+// arguments is shown as one block), so an argument is added by adding it here, and storing its
+// value in TailOpts::set.
+
+pub static ANNDATA_ARGS: [ArgDoc; 2] = [
+    ArgDoc::new(
+        "ANNDATA=stem",
+        ArgType::Path,
+        "write per-cell obs table to stem.csv and stem.h5",
+    ),
+    ArgDoc::new(
+        "ANNDATA_VARS=x1,...,xn",
+        ArgType::List,
+        "variables to include in the ANNDATA obs table",
+    ),
+];
+
+pub static PLOT_ARGS: [&[ArgDoc]; 8] = [
+    &[
+        ArgDoc::new(
+            "PLOT_VGENE=filename",
+            ArgType::Path,
+            "V gene usage bar chart",
+        ),
+        ArgDoc::new(
+            "PLOT_JGENE=filename",
+            ArgType::Path,
+            "J gene usage bar chart",
+        ),
+        ArgDoc::new(
+            "PLOT_VJ_HEATMAP=filename",
+            ArgType::Path,
+            "V/J gene pairing heatmap",
+        ),
+        ArgDoc::new(
+            "PLOT_HL_HEATMAP=filename",
+            ArgType::Path,
+            "heavy/light gene pairing heatmap",
+        ),
+        ArgDoc::new(
+            "PLOT_GENE_WEIGHT=cells|clonotypes",
+            ArgType::String,
+            "count cells or clonotypes in gene plots",
+        )
+        .with_default("cells")
+        .with_values(&["cells", "clonotypes"]),
+        ArgDoc::new(
+            "PLOT_GENE_BY=dataset|origin",
+            ArgType::String,
+            "split gene usage bar charts by dataset or origin",
+        )
+        .with_default("dataset")
+        .with_values(&["dataset", "origin"]),
+        ArgDoc::new(
+            "PLOT_GENE_CHAIN=chain",
+            ArgType::String,
+            "restrict gene usage plots to one chain type",
+        )
+        .with_values(&["IGH", "IGK", "IGL", "TRA", "TRB"]),
+    ],
+    &[
+        ArgDoc::new(
+            "SPECTRATYPE=filename",
+            ArgType::Path,
+            "CDR3 length histograms, faceted by chain type and origin",
+        ),
+        ArgDoc::new(
+            "SPECTRATYPE_CSV=filename",
+            ArgType::Path,
+            "write the table underlying SPECTRATYPE",
+        ),
+        ArgDoc::new(
+            "SPECTRATYPE_BY=origin|dataset|none",
+            ArgType::String,
+            "how to split SPECTRATYPE histograms",
+        )
+        .with_default("origin")
+        .with_values(&["origin", "dataset", "none"]),
+        ArgDoc::new(
+            "SPECTRATYPE_COLOR=vfamily",
+            ArgType::String,
+            "stack SPECTRATYPE histogram bars by V gene family",
+        )
+        .with_values(&["vfamily"]),
+    ],
+    &[
+        ArgDoc::new("LOGO=filename", ArgType::Path, "sequence logos for CDR3s"),
+        ArgDoc::new(
+            "LOGO_BY=all|group|clonotype",
+            ArgType::String,
+            "make one logo for all CDR3s, or one for each group or clonotype",
+        )
+        .with_default("all")
+        .with_values(&["all", "group", "clonotype"]),
+        ArgDoc::new(
+            "PEER_LOGO=filename",
+            ArgType::Path,
+            "sequence logos for the peer groups of the V genes that are shown",
+        ),
+    ],
+    &[
+        ArgDoc::new(
+            "ALLUVIAL=filename",
+            ArgType::Path,
+            "clonotype frequencies across origins, drawn as ribbons",
+        ),
+        ArgDoc::new(
+            "ALLUVIAL_CSV=filename",
+            ArgType::Path,
+            "write the table underlying ALLUVIAL",
+        ),
+        ArgDoc::new(
+            "ALLUVIAL_TOP=n",
+            ArgType::Integer,
+            "number of clonotypes to show in ALLUVIAL",
+        )
+        .with_default("10"),
+        ArgDoc::new(
+            "ALLUVIAL_ORDER=o1,...,on",
+            ArgType::List,
+            "origins to show in ALLUVIAL, in order",
+        ),
+    ],
+    &[
+        ArgDoc::new(
+            "HTML_PLOT=filename",
+            ArgType::Path,
+            "honeycomb plot as an interactive html page",
+        ),
+        ArgDoc::new(
+            "HTML_PLOT_VARS=x1,...,xn",
+            ArgType::List,
+            "per-cell variables to show when hovering in HTML_PLOT",
+        ),
+    ],
+    &[
+        ArgDoc::new(
+            "PLOT_XY_FACET=var",
+            ArgType::String,
+            "split PLOT_XY into panels, one for each value of var",
+        ),
+        ArgDoc::new(
+            "PLOT_XY_FIT",
+            ArgType::Flag,
+            "add a least squares line and R² to PLOT_XY",
+        ),
+        ArgDoc::new(
+            "PLOT_XY_DENSITY",
+            ArgType::Flag,
+            "add density contours to PLOT_XY",
+        ),
+    ],
+    &[ArgDoc::new(
+        "PLOTS=spec",
+        ArgType::String,
+        "an additional honeycomb, xy or similarity matrix plot; may be repeated",
+    )],
+    &[ArgDoc::new(
+        "PLOT_LAYOUT=random|frontchain[,seed=n][,stats]",
+        ArgType::String,
+        "circle packing layout for honeycomb plots",
+    )
+    .with_default("random")],
+];
+
+pub fn tail_args() -> impl Iterator<Item = &'static ArgDoc> {
+    ANNDATA_ARGS
+        .iter()
+        .chain(PLOT_ARGS.iter().flat_map(|g| g.iter()))
+}
@@ -15,8 +15,10 @@ use enclone_args::proc_args_check::{check_gvars, check_lvars, check_pcols, get_k
 use enclone_core::cell_color::CellColor;
 use enclone_core::defs::EncloneControl;
 use enclone_core::enclone_structs::*;
+use enclone_help::completions::completions;
 use enclone_stuff::start::*;
 use enclone_stuff::vars::match_vars;
+use enclone_tail::tail_opts::TailOpts;
 use enclone_vars::decode_arith;
 use expr_tools::vars_of_node;
 use io_utils::{open_for_read, open_userfile_for_read, path_exists};
@@ -29,7 +31,7 @@ use vector_utils::{bin_member, next_diff, unique_sort};
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
 pub fn main_enclone(args: &Vec<String>) -> Result<(), String> {
-    let setup = main_enclone_setup(args)?;
+    let (setup, tail_opts) = main_enclone_setup(args)?;
     if setup.tall.is_none() {
         return Ok(Default::default());
     }
@@ -37,10 +39,10 @@ pub fn main_enclone(args: &Vec<String>) -> Result<(), String> {
     if setup.tall.is_none() {
         return Ok(Default::default());
     }
-    main_enclone_stop(&setup, &exacts, fate)
+    main_enclone_stop(&setup, &tail_opts, &exacts, fate)
 }
 
-pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
+pub fn main_enclone_setup(args: &Vec<String>) -> Result<(EncloneSetup, TailOpts), String> {
     let tall = Instant::now();
 
     // Test for enclone --check.
@@ -74,7 +76,7 @@ pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
         println!("\nCheck complete: it appears that your install of enclone was successful!\n");
         print!("Your version is: ");
         println!("{}\n", env!("CARGO_PKG_VERSION"));
-        return Ok(EncloneSetup::default());
+        return Ok(Default::default());
     }
 
     // Set up stuff, read args, etc.
@@ -113,16 +115,30 @@ pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
     }
     if args_orig.len() == 2 && (args_orig[1] == "version" || args_orig[1] == "--version") {
         println!("{}", env!("CARGO_PKG_VERSION"));
-        return Ok(EncloneSetup::default());
+        return Ok(Default::default());
+    }
+    if args_orig.len() >= 2 && args_orig[1] == "completions" {
+        // PRE has already been determined by critical_args, from the defaults, PRE, ENCLONE_PRE
+        // and PREPOST, so those are the only other arguments allowed.
+
+        let pre_arg = |x: &String| x.starts_with("PRE=") || x.starts_with("PREPOST=");
+        if args_orig.len() < 3 || !args_orig[3..].iter().all(pre_arg) {
+            return Err(
+                "\nUsage: enclone completions bash|zsh|fish [PRE=...] [PREPOST=...]\n".to_string(),
+            );
+        }
+        print!("{}", completions(&args_orig[2], &ctl)?);
+        return Ok(Default::default());
     }
     if ctl.gen_opt.evil_eye {
         println!("calling perf_stats, before setup");
     }
 
     let mut argsx = Vec::<String>::new();
-    setup(&mut ctl, &args, &mut argsx, &args_orig)?;
+    let mut tail_opts = TailOpts::default();
+    setup(&mut ctl, &args, &mut argsx, &args_orig, &mut tail_opts)?;
     if ctl.gen_opt.split {
-        return Ok(EncloneSetup::default());
+        return Ok(Default::default());
     }
     let mut argsy = Vec::<String>::new();
     for i in 0..args_orig.len() {
@@ -138,7 +154,7 @@ pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
         }
     }
     if argsy.len() == 1 || (argsy.len() > 1 && (argsy[1] == "help" || argsy[1] == "--help")) {
-        return Ok(EncloneSetup::default());
+        return Ok(Default::default());
     }
 
     // Dump internal ids.
@@ -151,7 +167,7 @@ pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
             }
             x.sort_unstable();
             println!("\n{}\n", x.iter().format(","));
-            return Ok(EncloneSetup::default());
+            return Ok(Default::default());
         }
     }
 
@@ -243,6 +259,7 @@ pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
         }
     }
     check_pcols(&ctl, &gex_info, &var_def_vars, ctl.parseable_opt.pbarcode)?;
+    check_pcols(&ctl, &gex_info, &tail_opts.vars(), true)?;
 
     // Check DVARS.
 
@@ -397,11 +414,14 @@ pub fn main_enclone_setup(args: &Vec<String>) -> Result<EncloneSetup, String> {
 
     // Return.
 
-    Ok(EncloneSetup {
-        ctl,
-        refdata,
-        ann: ann.to_string(),
-        gex_info,
-        tall: Some(tall),
-    })
+    Ok((
+        EncloneSetup {
+            ctl,
+            refdata,
+            ann: ann.to_string(),
+            gex_info,
+            tall: Some(tall),
+        },
+        tail_opts,
+    ))
 }
@@ -14,7 +14,11 @@ use enclone_help::help2::help2;
 use enclone_help::help3::help3;
 use enclone_help::help4::help4;
 use enclone_help::help5::help5;
+use enclone_help::help_catalog::help_catalog;
+use enclone_help::help_export::help_export;
+use enclone_help::help_registry::{add_suggestion, help_search};
 use enclone_help::help_utils::HelpDesk;
+use enclone_tail::tail_opts::{proc_tail_arg, TailOpts};
 use io_utils::{open_for_read, path_exists};
 use itertools::Itertools;
 use std::env;
@@ -134,6 +138,7 @@ pub fn setup(
     args: &Vec<String>,
     argsx: &mut Vec<String>,
     args_orig: &Vec<String>,
+    tail_opts: &mut TailOpts,
 ) -> Result<(), String> {
     let mut using_pager = false;
 
@@ -225,6 +230,9 @@ pub fn setup(
         help2(&argsx, ctl, &mut h)?;
         help3(&argsx, &mut h)?;
         help4(&argsx, &mut h)?;
+        help_search(&argsx, ctl, &mut h)?;
+        help_catalog(&argsx, ctl, &mut h)?;
+        help_export(&argsx, ctl, &mut h)?;
         help5(&argsx, ctl, &mut h)?;
         if argsx.len() == 1 || (argsx.len() > 1 && argsx[1] == "help") {
             return Ok(());
@@ -259,9 +267,20 @@ pub fn setup(
     }
     USING_PAGER.store(using_pager, SeqCst);
 
-    // Process args (and set defaults for them).
+    // Process args (and set defaults for them).  Arguments that are handled by enclone_tail are
+    // removed first.
 
-    proc_args(ctl, args)?;
+    let mut args_rest = Vec::<String>::new();
+    for arg in args.iter() {
+        if !proc_tail_arg(arg, tail_opts)? {
+            args_rest.push(arg.clone());
+        }
+    }
+    tail_opts.check()?;
+    if let Err(err) = proc_args(ctl, &args_rest) {
+        return Err(add_suggestion(err, &args_rest, ctl));
+    }
+    tail_opts.check_ctl(ctl)?;
     if ctl.gen_opt.split {
         return Ok(());
     }
@@ -8,6 +8,7 @@ use enclone_process::process_clonotypes::process_clonotypes;
 use enclone_tail::grouper::grouper;
 use enclone_tail::print_clonotypes::{EncloneOrbitProcessor, PrintClonotypesResult};
 use enclone_tail::tail::tail_code;
+use enclone_tail::tail_opts::TailOpts;
 use io_utils::open_for_read;
 use stats_utils::percent_ratio;
 use std::{collections::HashMap, io::BufRead};
@@ -18,6 +19,7 @@ use vector_utils::*;
 
 pub fn main_enclone_stop(
     setup: &EncloneSetup,
+    tail_opts: &TailOpts,
     exacts: &EncloneExacts,
     fate: Vec<BarcodeFates>,
 ) -> Result<(), String> {
@@ -51,7 +53,7 @@ pub fn main_enclone_stop(
         }
     }
 
-    let mut proc = EncloneOrbitProcessor::new(setup, &exacts.vdj_cells);
+    let mut proc = EncloneOrbitProcessor::new(setup, tail_opts, &exacts.vdj_cells);
 
     process_clonotypes(setup, exacts, &gex_readers, &fate, &mut proc)?;
 
@@ -167,6 +169,7 @@ pub fn main_enclone_stop(
         &rsi,
         exact_clonotypes,
         ctl,
+        tail_opts,
         &mut out_datas,
         join_info,
         gex_info,
@@ -0,0 +1,16 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+#![no_main]
+
+use enclone_tail::display_tree::display_tree;
+use enclone_tail_fuzz::{tree, TreeInput};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|x: (TreeInput, u8)| {
+    let (names, r, edges) = tree(&x.0);
+    let t = display_tree(&names, &edges, r, x.1 as usize);
+
+    // There should be one line per vertex.
+
+    assert_eq!(t.lines().count(), names.len());
+});
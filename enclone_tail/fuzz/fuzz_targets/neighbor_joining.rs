@@ -0,0 +1,17 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+#![no_main]
+
+use enclone_tail::neighbor::neighbor_joining;
+use enclone_tail_fuzz::{dist_matrix, DistInput};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|x: DistInput| {
+    let d = dist_matrix(&x);
+    let n = d.len();
+    let edges = neighbor_joining(&d);
+    assert_eq!(edges.len(), if n == 1 { 0 } else { 2 * n - 3 });
+    for e in edges.iter() {
+        assert!(e.2 >= 0.0);
+    }
+});
@@ -0,0 +1,17 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+#![no_main]
+
+use enclone_tail::newick::newick;
+use enclone_tail_fuzz::{tree, TreeInput};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|x: TreeInput| {
+    let (names, r, edges) = tree(&x);
+    let edges = edges
+        .iter()
+        .map(|e| (e.0, e.1, format!("{:.2}", e.2)))
+        .collect::<Vec<_>>();
+    let t = newick(&names, r, &edges);
+    assert!(t.ends_with(';'));
+});
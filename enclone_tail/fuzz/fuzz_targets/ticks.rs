@@ -0,0 +1,20 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+#![no_main]
+
+use enclone_tail::ticks::ticks;
+use libfuzzer_sys::fuzz_target;
+
+// The range must be finite, and is bounded here so that the tick values are representable.
+
+fuzz_target!(|x: (f32, f32, u8)| {
+    let (mut low, mut high, max_ticks) = (x.0, x.1, x.2 as usize);
+    if !low.is_finite() || !high.is_finite() || low.abs() > 1e30 || high.abs() > 1e30 {
+        return;
+    }
+    if low > high {
+        std::mem::swap(&mut low, &mut high);
+    }
+    let t = ticks(low, high, max_ticks, false);
+    assert!(t.len() <= max_ticks.max(1));
+});
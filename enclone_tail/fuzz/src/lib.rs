@@ -0,0 +1,80 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Inputs for the fuzz targets.  The functions being fuzzed assume that their inputs are valid,
+// e.g. that a tree is a tree, so rather than fuzzing with arbitrary inputs, we make valid inputs
+// from arbitrary data.
+
+use arbitrary::Arbitrary;
+
+// Maximum number of vertices in a tree.
+
+pub const MAX_VERTICES: usize = 64;
+
+#[derive(Arbitrary, Debug)]
+pub struct TreeInput {
+    pub names: Vec<String>,
+    pub parents: Vec<u16>,
+    pub weights: Vec<u16>,
+    pub shuffle: Vec<u16>,
+}
+
+// A rooted tree, given by vertex names, the index of the root, and directed edges
+// (parent, child, weight), with finite nonnegative weights.  Every vertex but the first is given
+// a parent among the vertices before it, and then the vertices are renumbered at random.  Vertex
+// names are single lines.
+
+pub fn tree(x: &TreeInput) -> (Vec<String>, usize, Vec<(usize, usize, f64)>) {
+    let mut names = x
+        .names
+        .iter()
+        .take(MAX_VERTICES)
+        .map(|s| s.replace('\n', " "))
+        .collect::<Vec<String>>();
+    if names.is_empty() {
+        names.push(String::new());
+    }
+    let n = names.len();
+    let mut perm = (0..n).collect::<Vec<usize>>();
+    for i in (1..n).rev() {
+        let j = *x.shuffle.get(i).unwrap_or(&0) as usize % (i + 1);
+        perm.swap(i, j);
+    }
+    let mut edges = Vec::new();
+    for i in 1..n {
+        let p = *x.parents.get(i).unwrap_or(&0) as usize % i;
+        let w = *x.weights.get(i).unwrap_or(&0) as f64 / 100.0;
+        edges.push((perm[p], perm[i], w));
+    }
+    (names, perm[0], edges)
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct DistInput {
+    pub n: u8,
+    pub dist: Vec<u16>,
+}
+
+// Maximum size of a distance matrix.  Neighbor joining is O(n^4).
+
+pub const MAX_DIST: usize = 24;
+
+// A symmetric distance matrix, with finite nonnegative entries and zero diagonal.
+
+pub fn dist_matrix(x: &DistInput) -> Vec<Vec<f64>> {
+    let n = 1 + x.n as usize % MAX_DIST;
+
+    // Entry (i, j) for i < j is the kth value in x.dist, in row order.
+
+    let dist = |i: usize, j: usize| {
+        let (i, j) = (i.min(j), i.max(j));
+        let k = i * n - i * (i + 1) / 2 + (j - i - 1);
+        *x.dist.get(k).unwrap_or(&0) as f64 / 100.0
+    };
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { 0.0 } else { dist(i, j) })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,317 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute ALLUVIAL.  For an ordered list of origins (by default, all origins, in the order of
+// origin_list, or as given by ALLUVIAL_ORDER), and the top clonotypes by total cell count across
+// those origins (ALLUVIAL_TOP, default 10), draw one column for each origin, in which each
+// clonotype is a bar whose height is proportional to the fraction of cells in the origin that
+// lie in the clonotype.  The bars for a clonotype in adjacent origins are connected by ribbons.
+// Clonotypes are stacked in the same order in every column, so that ribbons do not cross.
+//
+// Cells not assigned to an origin are ignored.  The frequencies are computed relative to all
+// cells from the origin in the clonotypes that are shown, not just the top ones.  If
+// ALLUVIAL_CSV is given, the frequencies are also written there.
+
+use crate::colors::default_colors;
+use crate::plot_utils::{emit_svg, svg_escape, svg_header};
+use crate::string_width::arial_width;
+use crate::tail_opts::TailOpts;
+use enclone_core::defs::{EncloneControl, ExactClonotype};
+use io_utils::{fwriteln, open_for_write_new};
+use std::io::Write;
+
+// Find the extent (top, bottom) of each bar, given the frequency of each clonotype in each column,
+// as freqs[clonotype][column].  In each column, the bars are stacked downward from y0, separated
+// by gap, and the vertical scale is set so that the fullest column fills plot_height.  If there
+// are so many clonotypes that the gaps would take more than half of plot_height, the gap is
+// reduced, so that the scale stays positive.
+
+fn bar_extents(freqs: &[Vec<f64>], y0: f64, plot_height: f64, gap: f64) -> Vec<Vec<(f64, f64)>> {
+    let ncols = freqs.first().map_or(0, |x| x.len());
+    let mut fmax = 0.0_f64;
+    for c in 0..ncols {
+        fmax = fmax.max(freqs.iter().map(|x| x[c]).sum());
+    }
+    let gap = gap.min(plot_height / (2.0 * freqs.len() as f64));
+    let scale = (plot_height - gap * freqs.len() as f64) / fmax;
+    let mut bars = vec![vec![(0.0, 0.0); ncols]; freqs.len()];
+    for c in 0..ncols {
+        let mut y = y0;
+        for k in 0..freqs.len() {
+            let h = freqs[k][c] * scale;
+            bars[k][c] = (y, y + h);
+            y += h + gap;
+        }
+    }
+    bars
+}
+
+pub fn plot_alluvial(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    groups: &Vec<Vec<(i32, String)>>,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    if tail_opts.alluvial.is_empty() {
+        return Ok(());
+    }
+    let origin_list = &ctl.origin_info.origin_list;
+    if origin_list.is_empty() {
+        return Err("\nALLUVIAL requires that origins be specified.\n".to_string());
+    }
+
+    // Determine the origins to show, as indices in origin_list.
+
+    let mut origins = Vec::<usize>::new();
+    if tail_opts.alluvial_order.is_empty() {
+        origins = (0..origin_list.len()).collect();
+    } else {
+        for x in tail_opts.alluvial_order.iter() {
+            match origin_list.iter().position(|y| y == x) {
+                Some(p) => origins.push(p),
+                None => {
+                    return Err(format!(
+                        "\nALLUVIAL_ORDER includes {}, which is not the name of an origin.\n",
+                        x
+                    ))
+                }
+            }
+        }
+    }
+    let mut to_col = vec![None; origin_list.len()];
+    for (c, o) in origins.iter().enumerate() {
+        to_col[*o] = Some(c);
+    }
+
+    // Count cells for each clonotype and origin.
+
+    let mut counts = Vec::<(String, Vec<usize>)>::new();
+    let mut totals = vec![0; origins.len()];
+    for i in 0..groups.len() {
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            let mut n = vec![0; origins.len()];
+            for u in 0..exacts[oo].len() {
+                let ex = &exact_clonotypes[exacts[oo][u]];
+                for m in 0..ex.ncells() {
+                    if let Some(o) = ex.clones[m][0].origin_index {
+                        if let Some(c) = to_col[o] {
+                            n[c] += 1;
+                            totals[c] += 1;
+                        }
+                    }
+                }
+            }
+            if n.iter().sum::<usize>() > 0 {
+                counts.push((format!("{}.{}", i + 1, j + 1), n));
+            }
+        }
+    }
+    if counts.is_empty() {
+        return Err("\nALLUVIAL: there are no cells in the given origins to plot.\n".to_string());
+    }
+
+    // Find the top clonotypes.  The sort is stable, so ties are broken by clonotype order.
+
+    counts.sort_by_key(|x| std::cmp::Reverse(x.1.iter().sum::<usize>()));
+    counts.truncate(tail_opts.alluvial_top.unwrap_or(10));
+    let freq = |n: usize, c: usize| {
+        if totals[c] == 0 {
+            0.0
+        } else {
+            n as f64 / totals[c] as f64
+        }
+    };
+
+    // Write the table.
+
+    if !tail_opts.alluvial_csv.is_empty() {
+        let mut f = open_for_write_new![&tail_opts.alluvial_csv];
+        fwriteln!(f, "clonotype,origin,cells,frequency");
+        for (name, n) in counts.iter() {
+            for (c, o) in origins.iter().enumerate() {
+                fwriteln!(
+                    f,
+                    "{},{},{},{:.6}",
+                    name,
+                    origin_list[*o],
+                    n[c],
+                    freq(n[c], c)
+                );
+            }
+        }
+    }
+
+    // Define parameters of the plot.
+
+    let font = "arial";
+    let font_size = 14.0;
+    let margin = 20.0;
+    let col_width = 24.0;
+    let col_sep = 160.0;
+    let plot_height = 500.0;
+    let gap = 2.0;
+    let mut legend_width = 0.0_f64;
+    for x in counts.iter() {
+        legend_width = legend_width.max(arial_width(&x.0, font_size) + 40.0);
+    }
+    let x0 = margin + 20.0;
+    let y0 = margin + 40.0;
+    let plot_width = (origins.len() - 1) as f64 * col_sep + col_width;
+    let width = x0 + plot_width + 40.0 + legend_width + margin;
+    let legend_height = counts.len() as f64 * (font_size + 8.0);
+    let height = y0 + f64::max(plot_height + 2.0 * font_size, legend_height) + margin;
+
+    // Find the extent of each bar.
+
+    let freqs = counts
+        .iter()
+        .map(|x| (0..origins.len()).map(|c| freq(x.1[c], c)).collect())
+        .collect::<Vec<Vec<f64>>>();
+    let bars = bar_extents(&freqs, y0, plot_height, gap);
+
+    // Start the svg.
+
+    let mut svg = svg_header(width, height);
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"20\" \
+         text-anchor=\"middle\">clonotype frequencies across origins</text>\n",
+        x0 + plot_width / 2.0,
+        margin + 10.0,
+        font
+    );
+
+    // Draw the ribbons, then the bars on top of them.
+
+    let dc = default_colors();
+    let color = |k: usize| {
+        let c = &dc[k % dc.len()];
+        format!("rgb({},{},{})", c[0], c[1], c[2])
+    };
+    for k in 0..counts.len() {
+        for c in 0..origins.len() - 1 {
+            let ((a0, a1), (b0, b1)) = (bars[k][c], bars[k][c + 1]);
+            if a1 == a0 && b1 == b0 {
+                continue;
+            }
+            let xa = x0 + c as f64 * col_sep + col_width;
+            let xb = x0 + (c + 1) as f64 * col_sep;
+            let xm = (xa + xb) / 2.0;
+            svg += &format!(
+                "<path d=\"M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1} \
+                 L {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1} Z\" \
+                 fill=\"{}\" fill-opacity=\"0.4\" />\n",
+                xa,
+                a0,
+                xm,
+                a0,
+                xm,
+                b0,
+                xb,
+                b0,
+                xb,
+                b1,
+                xm,
+                b1,
+                xm,
+                a1,
+                xa,
+                a1,
+                color(k)
+            );
+        }
+    }
+    for k in 0..counts.len() {
+        for (c, o) in origins.iter().enumerate() {
+            let (ya, yb) = bars[k][c];
+            if yb > ya {
+                svg += &format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"{}\">\
+                     <title>{} in {}: {} cells, {:.2}%</title></rect>\n",
+                    x0 + c as f64 * col_sep,
+                    ya,
+                    col_width,
+                    yb - ya,
+                    color(k),
+                    counts[k].0,
+                    svg_escape(&origin_list[*o]),
+                    counts[k].1[c],
+                    100.0 * freq(counts[k].1[c], c)
+                );
+            }
+        }
+    }
+
+    // Label the origins.
+
+    for (c, o) in origins.iter().enumerate() {
+        svg += &format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+             text-anchor=\"middle\">{}</text>\n",
+            x0 + c as f64 * col_sep + col_width / 2.0,
+            y0 + plot_height + font_size + 6.0,
+            font,
+            font_size,
+            svg_escape(&origin_list[*o])
+        );
+    }
+
+    // Draw the legend.
+
+    let lx = x0 + plot_width + 40.0;
+    for k in 0..counts.len() {
+        let ly = y0 + k as f64 * (font_size + 8.0);
+        svg += &format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" fill=\"{}\" />\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\">{}</text>\n",
+            lx,
+            ly,
+            font_size,
+            font_size,
+            color(k),
+            lx + font_size + 6.0,
+            ly + font_size - 2.0,
+            font,
+            font_size,
+            counts[k].0
+        );
+    }
+    svg += "</svg>\n";
+    emit_svg(svg, &tail_opts.alluvial, svgs);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_extents() {
+        // Two clonotypes in two origins.  The second origin is fuller, so it fills the plot,
+        // including the gaps.
+
+        let close =
+            |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() < 1.0e-9 && (a.1 - b.1).abs() < 1.0e-9;
+        let bars = bar_extents(&[vec![0.5, 0.6], vec![0.2, 0.4]], 10.0, 104.0, 2.0);
+        assert!(close(bars[0][0], (10.0, 60.0)));
+        assert!(close(bars[1][0], (62.0, 82.0)));
+        assert!(close(bars[0][1], (10.0, 70.0)));
+        assert!(close(bars[1][1], (72.0, 112.0)));
+
+        // A clonotype absent from an origin has an empty bar.
+
+        let bars = bar_extents(&[vec![1.0, 0.0], vec![0.0, 1.0]], 0.0, 100.0, 2.0);
+        assert_eq!(bars[1][0].0, bars[1][0].1);
+        assert_eq!(bars[0][1].0, bars[0][1].1);
+
+        // With many clonotypes, the gaps shrink, and the bars stay inside the plot.
+
+        let freqs = vec![vec![0.001]; 1000];
+        let bars = bar_extents(&freqs, 0.0, 500.0, 2.0);
+        for k in 0..freqs.len() {
+            assert!(bars[k][0].1 > bars[k][0].0);
+            assert!(bars[k][0].1 <= 500.0 + 1.0e-9);
+        }
+    }
+}
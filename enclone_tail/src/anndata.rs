@@ -0,0 +1,165 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute ANNDATA.  We write one row per cell, with the clonotype group, clonotype and exact
+// subclonotype ids, and the values of the variables given by ANNDATA_VARS, in two forms:
+// - stem.csv, a CSV file whose first column is the obs index
+// - stem.h5, an HDF5 file containing a group obs, in the AnnData dataframe encoding.
+// Either may be merged onto the obs of an AnnData object having the same barcodes, e.g. using
+// anndata.experimental.read_elem(h5py.File("stem.h5")["obs"]).
+//
+// If there is one dataset, the index is the barcode.  Otherwise it is barcode-dataset, which
+// matches the index generated by anndata.concat(..., keys=datasets, index_unique="-").
+//
+// All values are written as strings.  Cells lacking a value for a variable get an empty string.
+
+use crate::tail_opts::TailOpts;
+use enclone_core::defs::{EncloneControl, ExactClonotype, POUT_SEP};
+use hdf5::types::VarLenUnicode;
+use io_utils::{fwrite, fwriteln, open_for_write_new};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::io::Write;
+
+fn csv_field(x: &str) -> String {
+    if x.contains(',') || x.contains('"') {
+        format!("\"{}\"", x.replace('"', "\"\""))
+    } else {
+        x.to_string()
+    }
+}
+
+fn to_h5_string(x: &str) -> VarLenUnicode {
+    x.replace('\0', "").parse::<VarLenUnicode>().unwrap()
+}
+
+fn write_h5_str_attr(loc: &hdf5::Location, name: &str, value: &str) -> hdf5::Result<()> {
+    loc.new_attr_builder()
+        .with_data(&ndarray::arr0(to_h5_string(value)))
+        .create(name)?;
+    Ok(())
+}
+
+fn write_h5_str_array(group: &hdf5::Group, name: &str, values: &[String]) -> hdf5::Result<()> {
+    let x = values.iter().map(|v| to_h5_string(v)).collect::<Vec<_>>();
+    let ds = group
+        .new_dataset_builder()
+        .with_data(x.as_slice())
+        .create(name)?;
+    write_h5_str_attr(&ds, "encoding-type", "string-array")?;
+    write_h5_str_attr(&ds, "encoding-version", "0.2.0")?;
+    Ok(())
+}
+
+fn write_obs_h5(
+    filename: &str,
+    index: &[String],
+    cols: &[(String, Vec<String>)],
+) -> hdf5::Result<()> {
+    let file = hdf5::File::create(filename)?;
+    let obs = file.create_group("obs")?;
+    write_h5_str_attr(&obs, "encoding-type", "dataframe")?;
+    write_h5_str_attr(&obs, "encoding-version", "0.2.0")?;
+    write_h5_str_attr(&obs, "_index", "_index")?;
+    let order = cols.iter().map(|c| to_h5_string(&c.0)).collect::<Vec<_>>();
+    obs.new_attr_builder()
+        .with_data(order.as_slice())
+        .create("column-order")?;
+    write_h5_str_array(&obs, "_index", index)?;
+    for (name, values) in cols.iter() {
+        write_h5_str_array(&obs, name, values)?;
+    }
+    Ok(())
+}
+
+// Get the value of a variable for the mth cell in an exact subclonotype, from its out_data.
+// Per-cell values are separated by POUT_SEP, as for parseable output.  If the variable has no
+// value, return an empty string.
+
+pub fn cell_value(y: &HashMap<String, String>, var: &str, m: usize) -> String {
+    match y.get(var) {
+        Some(x) => {
+            let vals = x.split(POUT_SEP).collect::<Vec<&str>>();
+            if vals.len() > 1 {
+                vals.get(m).unwrap_or(&"").to_string()
+            } else {
+                vals[0].to_string()
+            }
+        }
+        None => String::new(),
+    }
+}
+
+pub fn write_anndata_obs(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    groups: &Vec<Vec<(i32, String)>>,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+) -> Result<(), String> {
+    if tail_opts.anndata.is_empty() {
+        return Ok(());
+    }
+    let vars = &tail_opts.anndata_vars;
+    let multi = ctl.origin_info.n() > 1;
+
+    // Gather the table, one column at a time.
+
+    let mut names = vec![
+        "barcode".to_string(),
+        "dataset".to_string(),
+        "group_id".to_string(),
+        "clonotype_id".to_string(),
+        "exact_subclonotype_id".to_string(),
+    ];
+    names.append(&mut vars.clone());
+    let mut index = Vec::<String>::new();
+    let mut cols = vec![Vec::<String>::new(); names.len()];
+    for i in 0..groups.len() {
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            for u in 0..exacts[oo].len() {
+                let ex = &exact_clonotypes[exacts[oo][u]];
+                for m in 0..ex.ncells() {
+                    let bc = &ex.clones[m][0].barcode;
+                    let dataset = &ctl.origin_info.dataset_id[ex.clones[m][0].dataset_index];
+                    if multi {
+                        index.push(format!("{}-{}", bc, dataset));
+                    } else {
+                        index.push(bc.clone());
+                    }
+                    cols[0].push(bc.clone());
+                    cols[1].push(dataset.clone());
+                    cols[2].push(format!("{}", i + 1));
+                    cols[3].push(format!("{}", j + 1));
+                    cols[4].push(format!("{}", u + 1));
+
+                    for (k, v) in vars.iter().enumerate() {
+                        cols[5 + k].push(cell_value(&out_datas[oo][u], v, m));
+                    }
+                }
+            }
+        }
+    }
+
+    // Write the CSV file.
+
+    let csv = format!("{}.csv", tail_opts.anndata);
+    let mut f = open_for_write_new![&csv];
+    fwriteln!(f, ",{}", names.iter().map(|x| csv_field(x)).format(","));
+    for r in 0..index.len() {
+        fwrite!(f, "{}", csv_field(&index[r]));
+        for c in 0..cols.len() {
+            fwrite!(f, ",{}", csv_field(&cols[c][r]));
+        }
+        fwriteln!(f, "");
+    }
+
+    // Write the HDF5 file.
+
+    let h5 = format!("{}.h5", tail_opts.anndata);
+    let cols = names.into_iter().zip(cols.into_iter()).collect::<Vec<_>>();
+    write_obs_h5(&h5, &index, &cols)
+        .map_err(|e| format!("\nUnable to write ANNDATA file {}: {}.\n", h5, e))?;
+    Ok(())
+}
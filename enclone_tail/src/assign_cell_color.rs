@@ -6,23 +6,51 @@ use crate::TextUtils;
 use ansi_escape::print_color13;
 use enclone_core::cell_color::CellColor;
 use enclone_core::defs::{EncloneControl, ExactClonotype, PlotOpt, POUT_SEP};
-use lazy_static::lazy_static;
 use std::collections::HashMap;
-use std::sync::Mutex;
 use vdj_ann::refx::RefData;
 use vector_utils::{bin_position, unique_sort, VecUtils};
 
-// The use of these global variables is horrible.  It already lead to one bug that was very
-// difficult to track down.
+// State for coloring the cells in one plot.  If cells are colored by the value of a variable,
+// this records the range of its values, which is needed both to assign colors and to make the
+// legend.  The range is None if no exact subclonotype has a value for the variable, and is
+// (f64::MAX, f64::MIN) if none of the values are numeric.
+//
+// This replaces global state, so that plots may be made concurrently.
 
-lazy_static! {
-    pub static ref VAR_LOW: Mutex<Vec<(String, f64)>> = Mutex::new(Vec::<(String, f64)>::new());
-    pub static ref VAR_HIGH: Mutex<Vec<(String, f64)>> = Mutex::new(Vec::<(String, f64)>::new());
+#[derive(Clone, Debug, Default)]
+pub struct PlotColorContext {
+    pub var_range: Option<(f64, f64)>,
+}
+
+impl PlotColorContext {
+    pub fn new(plot_opt: &PlotOpt, out_datas: &Vec<Vec<HashMap<String, String>>>) -> Self {
+        let mut ctx = PlotColorContext::default();
+        if let CellColor::ByVariableValue(ref x) = plot_opt.cell_color {
+            for i in 0..out_datas.len() {
+                for j in 0..out_datas[i].len() {
+                    if let Some(val_list) = out_datas[i][j].get(&x.var) {
+                        let (low, high) = ctx.var_range.get_or_insert((f64::MAX, f64::MIN));
+                        for val in val_list.split(POUT_SEP) {
+                            if val.parse::<f64>().is_ok() {
+                                let v = val.force_f64();
+                                if v.is_finite() {
+                                    *low = low.min(v);
+                                    *high = high.max(v);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        ctx
+    }
 }
 
 pub fn assign_cell_color(
     ctl: &EncloneControl,
     plot_opt: &PlotOpt, // overrides ctl
+    color_ctx: &PlotColorContext,
     refdata: &RefData,
     const_names: &Vec<String>,
     dsx: usize,
@@ -64,49 +92,7 @@ pub fn assign_cell_color(
             CellColor::ByVariableValue(ref x) => {
                 color = "undefined".to_string();
                 if out_datas[i][j].contains_key(&x.var) {
-                    let n = VAR_LOW.lock().unwrap().len();
-                    let mut computed = false;
-                    for z in 0..n {
-                        if VAR_LOW.lock().unwrap()[z].0 == x.var {
-                            computed = true;
-                        }
-                    }
-                    if !computed {
-                        let (mut low, mut high) = (f64::MAX, f64::MIN);
-                        for i in 0..out_datas.len() {
-                            for j in 0..out_datas[i].len() {
-                                if out_datas[i][j].contains_key(&x.var) {
-                                    let val_list = &out_datas[i][j][&x.var];
-                                    let vals = val_list.split(POUT_SEP).collect::<Vec<&str>>();
-                                    for k in 0..vals.len() {
-                                        let val = &vals[k];
-                                        if val.parse::<f64>().is_ok() {
-                                            let v = val.force_f64();
-                                            if v.is_finite() {
-                                                low = low.min(v);
-                                                high = high.max(v);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        VAR_LOW.lock().unwrap().push((x.var.clone(), low));
-                        VAR_HIGH.lock().unwrap().push((x.var.clone(), high));
-                    }
-                    let (mut low, mut high) = (0.0, 0.0);
-                    let n = VAR_LOW.lock().unwrap().len();
-                    for z in 0..n {
-                        if VAR_LOW.lock().unwrap()[z].0 == x.var {
-                            low = VAR_LOW.lock().unwrap()[z].1;
-                        }
-                    }
-                    let n = VAR_HIGH.lock().unwrap().len();
-                    for z in 0..n {
-                        if VAR_HIGH.lock().unwrap()[z].0 == x.var {
-                            high = VAR_HIGH.lock().unwrap()[z].1;
-                        }
-                    }
+                    let (low, high) = color_ctx.var_range.unwrap();
                     let val_list = &out_datas[i][j][&x.var];
                     let vals = val_list.split(POUT_SEP).collect::<Vec<&str>>();
                     let val;
@@ -0,0 +1,241 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Make an svg grouped bar chart.  There is one group of bars for each x label, and within each
+// group, one bar for each series, colored by series.  The x labels are rotated, so that long
+// labels such as gene names fit.  If there is more than one series, a legend is shown at the
+// right.
+
+use crate::plot_utils::{svg_escape, svg_header};
+use crate::string_width::arial_width;
+use crate::ticks::ticks;
+use string_utils::TextUtils;
+
+pub struct BarSeries {
+    pub name: String,
+    pub values: Vec<f64>, // one for each x label
+    pub color: (u8, u8, u8),
+}
+
+pub fn grouped_bar_chart(
+    title: &str,
+    xlabels: &[String],
+    xdesc: &str,
+    ydesc: &str,
+    series: &[BarSeries],
+) -> String {
+    // Define parameters of the plot.
+
+    let font = "arial";
+    let title_font_size = 20.0;
+    let font_size = 14.0;
+    let margin = 20.0;
+    let bar_width = if series.len() <= 2 { 12.0 } else { 8.0 };
+    let group_sep = 6.0;
+    let plot_height = 400.0;
+    let axis_ticks = 5;
+
+    // Determine the y range and tick marks.  Ticks omit zero, so we add it.
+
+    let mut ymax = 0.0_f64;
+    for s in series.iter() {
+        for v in s.values.iter() {
+            ymax = ymax.max(*v);
+        }
+    }
+    if ymax == 0.0 {
+        ymax = 1.0;
+    }
+    let mut yticks = vec!["0".to_string()];
+    yticks.append(&mut ticks(0.0, ymax as f32, axis_ticks, false));
+    for t in yticks.iter() {
+        ymax = ymax.max(t.force_f64());
+    }
+
+    // Determine the sizes of the plot areas.
+
+    let mut ytick_width = 0.0_f64;
+    for t in yticks.iter() {
+        ytick_width = ytick_width.max(arial_width(t, font_size));
+    }
+    let mut xlabel_width = 0.0_f64;
+    for x in xlabels.iter() {
+        xlabel_width = xlabel_width.max(arial_width(x, font_size));
+    }
+    let mut legend_width = 0.0;
+    if series.len() > 1 {
+        for s in series.iter() {
+            legend_width = f64::max(legend_width, arial_width(&s.name, font_size) + 40.0);
+        }
+    }
+    let group_width = series.len() as f64 * bar_width + group_sep;
+    let plot_width = f64::max(300.0, xlabels.len() as f64 * group_width);
+    let x0 = margin + 2.0 * font_size + ytick_width + 8.0;
+    let y0 = margin + 2.0 * title_font_size;
+    let width = x0 + plot_width + margin + legend_width;
+    let height = y0 + plot_height + 8.0 + xlabel_width + 2.0 * font_size + margin;
+
+    // Start the svg.
+
+    let mut svg = svg_header(width, height);
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+         text-anchor=\"middle\">{}</text>\n",
+        x0 + plot_width / 2.0,
+        margin + title_font_size,
+        font,
+        title_font_size,
+        svg_escape(title)
+    );
+
+    // Draw the y axis, with tick marks and grid lines.
+
+    let ypos = |y: f64| y0 + plot_height * (1.0 - y / ymax);
+    for t in yticks.iter() {
+        let y = ypos(t.force_f64());
+        svg += &format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" \
+             stroke=\"rgb(220,220,220)\" stroke-width=\"1\" />\n",
+            x0,
+            y,
+            x0 + plot_width,
+            y
+        );
+        svg += &format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+             text-anchor=\"end\">{}</text>\n",
+            x0 - 6.0,
+            y + font_size / 3.0,
+            font,
+            font_size,
+            t
+        );
+    }
+    svg += &format!(
+        "<text transform=\"translate({:.1},{:.1}) rotate(-90)\" font-family=\"{}\" \
+         font-size=\"{}\" text-anchor=\"middle\">{}</text>\n",
+        margin + font_size,
+        y0 + plot_height / 2.0,
+        font,
+        font_size,
+        svg_escape(ydesc)
+    );
+
+    // Draw the bars and x labels.
+
+    for i in 0..xlabels.len() {
+        let gx = x0 + i as f64 * group_width + group_sep / 2.0;
+        for (k, s) in series.iter().enumerate() {
+            let v = s.values[i];
+            if v > 0.0 {
+                let y = ypos(v);
+                svg += &format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" \
+                     fill=\"rgb({},{},{})\"><title>{}: {} = {:.2}</title></rect>\n",
+                    gx + k as f64 * bar_width,
+                    y,
+                    bar_width,
+                    y0 + plot_height - y,
+                    s.color.0,
+                    s.color.1,
+                    s.color.2,
+                    svg_escape(&s.name),
+                    svg_escape(&xlabels[i]),
+                    v
+                );
+            }
+        }
+        svg += &format!(
+            "<text transform=\"translate({:.1},{:.1}) rotate(-90)\" font-family=\"{}\" \
+             font-size=\"{}\" text-anchor=\"end\">{}</text>\n",
+            gx + series.len() as f64 * bar_width / 2.0 + font_size / 3.0,
+            y0 + plot_height + 6.0,
+            font,
+            font_size,
+            svg_escape(&xlabels[i])
+        );
+    }
+    svg += &format!(
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n\
+         <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n",
+        x0,
+        y0,
+        x0,
+        y0 + plot_height,
+        x0,
+        y0 + plot_height,
+        x0 + plot_width,
+        y0 + plot_height
+    );
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+         text-anchor=\"middle\">{}</text>\n",
+        x0 + plot_width / 2.0,
+        height - margin,
+        font,
+        font_size,
+        svg_escape(xdesc)
+    );
+
+    // Draw the legend.
+
+    if series.len() > 1 {
+        let lx = x0 + plot_width + 20.0;
+        for (k, s) in series.iter().enumerate() {
+            let ly = y0 + k as f64 * (font_size + 8.0);
+            svg += &format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" \
+                 fill=\"rgb({},{},{})\" />\n\
+                 <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\">{}</text>\n",
+                lx,
+                ly,
+                font_size,
+                font_size,
+                s.color.0,
+                s.color.1,
+                s.color.2,
+                lx + font_size + 6.0,
+                ly + font_size - 2.0,
+                font,
+                font_size,
+                svg_escape(&s.name)
+            );
+        }
+    }
+    svg += "</svg>\n";
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grouped_bar_chart() {
+        let series = |name: &str, values: Vec<f64>| BarSeries {
+            name: name.to_string(),
+            values,
+            color: (0, 0, 0),
+        };
+        let xlabels = vec!["IGHV1-2".to_string(), "IGHV3-23".to_string()];
+
+        // Zero values get no bar.  Names are escaped, and a legend is shown only if there is
+        // more than one series.
+
+        let svg = grouped_bar_chart(
+            "V gene usage",
+            &xlabels,
+            "V gene",
+            "% of cells",
+            &[
+                series("a&b", vec![25.0, 75.0]),
+                series("c", vec![0.0, 100.0]),
+            ],
+        );
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<title>").count(), 3);
+        assert!(svg.contains("<title>a&amp;b: IGHV3-23 = 75.00</title>"));
+        assert!(svg.contains(">a&amp;b</text>"));
+        let svg = grouped_bar_chart("", &xlabels, "", "", &[series("a", vec![25.0, 75.0])]);
+        assert!(!svg.contains(">a</text>"));
+    }
+}
@@ -2,7 +2,9 @@
 //
 // Given a collection of circles having specified colors, create an svg string that shows the
 // circles on a canvas of fixed size.  The circles are moved and resized accordingly.
-// Also shades smoothed polygons.  Also add tooltip notes if requested.
+// Also shades smoothed polygons.  Also add tooltip notes if requested.  If multi is set, meaning
+// that there is more than one dataset, the tooltip notes include the dataset index, as the same
+// barcode may appear in several datasets.
 
 use crate::polygon::Polygon;
 use std::collections::HashMap;
@@ -21,6 +23,7 @@ pub fn circles_to_svg(
     height: usize,
     boundary: usize,
     tooltip: bool,
+    multi: bool,
     by_var: bool,
     var: String,
     barcode_to_var_value: &HashMap<(usize, String), String>,
@@ -99,15 +102,20 @@ pub fn circles_to_svg(
     for i in 0..center.len() {
         let mut tooltipx = String::new();
         if tooltip {
+            let mut dataset = String::new();
+            if multi {
+                dataset = format!(",dataset_index={}", barcodes[i].0);
+            }
             let mut var_val = String::new();
             if by_var && barcode_to_var_value.contains_key(&barcodes[i]) {
                 var_val = format!(",{}={}", var, barcode_to_var_value[&barcodes[i]]);
             }
             tooltipx = format!(
-                " tooltip=\"group_id={},clonotype_id={},barcode={}{}\"",
+                " tooltip=\"group_id={},clonotype_id={},barcode={}{}{}\"",
                 group_index2[i] + 1,
                 clonotype_index2[i] + 1,
                 barcodes[i].1,
+                dataset,
                 var_val,
             );
         }
@@ -0,0 +1,319 @@
+// Copyright (c) 2021 10x Genomics, Inc. All rights reserved.
+
+// Convert svg figures to a vector pdf file, with one page per figure.
+//
+// The svg is first parsed by usvg, exactly as for png conversion, which converts text to paths
+// using the fonts that we carry, so that the pdf does not depend on fonts being installed.  The
+// resulting paths are then written directly as pdf drawing operators.  Only what our plots use
+// is supported: paths filled or stroked with solid colors, with opacity, nested in groups with
+// transforms.  Gradients, patterns, clip paths, masks, filters and images are ignored.
+//
+// Sizes are converted from svg pixels (96 per inch) to pdf points (72 per inch).
+//
+// Reference: PDF 1.4 specification, https://www.adobe.com/devnet/pdf/pdf_reference_archive.html.
+
+use crate::convert_svg_to_png::svg_to_tree;
+use std::fmt::Write;
+
+const POINTS_PER_PIXEL: f64 = 0.75;
+
+// Drawing state for one page: the content stream, and the distinct (fill, stroke) opacity pairs
+// used, which become graphics state resources.
+
+struct Page {
+    content: String,
+    opacities: Vec<(f64, f64)>,
+}
+
+impl Page {
+    fn set_opacity(&mut self, fill: f64, stroke: f64) {
+        if fill == 1.0 && stroke == 1.0 {
+            return;
+        }
+        let p = match self.opacities.iter().position(|x| *x == (fill, stroke)) {
+            Some(p) => p,
+            None => {
+                self.opacities.push((fill, stroke));
+                self.opacities.len() - 1
+            }
+        };
+        writeln!(self.content, "/G{} gs", p).unwrap();
+    }
+}
+
+fn transform(t: &usvg::Transform) -> String {
+    format!(
+        "{:.4} {:.4} {:.4} {:.4} {:.4} {:.4} cm",
+        t.a, t.b, t.c, t.d, t.e, t.f
+    )
+}
+
+// Return the rgb components of a paint, scaled to [0,1].  Paints other than solid colors are
+// drawn in gray.
+
+fn rgb(paint: &usvg::Paint) -> String {
+    match paint {
+        usvg::Paint::Color(c) => format!(
+            "{:.4} {:.4} {:.4}",
+            c.red as f64 / 255.0,
+            c.green as f64 / 255.0,
+            c.blue as f64 / 255.0
+        ),
+        _ => "0.5 0.5 0.5".to_string(),
+    }
+}
+
+fn draw_path(page: &mut Page, p: &usvg::Path, opacity: f64) {
+    if p.visibility != usvg::Visibility::Visible || (p.fill.is_none() && p.stroke.is_none()) {
+        return;
+    }
+    page.content += "q\n";
+    writeln!(page.content, "{}", transform(&p.transform)).unwrap();
+    let mut fill_opacity = opacity;
+    let mut stroke_opacity = opacity;
+    if let Some(ref fill) = p.fill {
+        writeln!(page.content, "{} rg", rgb(&fill.paint)).unwrap();
+        fill_opacity *= fill.opacity.value();
+    }
+    if let Some(ref stroke) = p.stroke {
+        writeln!(page.content, "{} RG", rgb(&stroke.paint)).unwrap();
+        writeln!(page.content, "{:.4} w", stroke.width.value()).unwrap();
+        let cap = match stroke.linecap {
+            usvg::LineCap::Butt => 0,
+            usvg::LineCap::Round => 1,
+            usvg::LineCap::Square => 2,
+        };
+        let join = match stroke.linejoin {
+            usvg::LineJoin::Miter => 0,
+            usvg::LineJoin::Round => 1,
+            usvg::LineJoin::Bevel => 2,
+        };
+        writeln!(page.content, "{} J {} j", cap, join).unwrap();
+        writeln!(page.content, "{:.4} M", stroke.miterlimit.value()).unwrap();
+        if let Some(ref dashes) = stroke.dasharray {
+            let dashes = dashes
+                .iter()
+                .map(|x| format!("{:.4}", x))
+                .collect::<Vec<_>>();
+            writeln!(
+                page.content,
+                "[{}] {:.4} d",
+                dashes.join(" "),
+                stroke.dashoffset
+            )
+            .unwrap();
+        }
+        stroke_opacity *= stroke.opacity.value();
+    }
+    page.set_opacity(fill_opacity, stroke_opacity);
+    for seg in p.data.iter() {
+        match *seg {
+            usvg::PathSegment::MoveTo { x, y } => {
+                writeln!(page.content, "{:.4} {:.4} m", x, y).unwrap();
+            }
+            usvg::PathSegment::LineTo { x, y } => {
+                writeln!(page.content, "{:.4} {:.4} l", x, y).unwrap();
+            }
+            usvg::PathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                writeln!(
+                    page.content,
+                    "{:.4} {:.4} {:.4} {:.4} {:.4} {:.4} c",
+                    x1, y1, x2, y2, x, y
+                )
+                .unwrap();
+            }
+            usvg::PathSegment::ClosePath => page.content += "h\n",
+        }
+    }
+    let even_odd = match p.fill {
+        Some(ref fill) => fill.rule == usvg::FillRule::EvenOdd,
+        None => false,
+    };
+    let op = match (p.fill.is_some(), p.stroke.is_some(), even_odd) {
+        (true, true, false) => "B",
+        (true, true, true) => "B*",
+        (true, false, false) => "f",
+        (true, false, true) => "f*",
+        _ => "S",
+    };
+    writeln!(page.content, "{}\nQ", op).unwrap();
+}
+
+fn draw_node(page: &mut Page, node: &usvg::Node, opacity: f64) {
+    match *node.borrow() {
+        usvg::NodeKind::Group(ref g) => {
+            page.content += "q\n";
+            writeln!(page.content, "{}", transform(&g.transform)).unwrap();
+            for child in node.children() {
+                draw_node(page, &child, opacity * g.opacity.value());
+            }
+            page.content += "Q\n";
+        }
+        usvg::NodeKind::Path(ref p) => draw_path(page, p, opacity),
+        _ => {}
+    }
+}
+
+// Convert svg figures to a pdf file, with one page per figure.
+
+pub fn convert_svgs_to_pdf(svgs: &[String]) -> Vec<u8> {
+    // Make the pages.  The svg y axis points down, so we flip it.
+
+    let mut pages = Vec::<(f64, f64, Page)>::new();
+    for svg in svgs.iter() {
+        let tree = svg_to_tree(svg.as_bytes());
+        let svg_node = tree.svg_node();
+        let (width, height) = (svg_node.size.width(), svg_node.size.height());
+        let vb = &svg_node.view_box.rect;
+        let (sx, sy) = (width / vb.width(), height / vb.height());
+        let (w, h) = (width * POINTS_PER_PIXEL, height * POINTS_PER_PIXEL);
+        let mut page = Page {
+            content: String::new(),
+            opacities: Vec::new(),
+        };
+        writeln!(
+            page.content,
+            "{:.4} 0 0 {:.4} 0 {:.4} cm",
+            POINTS_PER_PIXEL, -POINTS_PER_PIXEL, h
+        )
+        .unwrap();
+        writeln!(
+            page.content,
+            "{:.4} 0 0 {:.4} {:.4} {:.4} cm",
+            sx,
+            sy,
+            -vb.x() * sx,
+            -vb.y() * sy
+        )
+        .unwrap();
+        for child in tree.root().children() {
+            draw_node(&mut page, &child, 1.0);
+        }
+        pages.push((w, h, page));
+    }
+
+    // Define the objects.  Object 1 is the catalog, object 2 is the page tree, and each page
+    // has two objects, the page and its content stream.
+
+    let kids = (0..pages.len())
+        .map(|i| format!("{} 0 R", 3 + 2 * i))
+        .collect::<Vec<_>>();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+    ];
+    for (i, (w, h, page)) in pages.iter().enumerate() {
+        let mut gs = String::new();
+        for (j, (fill, stroke)) in page.opacities.iter().enumerate() {
+            write!(gs, " /G{} << /ca {:.4} /CA {:.4} >>", j, fill, stroke).unwrap();
+        }
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /ExtGState <<{} >> >> /Contents {} 0 R >>",
+            w,
+            h,
+            gs,
+            4 + 2 * i
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            page.content.len(),
+            page.content
+        ));
+    }
+
+    // Write the file.
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::<usize>::new();
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, obj).as_bytes());
+    }
+    let xref = pdf.len();
+    let mut tail = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for x in offsets.iter() {
+        writeln!(tail, "{:010} 00000 n ", x).unwrap();
+    }
+    writeln!(
+        tail,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+        objects.len() + 1,
+        xref
+    )
+    .unwrap();
+    pdf.extend(tail.as_bytes());
+    pdf
+}
+
+pub fn convert_svg_to_pdf(svg: &str) -> Vec<u8> {
+    convert_svgs_to_pdf(&[svg.to_string()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfind(pdf: &[u8], pat: &str) -> Option<usize> {
+        pdf.windows(pat.len()).rposition(|w| w == pat.as_bytes())
+    }
+
+    #[test]
+    fn test_convert_svgs_to_pdf() {
+        let svg = |color: &str| {
+            format!(
+                "<svg version=\"1.1\" baseProfile=\"full\" width=\"200\" height=\"100\" \
+                 xmlns=\"http://www.w3.org/2000/svg\">\n\
+                 <rect x=\"10\" y=\"10\" width=\"50\" height=\"20\" fill=\"{}\" \
+                 fill-opacity=\"0.5\" />\n\
+                 </svg>\n",
+                color
+            )
+        };
+        for n in 1..=2 {
+            let svgs = ["red", "blue"][0..n]
+                .iter()
+                .map(|c| svg(c))
+                .collect::<Vec<_>>();
+            let pdf = convert_svgs_to_pdf(&svgs);
+            assert!(pdf.starts_with(b"%PDF-1.4\n"));
+            assert!(pdf.ends_with(b"%%EOF\n"));
+            assert!(rfind(&pdf, &format!("/Count {} >>", n)).is_some());
+            assert!(rfind(&pdf, "/MediaBox [0 0 150.00 75.00]").is_some());
+
+            // The xref table has an entry for each object, which are the catalog, the page tree,
+            // and two objects per page, and each entry gives the offset of its object.
+
+            let startxref = rfind(&pdf, "startxref\n").unwrap();
+            let tail = std::str::from_utf8(&pdf[startxref..]).unwrap();
+            let xref = tail.lines().nth(1).unwrap().parse::<usize>().unwrap();
+            let lines = std::str::from_utf8(&pdf[xref..startxref])
+                .unwrap()
+                .lines()
+                .collect::<Vec<_>>();
+            let nobj = 2 + 2 * n;
+            assert_eq!(lines[0], "xref");
+            assert_eq!(lines[1], format!("0 {}", nobj + 1));
+            assert_eq!(lines[2], "0000000000 65535 f ");
+            for k in 1..=nobj {
+                let entry = lines[2 + k];
+                assert!(entry.len() == 19 && entry.ends_with(" 00000 n "));
+                let offset = entry[0..10].parse::<usize>().unwrap();
+                let obj = format!("{} 0 obj\n", k);
+                assert_eq!(&pdf[offset..offset + obj.len()], obj.as_bytes());
+            }
+            assert_eq!(lines[3 + nobj], "trailer");
+        }
+    }
+}
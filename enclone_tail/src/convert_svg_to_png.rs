@@ -66,7 +66,10 @@ pub fn set_pixels_per_meter(png: &mut Vec<u8>, pixels_per_meter: u32) {
 // The following code is pretty much copied from the resvg crate,
 // rev = 6b29007311edc5022635362fe56f6e5c0318fdeb, done June 14, 2021.
 
-pub fn convert_svg_to_png(svg: &[u8], width: u32) -> Vec<u8> {
+// Parse an svg, converting text to paths using the fonts that we carry.  Arial is replaced by
+// Liberation Sans, which has the same metrics.  This is also used by convert_svg_to_pdf.
+
+pub fn svg_to_tree(svg: &[u8]) -> usvg::Tree {
     let fontdb = load_fonts();
     let usvg = usvg::OptionsRef {
         resources_dir: None,
@@ -92,10 +95,11 @@ pub fn convert_svg_to_png(svg: &[u8], width: u32) -> Vec<u8> {
             svg
         );
     }
-    let tree = tree.unwrap();
-
-    // Proceed.
+    tree.unwrap()
+}
 
+pub fn convert_svg_to_png(svg: &[u8], width: u32) -> Vec<u8> {
+    let tree = svg_to_tree(svg);
     let fit_to = usvg::FitTo::Width(width);
     let size = fit_to
         .fit_to(tree.svg_node().size.to_screen_size())
@@ -24,18 +24,19 @@ pub fn display_tree(
     // Test input data and create an index.
 
     let mut edges = edges.clone();
-    let mut n = 0;
-    for i in 0..edges.len() {
-        n = max(n, edges[i].0 + 1);
-        n = max(n, edges[i].1 + 1);
+    let n = vnames.len();
+    for e in edges.iter() {
+        assert!(max(e.0, e.1) < n);
     }
     assert!(r < n);
+    if edges.is_empty() {
+        return format!("{}\n", vnames[r]);
+    }
     let mut index = vec![Vec::<usize>::new(); n];
     for i in 0..edges.len() {
         index[edges[i].0].push(i);
         index[edges[i].1].push(i);
     }
-    assert_eq!(n, vnames.len());
 
     // For each vertex, define a path, which is the sequence of edge indices from the root to it.
 
@@ -0,0 +1,290 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute PLOT_VGENE, PLOT_JGENE, PLOT_VJ_HEATMAP and PLOT_HL_HEATMAP.
+//
+// PLOT_VGENE and PLOT_JGENE make bar charts showing, for each dataset (or origin, if
+// PLOT_GENE_BY=origin), the percent of chains assigned to each V (or J) gene.  PLOT_VJ_HEATMAP
+// shows the number of chains for each pair of V and J genes.  PLOT_HL_HEATMAP shows the number
+// of heavy/light (or TRB/TRA) V gene pairings within exact subclonotypes.
+//
+// By default, each cell contributes one count per chain.  If PLOT_GENE_WEIGHT=clonotypes, each
+// clonotype instead contributes one count for each distinct gene (or gene pair) that it has.
+// PLOT_GENE_CHAIN restricts all but the heavy/light heatmap to one chain type.
+
+use crate::bar_chart::{grouped_bar_chart, BarSeries};
+use crate::colors::default_colors;
+use crate::heatmap::heatmap;
+use crate::plot_utils::{emit_svg, CHAIN_TYPES};
+use crate::tail_opts::TailOpts;
+use enclone_core::defs::{EncloneControl, ExactClonotype};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use vdj_ann::refx::RefData;
+
+// Add counts to a table, either once per cell, or once per clonotype.
+
+fn add_count<K: Clone + Ord + std::hash::Hash>(
+    counts: &mut BTreeMap<K, f64>,
+    seen: &mut HashSet<K>,
+    key: K,
+    ncells: usize,
+    by_clonotype: bool,
+) {
+    if by_clonotype {
+        if seen.insert(key.clone()) {
+            *counts.entry(key).or_insert(0.0) += 1.0;
+        }
+    } else {
+        *counts.entry(key).or_insert(0.0) += ncells as f64;
+    }
+}
+
+// Turn a table of counts keyed by (row, column) into a matrix for a heatmap.
+
+fn to_matrix(
+    counts: &BTreeMap<(String, String), f64>,
+) -> (Vec<String>, Vec<String>, Vec<Vec<f64>>) {
+    let rows = counts.keys().map(|k| k.0.clone()).collect::<BTreeSet<_>>();
+    let cols = counts.keys().map(|k| k.1.clone()).collect::<BTreeSet<_>>();
+    let rows = rows.into_iter().collect::<Vec<_>>();
+    let cols = cols.into_iter().collect::<Vec<_>>();
+    let mut vals = vec![vec![0.0; cols.len()]; rows.len()];
+    for (i, r) in rows.iter().enumerate() {
+        for (j, c) in cols.iter().enumerate() {
+            if let Some(v) = counts.get(&(r.clone(), c.clone())) {
+                vals[i][j] = *v;
+            }
+        }
+    }
+    (rows, cols, vals)
+}
+
+fn usage_chart(
+    counts: &BTreeMap<(String, String), f64>,
+    cats: &[String],
+    segment: &str,
+    tail_opts: &TailOpts,
+) -> String {
+    let genes = counts
+        .keys()
+        .map(|k| k.1.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let dc = default_colors();
+    let mut series = Vec::<BarSeries>::new();
+    for (k, cat) in cats.iter().enumerate() {
+        let mut total = 0.0;
+        for g in genes.iter() {
+            total += counts.get(&(cat.clone(), g.clone())).unwrap_or(&0.0);
+        }
+        let values = genes
+            .iter()
+            .map(|g| {
+                let n = *counts.get(&(cat.clone(), g.clone())).unwrap_or(&0.0);
+                if total > 0.0 {
+                    100.0 * n / total
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+        let c = &dc[k % dc.len()];
+        series.push(BarSeries {
+            name: cat.clone(),
+            values,
+            color: (c[0], c[1], c[2]),
+        });
+    }
+    let weight = if tail_opts.gene_plot_by_clonotype {
+        "clonotypes"
+    } else {
+        "cells"
+    };
+    let mut title = format!("{} gene usage", segment);
+    if !tail_opts.gene_plot_chain.is_empty() {
+        title = format!("{} {}", tail_opts.gene_plot_chain, title);
+    }
+    grouped_bar_chart(
+        &title,
+        &genes,
+        &format!("{} gene", segment),
+        &format!("% of {}", weight),
+        &series,
+    )
+}
+
+pub fn plot_gene_usage(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    refdata: &RefData,
+    groups: &Vec<Vec<(i32, String)>>,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    if tail_opts.plot_vgene.is_empty()
+        && tail_opts.plot_jgene.is_empty()
+        && tail_opts.plot_vj_heatmap.is_empty()
+        && tail_opts.plot_hl_heatmap.is_empty()
+    {
+        return Ok(());
+    }
+    let by_clonotype = tail_opts.gene_plot_by_clonotype;
+
+    // Tabulate counts.
+
+    let mut cats = BTreeSet::<String>::new();
+    let mut vcounts = BTreeMap::<(String, String), f64>::new();
+    let mut jcounts = BTreeMap::<(String, String), f64>::new();
+    let mut vjcounts = BTreeMap::<(String, String), f64>::new();
+    let mut hlcounts = BTreeMap::<(String, String), f64>::new();
+    for i in 0..groups.len() {
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            let (mut vseen, mut jseen) = (HashSet::new(), HashSet::new());
+            let (mut vjseen, mut hlseen) = (HashSet::new(), HashSet::new());
+            for u in 0..exacts[oo].len() {
+                let ex = &exact_clonotypes[exacts[oo][u]];
+
+                // Find the category of each cell.
+
+                let mut cell_cats = BTreeMap::<String, usize>::new();
+                for m in 0..ex.ncells() {
+                    let cat = if tail_opts.gene_plot_by_origin {
+                        match ex.clones[m][0].origin_index {
+                            Some(x) => ctl.origin_info.origin_list[x].clone(),
+                            None => "unknown".to_string(),
+                        }
+                    } else {
+                        ctl.origin_info.dataset_id[ex.clones[m][0].dataset_index].clone()
+                    };
+                    *cell_cats.entry(cat).or_insert(0) += 1;
+                }
+
+                // Count chains.
+
+                for k in 0..ex.share.len() {
+                    let s = &ex.share[k];
+                    let chain_type = CHAIN_TYPES[refdata.rtype[s.v_ref_id] as usize];
+                    if !tail_opts.gene_plot_chain.is_empty()
+                        && chain_type != tail_opts.gene_plot_chain
+                    {
+                        continue;
+                    }
+                    let vname = refdata.name[s.v_ref_id].clone();
+                    let jname = refdata.name[s.j_ref_id].clone();
+                    for (cat, n) in cell_cats.iter() {
+                        cats.insert(cat.clone());
+                        let vkey = (cat.clone(), vname.clone());
+                        add_count(&mut vcounts, &mut vseen, vkey, *n, by_clonotype);
+                        let jkey = (cat.clone(), jname.clone());
+                        add_count(&mut jcounts, &mut jseen, jkey, *n, by_clonotype);
+                    }
+                    let vjkey = (vname, jname);
+                    add_count(&mut vjcounts, &mut vjseen, vjkey, ex.ncells(), by_clonotype);
+                }
+
+                // Count heavy/light pairings.
+
+                for k1 in 0..ex.share.len() {
+                    if !ex.share[k1].left {
+                        continue;
+                    }
+                    for k2 in 0..ex.share.len() {
+                        if ex.share[k2].left {
+                            continue;
+                        }
+                        let hlkey = (
+                            refdata.name[ex.share[k1].v_ref_id].clone(),
+                            refdata.name[ex.share[k2].v_ref_id].clone(),
+                        );
+                        add_count(&mut hlcounts, &mut hlseen, hlkey, ex.ncells(), by_clonotype);
+                    }
+                }
+            }
+        }
+    }
+    let cats = cats.into_iter().collect::<Vec<_>>();
+    let weight = if by_clonotype { "clonotypes" } else { "cells" };
+
+    // Make the plots.
+
+    if !tail_opts.plot_vgene.is_empty() {
+        if vcounts.is_empty() {
+            return Err("\nPLOT_VGENE: there are no chains to plot.\n".to_string());
+        }
+        let svg = usage_chart(&vcounts, &cats, "V", tail_opts);
+        emit_svg(svg, &tail_opts.plot_vgene, svgs);
+    }
+    if !tail_opts.plot_jgene.is_empty() {
+        if jcounts.is_empty() {
+            return Err("\nPLOT_JGENE: there are no chains to plot.\n".to_string());
+        }
+        let svg = usage_chart(&jcounts, &cats, "J", tail_opts);
+        emit_svg(svg, &tail_opts.plot_jgene, svgs);
+    }
+    if !tail_opts.plot_vj_heatmap.is_empty() {
+        if vjcounts.is_empty() {
+            return Err("\nPLOT_VJ_HEATMAP: there are no chains to plot.\n".to_string());
+        }
+        let (rows, cols, vals) = to_matrix(&vjcounts);
+        let svg = heatmap(
+            "V/J gene pairing",
+            &rows,
+            &cols,
+            &vals,
+            "V gene",
+            "J gene",
+            weight,
+        );
+        emit_svg(svg, &tail_opts.plot_vj_heatmap, svgs);
+    }
+    if !tail_opts.plot_hl_heatmap.is_empty() {
+        if hlcounts.is_empty() {
+            return Err(
+                "\nPLOT_HL_HEATMAP: there are no heavy/light chain pairs to plot.\n".to_string(),
+            );
+        }
+        let (rows, cols, vals) = to_matrix(&hlcounts);
+        let svg = heatmap(
+            "heavy/light V gene pairing",
+            &rows,
+            &cols,
+            &vals,
+            "heavy or TRB V gene",
+            "light or TRA V gene",
+            weight,
+        );
+        emit_svg(svg, &tail_opts.plot_hl_heatmap, svgs);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_count_and_to_matrix() {
+        // Per cell, every call adds its cells.  Per clonotype, each key is counted once.
+
+        let (mut by_cell, mut by_clonotype) = (BTreeMap::new(), BTreeMap::new());
+        let (mut seen1, mut seen2) = (HashSet::new(), HashSet::new());
+        let keys = [
+            ("IGHV3-23", "IGHJ4", 3),
+            ("IGHV3-23", "IGHJ4", 2),
+            ("IGHV1-2", "IGHJ6", 1),
+        ];
+        for (v, j, n) in keys.iter() {
+            let key = (v.to_string(), j.to_string());
+            add_count(&mut by_cell, &mut seen1, key.clone(), *n, false);
+            add_count(&mut by_clonotype, &mut seen2, key, *n, true);
+        }
+        let (rows, cols, vals) = to_matrix(&by_cell);
+        assert_eq!(rows, vec!["IGHV1-2", "IGHV3-23"]);
+        assert_eq!(cols, vec!["IGHJ4", "IGHJ6"]);
+        assert_eq!(vals, vec![vec![0.0, 1.0], vec![5.0, 0.0]]);
+        let (_, _, vals) = to_matrix(&by_clonotype);
+        assert_eq!(vals, vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+    }
+}
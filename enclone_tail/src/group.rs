@@ -6,15 +6,24 @@
 // To keep compilation time down, this crate should not reach into the enclone crate.
 
 use crate::align_n::align_n;
+use crate::alluvial_plot::plot_alluvial;
+use crate::anndata::write_anndata_obs;
 use crate::clustal::print_clustal;
 use crate::fasta::generate_fasta;
+use crate::gene_usage::plot_gene_usage;
+use crate::html_plot::html_plot;
+use crate::logo::plot_logos;
 use crate::parseable::generate_parseable_output;
 use crate::phylip::print_phylip;
 use crate::plot::plot_clonotypes;
-use crate::plot_points::plot_points;
+use crate::plot_xy::plot_xy;
+use crate::plot_xy_facet::plot_xy_facet;
+use crate::plots::make_plots;
 use crate::print_stats::print_stats;
 use crate::requirements::test_requirements;
 use crate::sim_mat_plot::sim_mat_plot;
+use crate::spectratype::plot_spectratype;
+use crate::tail_opts::TailOpts;
 use crate::tree::print_tree;
 use ansi_escape::ansi_to_html::{
     compress_ansi_escapes, convert_text_with_ansi_escapes_to_html,
@@ -30,7 +39,7 @@ use enclone_core::defs::{ColInfo, EncloneControl, ExactClonotype, GexInfo};
 use enclone_core::enclone_structs::{BarcodeFates, JoinInfo};
 use enclone_help::help_utils::font_face_in_css;
 use enclone_proto::types::DonorReferenceItem;
-use io_utils::{fwrite, fwriteln, open_for_write_new};
+use io_utils::{fwrite, fwriteln};
 use itertools::Itertools;
 use std::cmp::max;
 use std::collections::HashMap;
@@ -54,6 +63,7 @@ pub fn group_and_print_clonotypes(
     rsi: &Vec<ColInfo>,
     exact_clonotypes: &Vec<ExactClonotype>,
     ctl: &EncloneControl,
+    tail_opts: &TailOpts,
     out_datas: &mut Vec<Vec<HashMap<String, String>>>,
     join_info: &Vec<JoinInfo>,
     gex_info: &GexInfo,
@@ -271,10 +281,10 @@ pub fn group_and_print_clonotypes(
         );
     }
 
-    // Now print clonotypes.
+    // Now print clonotypes.  Record which groups are shown, which excludes those that are
+    // skipped because of FAILS_ONLY.  PLOT_XY is restricted to them.
 
-    let mut plot_xy_vals = Vec::<(u32, (u8, u8, u8), f32, f32)>::new();
-    let mut plot_xy_comments = Vec::<String>::new();
+    let mut shown = vec![true; groups.len()];
     for i in 0..groups.len() {
         let mut o = Vec::<i32>::new();
         for j in 0..groups[i].len() {
@@ -304,6 +314,7 @@ pub fn group_and_print_clonotypes(
                     }
                 }
                 if !fail {
+                    shown[i] = false;
                     continue;
                 }
             }
@@ -342,49 +353,6 @@ pub fn group_and_print_clonotypes(
         for j in 0..o.len() {
             let oo = o[j] as usize;
 
-            // Generate data for PLOT_XY.
-
-            if !ctl.plot_opt.plot_xy_filename.is_empty() {
-                let xvar = &ctl.plot_opt.plot_xy_xvar;
-                let yvar = &ctl.plot_opt.plot_xy_yvar;
-                for i in 0..out_datas[oo].len() {
-                    let p = &out_datas[oo][i];
-                    if p.contains_key(&xvar.clone()) {
-                        let x = &p[&xvar.clone()];
-                        if x.parse::<f64>().is_ok() {
-                            let mut x = x.force_f64();
-                            if ctl.plot_opt.plot_xy_x_log10 {
-                                if x <= 0.0 {
-                                    continue;
-                                }
-                                x = x.log10();
-                            }
-                            if p.contains_key(&yvar.clone()) {
-                                let y = &p[&yvar.clone()];
-                                if y.parse::<f64>().is_ok() {
-                                    let mut y = y.force_f64();
-                                    if ctl.plot_opt.plot_xy_y_log10 {
-                                        if y <= 0.0 {
-                                            continue;
-                                        }
-                                        y = y.log10();
-                                    }
-                                    plot_xy_vals.push((4, (255, 0, 0), x as f32, y as f32));
-                                    let group_id = i;
-                                    let clonotype_id = j;
-                                    let com = format!(
-                                        "data-tooltip='{{\"group_id\":\"{}\",\"clonotype_id\":\"{}\"}}'",
-                                        group_id + 1,
-                                        clonotype_id + 1,
-                                    );
-                                    plot_xy_comments.push(com);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
             // Proceed.
 
             if !ctl.gen_opt.noprint {
@@ -679,59 +647,58 @@ pub fn group_and_print_clonotypes(
 
     // Execute SIM_MAT_PLOT.
 
-    sim_mat_plot(ctl, groups, out_datas, svgs);
+    sim_mat_plot(&ctl.plot_opt, groups, out_datas, svgs);
 
-    // Execute PLOT_XY.
+    // Execute ANNDATA.
 
-    if !ctl.plot_opt.plot_xy_filename.is_empty() {
-        let mut xvar = ctl.plot_opt.plot_xy_xvar.clone();
-        if ctl.plot_opt.plot_xy_x_log10 {
-            xvar = format!("log10({})", xvar);
-        }
-        let mut yvar = ctl.plot_opt.plot_xy_yvar.clone();
-        if ctl.plot_opt.plot_xy_y_log10 {
-            yvar = format!("log10({})", yvar);
-        }
-        let filename = ctl.plot_opt.plot_xy_filename.clone();
-        let mut svg = String::new();
-        plot_points(
-            &plot_xy_vals,
-            &xvar,
-            &yvar,
-            &mut svg,
-            ctl.plot_opt.plot_xy_sym,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-        )?;
-        if filename == "stdout" || filename == "gui_stdout" {
-            for line in svg.lines() {
-                println!("{}", line);
-            }
-        } else if filename == "gui" {
-            // Add tooltip notes.
-
-            let mut svg2 = String::new();
-            let mut count = 0;
-            for line in svg.lines() {
-                let mut s = line.to_string();
-                if s.starts_with("<circle ") {
-                    s = format!("<circle {}{}", plot_xy_comments[count], s.after("<circle"));
-                    count += 1;
-                }
-                svg2 += &mut format!("{}\n", s);
-            }
+    write_anndata_obs(ctl, tail_opts, groups, exacts, exact_clonotypes, out_datas)?;
 
-            // Save.
+    // Execute PLOT_VGENE, PLOT_JGENE, PLOT_VJ_HEATMAP and PLOT_HL_HEATMAP.
 
-            svgs.push(svg2);
-        } else {
-            let mut f = open_for_write_new![&filename];
-            fwrite!(f, "{}", svg);
-        }
+    plot_gene_usage(
+        ctl,
+        tail_opts,
+        refdata,
+        groups,
+        exacts,
+        exact_clonotypes,
+        svgs,
+    )?;
+
+    // Execute SPECTRATYPE.
+
+    plot_spectratype(
+        ctl,
+        tail_opts,
+        refdata,
+        groups,
+        exacts,
+        exact_clonotypes,
+        svgs,
+    )?;
+
+    // Execute LOGO and PEER_LOGO.
+
+    plot_logos(tail_opts, refdata, groups, exacts, exact_clonotypes, svgs)?;
+
+    // Execute ALLUVIAL.
+
+    plot_alluvial(ctl, tail_opts, groups, exacts, exact_clonotypes, svgs)?;
+
+    // Execute PLOT_XY.
+
+    plot_xy_facet(
+        ctl,
+        tail_opts,
+        groups,
+        &shown,
+        exacts,
+        exact_clonotypes,
+        out_datas,
+        svgs,
+    )?;
+    if !tail_opts.plot_xy_extended() {
+        plot_xy(&ctl.plot_opt, groups, &shown, out_datas, svgs)?;
     }
 
     // Finish CLUSTAL.
@@ -807,6 +774,7 @@ pub fn group_and_print_clonotypes(
     plot_clonotypes(
         ctl,
         &plot_opt,
+        &tail_opts.plot_layout,
         refdata,
         exacts,
         exact_clonotypes,
@@ -826,6 +794,33 @@ pub fn group_and_print_clonotypes(
         svgs.push(svg);
     }
 
+    // Execute HTML_PLOT.
+
+    html_plot(
+        ctl,
+        tail_opts,
+        refdata,
+        pics,
+        exacts,
+        exact_clonotypes,
+        out_datas,
+        groups,
+    )?;
+
+    // Execute PLOTS.
+
+    make_plots(
+        ctl,
+        tail_opts,
+        refdata,
+        exacts,
+        exact_clonotypes,
+        out_datas,
+        groups,
+        &shown,
+        svgs,
+    )?;
+
     // Test requirements.
 
     test_requirements(
@@ -0,0 +1,216 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Make an svg heatmap.  Each cell of the matrix is colored using the turbo color scale, scaled
+// so that the maximum value is at the top of the scale.  Cells with value zero are left white,
+// so that absent pairings stand out.  A color legend is shown at the right.
+
+use crate::colors::TURBO_SRGB_BYTES;
+use crate::plot_utils::{svg_escape, svg_header};
+use crate::string_width::arial_width;
+
+fn turbo_color(v: f64, max: f64) -> String {
+    if v <= 0.0 {
+        return "white".to_string();
+    }
+    let c = &TURBO_SRGB_BYTES[((v / max) * 255.0).round().min(255.0) as usize];
+    format!("rgb({},{},{})", c[0], c[1], c[2])
+}
+
+pub fn heatmap(
+    title: &str,
+    row_labels: &[String],
+    col_labels: &[String],
+    vals: &[Vec<f64>], // vals[row][col]
+    row_desc: &str,
+    col_desc: &str,
+    value_desc: &str,
+) -> String {
+    // Define parameters of the plot.
+
+    let font = "arial";
+    let title_font_size = 20.0;
+    let font_size = 12.0;
+    let margin = 20.0;
+    let cell = 14.0;
+    let legend_height = 200.0;
+    let legend_bar = 16.0;
+
+    // Determine the sizes of the plot areas.
+
+    let mut max = 0.0_f64;
+    for r in vals.iter() {
+        for v in r.iter() {
+            max = max.max(*v);
+        }
+    }
+    if max == 0.0 {
+        max = 1.0;
+    }
+    let mut row_width = 0.0_f64;
+    for x in row_labels.iter() {
+        row_width = row_width.max(arial_width(x, font_size));
+    }
+    let mut col_width = 0.0_f64;
+    for x in col_labels.iter() {
+        col_width = col_width.max(arial_width(x, font_size));
+    }
+    let max_label = format!("{:.1}", max);
+    let legend_width = legend_bar
+        + 8.0
+        + f64::max(
+            arial_width(&max_label, font_size),
+            arial_width(value_desc, font_size),
+        );
+    let plot_width = col_labels.len() as f64 * cell;
+    let plot_height = row_labels.len() as f64 * cell;
+    let x0 = margin + 2.0 * font_size + row_width + 6.0;
+    let y0 = margin + 2.0 * title_font_size + 2.0 * font_size + col_width + 6.0;
+    let width = x0 + plot_width + 30.0 + legend_width + margin;
+    let height = y0 + f64::max(plot_height, legend_height + 2.0 * font_size) + margin;
+
+    // Start the svg.
+
+    let mut svg = svg_header(width, height);
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+         text-anchor=\"middle\">{}</text>\n",
+        width / 2.0,
+        margin + title_font_size,
+        font,
+        title_font_size,
+        svg_escape(title)
+    );
+
+    // Draw the axis descriptions and labels.
+
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+         text-anchor=\"middle\">{}</text>\n",
+        x0 + plot_width / 2.0,
+        margin + 2.0 * title_font_size + font_size,
+        font,
+        font_size,
+        svg_escape(col_desc)
+    );
+    svg += &format!(
+        "<text transform=\"translate({:.1},{:.1}) rotate(-90)\" font-family=\"{}\" \
+         font-size=\"{}\" text-anchor=\"middle\">{}</text>\n",
+        margin + font_size,
+        y0 + plot_height / 2.0,
+        font,
+        font_size,
+        svg_escape(row_desc)
+    );
+    for (i, x) in row_labels.iter().enumerate() {
+        svg += &format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+             text-anchor=\"end\">{}</text>\n",
+            x0 - 4.0,
+            y0 + (i as f64 + 1.0) * cell - 3.0,
+            font,
+            font_size,
+            svg_escape(x)
+        );
+    }
+    for (j, x) in col_labels.iter().enumerate() {
+        svg += &format!(
+            "<text transform=\"translate({:.1},{:.1}) rotate(-90)\" font-family=\"{}\" \
+             font-size=\"{}\" text-anchor=\"start\">{}</text>\n",
+            x0 + (j as f64 + 1.0) * cell - 3.0,
+            y0 - 4.0,
+            font,
+            font_size,
+            svg_escape(x)
+        );
+    }
+
+    // Draw the cells.
+
+    for i in 0..row_labels.len() {
+        for j in 0..col_labels.len() {
+            let v = vals[i][j];
+            svg += &format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" fill=\"{}\" \
+                 stroke=\"rgb(230,230,230)\" stroke-width=\"0.5\">\
+                 <title>{} / {} = {:.2}</title></rect>\n",
+                x0 + j as f64 * cell,
+                y0 + i as f64 * cell,
+                cell,
+                cell,
+                turbo_color(v, max),
+                svg_escape(&row_labels[i]),
+                svg_escape(&col_labels[j]),
+                v
+            );
+        }
+    }
+
+    // Draw the color legend, from zero at the bottom to max at the top.
+
+    let lx = x0 + plot_width + 30.0;
+    let ly = y0 + font_size + 4.0;
+    let steps = 64;
+    for k in 0..steps {
+        let v = max * (steps - k) as f64 / steps as f64;
+        svg += &format!(
+            "<rect x=\"{:.1}\" y=\"{:.2}\" width=\"{}\" height=\"{:.2}\" fill=\"{}\" />\n",
+            lx,
+            ly + k as f64 * legend_height / steps as f64,
+            legend_bar,
+            legend_height / steps as f64 + 0.5,
+            turbo_color(v, max)
+        );
+    }
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\">{}</text>\n\
+         <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\">{}</text>\n\
+         <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\">0</text>\n",
+        lx,
+        y0,
+        font,
+        font_size,
+        svg_escape(value_desc),
+        lx + legend_bar + 4.0,
+        ly + font_size / 2.0,
+        font,
+        font_size,
+        max_label,
+        lx + legend_bar + 4.0,
+        ly + legend_height,
+        font,
+        font_size
+    );
+    svg += "</svg>\n";
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap() {
+        // Zero is white, and the maximum is at the top of the scale.
+
+        assert_eq!(turbo_color(0.0, 5.0), "white");
+        let c = &TURBO_SRGB_BYTES[255];
+        assert_eq!(
+            turbo_color(5.0, 5.0),
+            format!("rgb({},{},{})", c[0], c[1], c[2])
+        );
+
+        // There is one cell for each pair of row and column.
+
+        let rows = vec!["IGHV1-2".to_string(), "IGHV3-23".to_string()];
+        let cols = vec![
+            "IGKV1-5".to_string(),
+            "IGKV3-20".to_string(),
+            "<x>".to_string(),
+        ];
+        let vals = vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0]];
+        let svg = heatmap("pairing", &rows, &cols, &vals, "heavy", "light", "cells");
+        assert_eq!(svg.matches("<title>").count(), 6);
+        assert!(svg.contains("<title>IGHV3-23 / &lt;x&gt; = 5.00</title>"));
+        assert!(svg.contains(">5.0</text>"));
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute HTML_PLOT.  Make the honeycomb plot, exactly as for PLOT (and using the same coloring
+// options), and embed it in a self-contained html page.  Hovering over a cell shows its barcode,
+// dataset, clonotype id and CDR3s, and the values of the variables given by HTML_PLOT_VARS.
+// Clicking on a cell shows the enclone table for its clonotype.  There are no external
+// dependencies: the data and javascript are included in the page.
+//
+// The plot is made using the tooltip attributes of the circles, which are also used by the GUI,
+// and which identify cells by group id, clonotype id and barcode, and if there is more than one
+// dataset, dataset index.  We use the same key for the per-cell data, with dataset index 0 if
+// there is one dataset.
+
+use crate::anndata::cell_value;
+use crate::plot::plot_clonotypes;
+use crate::plot_utils::CHAIN_TYPES;
+use crate::tail_opts::TailOpts;
+use ansi_escape::ansi_to_html::convert_text_with_ansi_escapes_to_html;
+use enclone_core::defs::{EncloneControl, ExactClonotype};
+use io_utils::{fwrite, open_for_write_new};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use vdj_ann::refx::RefData;
+
+const HTML_PLOT_STYLE: &str = r#"
+body { font-family: Arial, sans-serif; margin: 20px; }
+#tip { position: absolute; display: none; pointer-events: none; background: white;
+  border: 1px solid #888; border-radius: 4px; padding: 6px 8px; font-size: 13px;
+  box-shadow: 2px 2px 6px rgba(0,0,0,0.2); }
+#tip table { border-collapse: collapse; }
+#tip td { padding: 0 6px 0 0; vertical-align: top; }
+circle[tooltip] { cursor: pointer; }
+circle.selected { stroke: black; stroke-width: 1.5; }
+#table { display: none; width: 100%; height: 600px; border: 1px solid #ccc; margin-top: 20px; }
+"#;
+
+const HTML_PLOT_SCRIPT: &str = r#"
+const tip = document.getElementById("tip");
+const table = document.getElementById("table");
+function esc(s) {
+  return String(s).replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;");
+}
+function parse(c) {
+  const m = /group_id=(\d+),clonotype_id=(\d+),barcode=([^,]*)(?:,dataset_index=(\d+))?/
+    .exec(c.getAttribute("tooltip"));
+  return m ? { clonotype: m[1] + "." + m[2], barcode: m[3], dataset: m[4] || "0" } : null;
+}
+for (const c of document.querySelectorAll("circle[tooltip]")) {
+  c.addEventListener("mousemove", (e) => {
+    const p = parse(c);
+    if (!p) return;
+    const cell = CELLS[p.clonotype + ":" + p.dataset + ":" + p.barcode] || {};
+    let rows = [["barcode", p.barcode], ["clonotype", p.clonotype]];
+    for (const [k, v] of Object.entries(cell)) rows.push([k, v]);
+    for (const x of (CLONOTYPES[p.clonotype] || {}).cdr3s || []) rows.push(["CDR3", x]);
+    tip.innerHTML = "<table>" + rows.map(r => "<tr><td><b>" + esc(r[0]) + "</b></td><td>"
+      + esc(r[1]) + "</td></tr>").join("") + "</table>";
+    tip.style.left = (e.pageX + 12) + "px";
+    tip.style.top = (e.pageY + 12) + "px";
+    tip.style.display = "block";
+  });
+  c.addEventListener("mouseleave", () => { tip.style.display = "none"; });
+  c.addEventListener("click", () => {
+    const p = parse(c);
+    if (!p || !CLONOTYPES[p.clonotype]) return;
+    for (const d of document.querySelectorAll("circle.selected")) d.classList.remove("selected");
+    for (const d of document.querySelectorAll("circle[tooltip]")) {
+      const q = parse(d);
+      if (q && q.clonotype == p.clonotype) d.classList.add("selected");
+    }
+    table.srcdoc = CLONOTYPES[p.clonotype].table;
+    table.style.display = "block";
+    table.scrollIntoView({ behavior: "smooth" });
+  });
+}
+"#;
+
+// Embed json in a script element.
+
+fn script_json(x: &Value) -> String {
+    serde_json::to_string(x).unwrap().replace("</", "<\\/")
+}
+
+// Make the page, given the plot, and the data for clonotypes and cells.
+
+fn html_page(svg: &str, clonotypes: Map<String, Value>, cells: Map<String, Value>) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>{}</title>\n\
+         <style>{}</style>\n\
+         </head>\n\
+         <body>\n\
+         <div id=\"plot\">\n{}\n</div>\n\
+         <div id=\"tip\"></div>\n\
+         <iframe id=\"table\"></iframe>\n\
+         <script>\n\
+         const CLONOTYPES = {};\n\
+         const CELLS = {};\n\
+         {}</script>\n\
+         </body>\n\
+         </html>\n",
+        "enclone honeycomb plot",
+        HTML_PLOT_STYLE,
+        svg,
+        script_json(&Value::Object(clonotypes)),
+        script_json(&Value::Object(cells)),
+        HTML_PLOT_SCRIPT
+    )
+}
+
+pub fn html_plot(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    refdata: &RefData,
+    pics: &Vec<String>,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+    groups: &Vec<Vec<(i32, String)>>,
+) -> Result<(), String> {
+    if tail_opts.html_plot.is_empty() {
+        return Ok(());
+    }
+
+    // Make the plot.  Setting the filename to gui causes tooltips to be generated, and nothing
+    // to be written.
+
+    let mut plot_opt = ctl.plot_opt.clone();
+    plot_opt.plot_file = "gui".to_string();
+    let mut svg = String::new();
+    plot_clonotypes(
+        ctl,
+        &plot_opt,
+        &tail_opts.plot_layout,
+        refdata,
+        exacts,
+        exact_clonotypes,
+        out_datas,
+        groups,
+        &mut svg,
+    )?;
+
+    // Gather data for clonotypes and cells.
+
+    let mut clonotypes = Map::new();
+    let mut cells = Map::new();
+    for i in 0..groups.len() {
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            let id = format!("{}.{}", i + 1, j + 1);
+            let mut cdr3s = Vec::<String>::new();
+            for u in 0..exacts[oo].len() {
+                let ex = &exact_clonotypes[exacts[oo][u]];
+                for s in ex.share.iter() {
+                    let x = format!(
+                        "{} {}",
+                        CHAIN_TYPES[refdata.rtype[s.v_ref_id] as usize], s.cdr3_aa
+                    );
+                    if !cdr3s.contains(&x) {
+                        cdr3s.push(x);
+                    }
+                }
+                for m in 0..ex.ncells() {
+                    let mut cell = Map::new();
+                    let li = ex.clones[m][0].dataset_index;
+                    cell.insert("dataset".to_string(), json!(ctl.origin_info.dataset_id[li]));
+                    for v in tail_opts.html_plot_vars.iter() {
+                        cell.insert(v.clone(), json!(cell_value(&out_datas[oo][u], v, m)));
+                    }
+                    let key = format!("{}:{}:{}", id, li, ex.clones[m][0].barcode);
+                    cells.insert(key, Value::Object(cell));
+                }
+            }
+            let table = convert_text_with_ansi_escapes_to_html(
+                &pics[oo],
+                "", // source
+                &format!("clonotype {}", id),
+                "",
+                "monospace",
+                14,
+            );
+            clonotypes.insert(id, json!({ "cdr3s": cdr3s, "table": table }));
+        }
+    }
+
+    // Write the page.
+
+    let html = html_page(&svg, clonotypes, cells);
+    if tail_opts.html_plot == "stdout" {
+        print!("{}", html);
+    } else {
+        let mut f = open_for_write_new![&tail_opts.html_plot];
+        fwrite!(f, "{}", html);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_page() {
+        // Data containing </script> must not end the script early.
+
+        assert_eq!(
+            script_json(&json!({ "a": "</script>" })),
+            r#"{"a":"<\/script>"}"#
+        );
+        let mut clonotypes = Map::new();
+        clonotypes.insert(
+            "1.1".to_string(),
+            json!({ "cdr3s": ["IGH CARDW"], "table": "<html></script></html>" }),
+        );
+        let mut cells = Map::new();
+        cells.insert("1.1:0:AAAC-1".to_string(), json!({ "dataset": "d1" }));
+        cells.insert("1.1:1:AAAC-1".to_string(), json!({ "dataset": "d2" }));
+        let svg =
+            "<svg><circle tooltip=\"group_id=1,clonotype_id=1,barcode=AAAC-1,dataset_index=1\" \
+                   /></svg>";
+        let html = html_page(svg, clonotypes, cells);
+        assert!(html.contains(svg));
+        assert_eq!(html.matches("</script>").count(), 1);
+        assert!(html.contains(
+            "const CELLS = {\"1.1:0:AAAC-1\":{\"dataset\":\"d1\"},\
+             \"1.1:1:AAAC-1\":{\"dataset\":\"d2\"}};"
+        ));
+        assert!(html.contains("\"cdr3s\":[\"IGH CARDW\"]"));
+    }
+}
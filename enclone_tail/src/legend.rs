@@ -1,6 +1,6 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 
-use crate::assign_cell_color::{VAR_HIGH, VAR_LOW};
+use crate::assign_cell_color::PlotColorContext;
 use crate::colors::TURBO_SRGB_BYTES;
 use crate::string_width::arial_width;
 use crate::ticks::ticks;
@@ -11,18 +11,17 @@ use string_utils::TextUtils;
 
 pub fn add_legend_for_color_by_variable(
     plot_opt: &PlotOpt,
+    color_ctx: &PlotColorContext,
     svg: &mut String,
     color: &Vec<String>,
     actual_width: f64,
     actual_height: f64,
 ) {
-    let mut var = String::new();
     let mut display_var = String::new();
     let mut xmin = None;
     let mut xmax = None;
     match plot_opt.cell_color {
         CellColor::ByVariableValue(ref x) => {
-            var = x.var.clone();
             display_var = x.display_var.clone();
             xmin = x.min;
             xmax = x.max;
@@ -40,13 +39,10 @@ pub fn add_legend_for_color_by_variable(
     // Get the actual low and high values for the variable.
 
     let (mut low, mut high) = (0.0, 0.0);
-    let n = VAR_LOW.lock().unwrap().len();
-    for i in 0..n {
-        if VAR_LOW.lock().unwrap()[i].0 == var {
-            low = VAR_LOW.lock().unwrap()[i].1;
-            high = VAR_HIGH.lock().unwrap()[i].1;
-            defined = true;
-        }
+    if let Some(r) = color_ctx.var_range {
+        low = r.0;
+        high = r.1;
+        defined = true;
     }
 
     // Print the variable name.
@@ -3,21 +3,29 @@
 
 pub mod align_n;
 pub mod alluvial_fb;
+pub mod alluvial_plot;
+pub mod anndata;
 pub mod assign_cell_color;
+pub mod bar_chart;
 pub mod cat_var;
 pub mod circles_to_svg;
 pub mod clustal;
 pub mod colors;
+pub mod convert_svg_to_pdf;
 pub mod convert_svg_to_png;
 pub mod display_tree;
 pub mod fasta;
 pub mod fate;
+pub mod gene_usage;
 pub mod group;
 pub mod group_colors;
 pub mod grouper;
+pub mod heatmap;
 pub mod hex;
+pub mod html_plot;
 pub mod keeper_group;
 pub mod legend;
+pub mod logo;
 mod mammalian_fixed_len;
 pub mod neighbor;
 pub mod newick;
@@ -27,6 +35,9 @@ pub mod phylip;
 pub mod plot;
 pub mod plot_points;
 pub mod plot_utils;
+pub mod plot_xy;
+pub mod plot_xy_facet;
+pub mod plots;
 pub mod polygon;
 pub mod print_clonotypes;
 pub mod print_dataset_vars;
@@ -35,8 +46,10 @@ pub mod requirements;
 pub mod sens_spec;
 mod set_speakers;
 pub mod sim_mat_plot;
+pub mod spectratype;
 pub mod string_width;
 pub mod tail;
+pub mod tail_opts;
 pub mod ticks;
 pub mod tree;
 
@@ -0,0 +1,351 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute LOGO and PEER_LOGO, which make svg sequence logos.
+//
+// LOGO makes logos for the CDR3 amino acid sequences of the clonotypes that are shown, weighted
+// by cell count.  Since a logo requires sequences of the same length, there is one logo for
+// each chain type and CDR3 length.  If LOGO_BY=group or LOGO_BY=clonotype, this is done
+// separately for each group or clonotype, rather than for all clonotypes together.
+//
+// PEER_LOGO makes logos for the mammalian peer groups of the V genes that appear in the
+// clonotypes that are shown, as in PEER_GROUP, with one logo per V gene.
+//
+// In each column, the total height is the information content, log2(20) - (H + e), where H is
+// the entropy of the amino acid distribution and e is the usual small sample correction, and
+// the amino acids are stacked in order of frequency, with heights proportional to their
+// frequencies.  Amino acids are colored by their class, following aa_classes.
+
+use crate::mammalian_fixed_len::mammalian_fixed_len_peer_groups;
+use crate::plot_utils::{emit_svg, svg_escape, svg_header, write_pdf, CHAIN_TYPES};
+use crate::print_clonotypes::aa_classes;
+use crate::string_width::arial_width;
+use crate::tail_opts::TailOpts;
+use enclone_core::defs::ExactClonotype;
+use std::collections::{BTreeMap, BTreeSet};
+use vdj_ann::refx::RefData;
+
+// Color an amino acid by the first of the following classes that contains it.
+
+fn aa_color(aa: u8) -> &'static str {
+    let colors = [
+        ('-', "rgb(215,48,39)"),  // acidic
+        ('+', "rgb(33,102,172)"), // basic
+        ('Ω', "rgb(118,42,131)"), // aromatic
+        ('Ψ', "rgb(0,0,0)"),      // aliphatic
+        ('π', "rgb(26,152,80)"),  // small
+        ('ζ', "rgb(241,163,64)"), // polar
+    ];
+    let classes = aa_classes();
+    for &(c, color) in colors.iter() {
+        for x in classes.iter() {
+            if x.0 == c && x.1.contains(&aa) {
+                return color;
+            }
+        }
+    }
+    "rgb(128,128,128)"
+}
+
+// Compute the letter heights for a column, in bits, from the amino acid counts.  The result is
+// sorted so that the most frequent amino acid is last, i.e. on top.
+
+fn column_heights(counts: &BTreeMap<u8, f64>) -> Vec<(u8, f64)> {
+    let n: f64 = counts.values().sum();
+    if n == 0.0 {
+        return Vec::new();
+    }
+    let mut h = 0.0;
+    for c in counts.values() {
+        if *c > 0.0 {
+            let p = c / n;
+            h -= p * p.log2();
+        }
+    }
+    let e = 19.0 / (2.0 * std::f64::consts::LN_2 * n);
+    let ic = (20.0_f64.log2() - (h + e)).max(0.0);
+    let mut x = counts
+        .iter()
+        .map(|(aa, c)| (*aa, ic * c / n))
+        .collect::<Vec<_>>();
+    x.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.0.cmp(&b.0)));
+    x
+}
+
+// Draw a logo with its upper left corner at (x0, y0), and return its height.  The columns are
+// labeled by position, starting at first_pos.
+
+fn draw_logo(
+    svg: &mut String,
+    x0: f64,
+    y0: f64,
+    col_width: f64,
+    title: &str,
+    first_pos: usize,
+    cols: &[BTreeMap<u8, f64>],
+) -> f64 {
+    let font = "arial";
+    let font_size = 12.0;
+    let height = 100.0;
+    let max_bits = 20.0_f64.log2();
+    let cap_height = 0.716; // height of capital letters in arial, relative to font size
+    let top = y0 + 2.0 * font_size;
+    *svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\">{}</text>\n",
+        x0,
+        y0 + font_size,
+        font,
+        font_size + 2.0,
+        svg_escape(title)
+    );
+
+    // Draw the y axis.
+
+    for b in 0..=4 {
+        let y = top + height * (1.0 - b as f64 / max_bits);
+        *svg += &format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+             text-anchor=\"end\">{}</text>\n",
+            x0 - 4.0,
+            y,
+            x0,
+            y,
+            x0 - 6.0,
+            y + font_size / 3.0,
+            font,
+            font_size,
+            b
+        );
+    }
+    *svg += &format!(
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n\
+         <text transform=\"translate({:.1},{:.1}) rotate(-90)\" font-family=\"{}\" \
+         font-size=\"{}\" text-anchor=\"middle\">bits</text>\n",
+        x0,
+        top,
+        x0,
+        top + height,
+        x0 - 22.0,
+        top + height / 2.0,
+        font,
+        font_size
+    );
+
+    // Draw the letters, by scaling glyphs to fill their boxes.
+
+    for (i, col) in cols.iter().enumerate() {
+        let x = x0 + 2.0 + i as f64 * col_width;
+        let mut y = top + height;
+        for (aa, bits) in column_heights(col).iter() {
+            let h = height * bits / max_bits;
+            if h >= 0.5 {
+                let letter = (*aa as char).to_string();
+                let w = arial_width(&letter, 100.0);
+                *svg += &format!(
+                    "<text transform=\"translate({:.2},{:.2}) scale({:.4},{:.4})\" \
+                     font-family=\"{}\" font-size=\"100\" font-weight=\"bold\" \
+                     fill=\"{}\">{}</text>\n",
+                    x,
+                    y,
+                    (col_width - 1.0) / w,
+                    h / (100.0 * cap_height),
+                    font,
+                    aa_color(*aa),
+                    letter
+                );
+            }
+            y -= h;
+        }
+        if cols.len() <= 40 || (first_pos + i) % 5 == 0 {
+            *svg += &format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+                 text-anchor=\"middle\">{}</text>\n",
+                x + col_width / 2.0,
+                top + height + font_size + 2.0,
+                font,
+                font_size - 2.0,
+                first_pos + i
+            );
+        }
+    }
+    2.0 * font_size + height + font_size + 20.0
+}
+
+// Draw a list of logos, one above the other.  Each logo is given by a title, the position of its
+// first column, and the columns.
+
+fn logos_svg(logos: &[(String, usize, Vec<BTreeMap<u8, f64>>)], col_width: f64) -> String {
+    let margin = 20.0;
+    let x0 = margin + 40.0;
+    let mut width = 0.0_f64;
+    for x in logos.iter() {
+        width = width.max(arial_width(&x.0, 14.0));
+        width = width.max(x.2.len() as f64 * col_width + 4.0);
+    }
+    let mut body = String::new();
+    let mut y = margin;
+    for x in logos.iter() {
+        y += draw_logo(&mut body, x0, y, col_width, &x.0, x.1, &x.2);
+    }
+    let (width, height) = (x0 + width + margin, y + margin);
+    format!("{}{}</svg>\n", svg_header(width, height), body)
+}
+
+// Send logos to their destination.  In a pdf file, each logo is on its own page.
+
+fn emit_logos(
+    logos: &[(String, usize, Vec<BTreeMap<u8, f64>>)],
+    col_width: f64,
+    filename: &str,
+    svgs: &mut Vec<String>,
+) {
+    if filename.ends_with(".pdf") {
+        let pages = logos
+            .iter()
+            .map(|x| logos_svg(std::slice::from_ref(x), col_width))
+            .collect::<Vec<_>>();
+        write_pdf(&pages, filename);
+    } else {
+        emit_svg(logos_svg(logos, col_width), filename, svgs);
+    }
+}
+
+pub fn plot_logos(
+    tail_opts: &TailOpts,
+    refdata: &RefData,
+    groups: &Vec<Vec<(i32, String)>>,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    // Execute LOGO.  Sets of CDR3s are keyed by (group, clonotype, chain type, length), where
+    // the group and clonotype are zero if not applicable.
+
+    if !tail_opts.logo.is_empty() {
+        let mut sets = BTreeMap::<(usize, usize, usize, usize), Vec<BTreeMap<u8, f64>>>::new();
+        let mut ncells = BTreeMap::<(usize, usize, usize, usize), usize>::new();
+        for i in 0..groups.len() {
+            for j in 0..groups[i].len() {
+                let oo = groups[i][j].0 as usize;
+                let (g, c) = match tail_opts.logo_by.as_str() {
+                    "group" => (i + 1, 0),
+                    "clonotype" => (i + 1, j + 1),
+                    _ => (0, 0),
+                };
+                for u in 0..exacts[oo].len() {
+                    let ex = &exact_clonotypes[exacts[oo][u]];
+                    for s in ex.share.iter() {
+                        let cdr3 = s.cdr3_aa.as_bytes();
+                        let key = (g, c, refdata.rtype[s.v_ref_id] as usize, cdr3.len());
+                        let cols = sets
+                            .entry(key)
+                            .or_insert_with(|| vec![BTreeMap::new(); cdr3.len()]);
+                        for (p, aa) in cdr3.iter().enumerate() {
+                            *cols[p].entry(*aa).or_insert(0.0) += ex.ncells() as f64;
+                        }
+                        *ncells.entry(key).or_insert(0) += ex.ncells();
+                    }
+                }
+            }
+        }
+        if sets.is_empty() {
+            return Err("\nLOGO: there are no CDR3 sequences to make logos for.\n".to_string());
+        }
+        let mut logos = Vec::new();
+        for (key, cols) in sets.into_iter() {
+            let (g, c, chain, len) = key;
+            let mut title = format!(
+                "{} CDR3, length {}, {} cells",
+                CHAIN_TYPES[chain], len, ncells[&key]
+            );
+            if c > 0 {
+                title = format!("group {}, clonotype {}: {}", g, c, title);
+            } else if g > 0 {
+                title = format!("group {}: {}", g, title);
+            }
+            logos.push((title, 1, cols));
+        }
+        emit_logos(&logos, 24.0, &tail_opts.logo, svgs);
+    }
+
+    // Execute PEER_LOGO.
+
+    if !tail_opts.peer_logo.is_empty() {
+        let mut vids = BTreeSet::<(String, usize)>::new();
+        for i in 0..groups.len() {
+            for j in 0..groups[i].len() {
+                let oo = groups[i][j].0 as usize;
+                for u in 0..exacts[oo].len() {
+                    for s in exact_clonotypes[exacts[oo][u]].share.iter() {
+                        vids.insert((refdata.name[s.v_ref_id].clone(), s.v_ref_id));
+                    }
+                }
+            }
+        }
+        let pg = mammalian_fixed_len_peer_groups(refdata);
+        let mut logos = Vec::new();
+        for (name, id) in vids.iter() {
+            if pg[*id].is_empty() {
+                continue;
+            }
+            let first = pg[*id].iter().map(|x| x.0).min().unwrap();
+            let last = pg[*id].iter().map(|x| x.0).max().unwrap();
+            let mut cols = vec![BTreeMap::<u8, f64>::new(); last - first + 1];
+            for (pos, aa, count) in pg[*id].iter() {
+                *cols[pos - first].entry(*aa).or_insert(0.0) += *count as f64;
+            }
+            logos.push((format!("{} peer group", name), first + 1, cols));
+        }
+        if logos.is_empty() {
+            return Err("\nPEER_LOGO: there are no peer groups to make logos for.\n".to_string());
+        }
+        emit_logos(&logos, 12.0, &tail_opts.peer_logo, svgs);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aa_color() {
+        assert_eq!(aa_color(b'D'), "rgb(215,48,39)");
+        assert_eq!(aa_color(b'H'), "rgb(33,102,172)");
+        assert_eq!(aa_color(b'W'), "rgb(118,42,131)");
+        assert_eq!(aa_color(b'L'), "rgb(0,0,0)");
+        assert_eq!(aa_color(b'G'), "rgb(26,152,80)");
+        assert_eq!(aa_color(b'T'), "rgb(241,163,64)");
+        assert_eq!(aa_color(b'C'), "rgb(128,128,128)");
+    }
+
+    #[test]
+    fn test_column_heights() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1.0e-9;
+        assert!(column_heights(&BTreeMap::new()).is_empty());
+
+        // A conserved column has nearly the maximum information content.
+
+        let x = column_heights(&[(b'A', 100.0)].iter().cloned().collect());
+        assert_eq!(x.len(), 1);
+        let e = 19.0 / (2.0 * std::f64::consts::LN_2 * 100.0);
+        assert!(close(x[0].1, 20.0_f64.log2() - e));
+
+        // Heights are proportional to frequencies, with the most frequent on top.
+
+        let x = column_heights(&[(b'A', 30.0), (b'G', 10.0)].iter().cloned().collect());
+        assert_eq!(x[0].0, b'G');
+        assert_eq!(x[1].0, b'A');
+        assert!(close(x[1].1, 3.0 * x[0].1));
+
+        // A uniform column has no information.
+
+        let x = column_heights(
+            &b"ACDEFGHIKLMNPQRSTVWY"
+                .iter()
+                .map(|aa| (*aa, 1000.0))
+                .collect(),
+        );
+        assert!(x.iter().all(|y| y.1 == 0.0));
+    }
+}
@@ -21,18 +21,19 @@ pub fn newick(vnames: &Vec<String>, r: usize, edges: &Vec<(usize, usize, String)
     // Set up.
 
     let mut edges = edges.clone();
-    let mut n = 0;
-    for i in 0..edges.len() {
-        n = max(n, edges[i].0 + 1);
-        n = max(n, edges[i].1 + 1);
+    let n = vnames.len();
+    for e in edges.iter() {
+        assert!(max(e.0, e.1) < n);
     }
     assert!(r < n);
+    if edges.is_empty() {
+        return format!("{};", vnames[r]);
+    }
     let mut index = vec![Vec::<usize>::new(); n];
     for i in 0..edges.len() {
         index[edges[i].0].push(i);
         index[edges[i].1].push(i);
     }
-    assert_eq!(n, vnames.len());
 
     // Incorporate the vertex names into the weights.
 
@@ -1,18 +1,90 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Circle packing for honeycomb plots.  There are two layouts, selected by PLOT_LAYOUT:
+//
+// random: the original algorithm, which places each circle at the point closest to the origin,
+// among a large random sample of points, for which it does not overlap anything.
+//
+// frontchain: the front-chain algorithm of Wang et al., "Visualization of large hierarchical
+// data by circle packing", CHI 2006, as implemented in d3-hierarchy (packSiblings and
+// packEnclose), https://github.com/d3/d3-hierarchy.  Each circle is placed tangent to two
+// circles on the front chain (the circles on the boundary of the pack), as close to the origin
+// as possible.  This is much faster and yields a tighter pack.
+//
+// Both are deterministic given the seed.
 
 use crate::polygon::{Point, Polygon};
 use rayon::prelude::*;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PackAlgorithm {
+    #[default]
+    Random,
+    FrontChain,
+}
+
+// Layout options for honeycomb plots.  For the random layout, the seed initializes the random
+// number generator.  For the front-chain layout, it determines the orientation of the pack.  If
+// stats is set, the area ratio of each plot (see area_ratio) is printed.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlotLayout {
+    pub algorithm: PackAlgorithm,
+    pub seed: u64,
+    pub stats: bool,
+}
+
+impl PlotLayout {
+    pub fn name(&self) -> &'static str {
+        match self.algorithm {
+            PackAlgorithm::Random => "random",
+            PackAlgorithm::FrontChain => "frontchain",
+        }
+    }
+}
+
 // Pack circles of given radii, which should be in descending order.  Return centers for the
-// circles.  There is probably a literature on this, and this is probably a very crappy algorithm.
-//
-// Should google "d3 circle packing algorithm".  The code is open source.
+// circles.
 //
 // quad: force into first quadrant rather than anywhere
 //
 // Blacklisted polygons are avoided.
 
-pub fn pack_circles(r: &Vec<f64>, blacklist: &Vec<Polygon>, quad: bool) -> Vec<(f64, f64)> {
+pub fn pack_circles(
+    r: &Vec<f64>,
+    blacklist: &Vec<Polygon>,
+    quad: bool,
+    layout: &PlotLayout,
+) -> Vec<(f64, f64)> {
+    match layout.algorithm {
+        PackAlgorithm::Random => pack_circles_random(r, blacklist, quad, layout.seed),
+        PackAlgorithm::FrontChain => pack_circles_front_chain(r, blacklist, quad, layout.seed),
+    }
+}
+
+// Compute the ratio of the total area of the circles to the area of the smallest circle that
+// encloses them.  This is a measure of the tightness of a packing.
+
+pub fn area_ratio(r: &[f64], c: &[(f64, f64)]) -> f64 {
+    if r.is_empty() {
+        return 1.0;
+    }
+    let circles = (0..r.len())
+        .map(|i| (c[i].0, c[i].1, r[i]))
+        .collect::<Vec<_>>();
+    let e = enclose(&circles, 0);
+    r.iter().map(|x| x * x).sum::<f64>() / (e.2 * e.2)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+//
+// RANDOM LAYOUT
+//
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// This is probably a very crappy algorithm.
+
+fn pack_circles_random(r: &[f64], blacklist: &[Polygon], quad: bool, seed: u64) -> Vec<(f64, f64)> {
     // Set up to track the centers of the circles.
 
     let mut c = Vec::<(f64, f64)>::new();
@@ -75,7 +147,7 @@ pub fn pack_circles(r: &Vec<f64>, blacklist: &Vec<Polygon>, quad: bool) -> Vec<(
 
     // Proceed.
 
-    let mut rand = 0i64;
+    let mut rand = seed as i64;
     // We use a ridiculously large sample.  Reducing it to 10,000 noticeably reduces symmetry.
     // Presumably as the number of clusters increases, the sample would need to be increased
     // (ideally) to increase symmetry.
@@ -219,3 +291,408 @@ pub fn pack_circles(r: &Vec<f64>, blacklist: &Vec<Polygon>, quad: bool) -> Vec<(
     }
     c
 }
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+//
+// FRONT-CHAIN LAYOUT
+//
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// A circle is represented as (x, y, r).
+
+type Circle = (f64, f64, f64);
+
+// Hand-rolled random number generator, as for the random layout.
+
+fn lcg(x: u64) -> u64 {
+    6_364_136_223_846_793_005u64
+        .wrapping_mul(x)
+        .wrapping_add(1_442_695_040_888_963_407)
+}
+
+// Place a circle of radius r tangent to circles a and b.
+
+fn place(b: Circle, a: Circle, r: f64) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let d2 = dx * dx + dy * dy;
+    if d2 > 0.0 {
+        let a2 = (a.2 + r) * (a.2 + r);
+        let b2 = (b.2 + r) * (b.2 + r);
+        if a2 > b2 {
+            let x = (d2 + b2 - a2) / (2.0 * d2);
+            let y = (b2 / d2 - x * x).max(0.0).sqrt();
+            (b.0 - x * dx - y * dy, b.1 - x * dy + y * dx)
+        } else {
+            let x = (d2 + a2 - b2) / (2.0 * d2);
+            let y = (a2 / d2 - x * x).max(0.0).sqrt();
+            (a.0 + x * dx - y * dy, a.1 + x * dy + y * dx)
+        }
+    } else {
+        (a.0 + r, a.1)
+    }
+}
+
+fn intersects(a: Circle, b: Circle) -> bool {
+    let dr = a.2 + b.2 - 1e-6;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    dr > 0.0 && dr * dr > dx * dx + dy * dy
+}
+
+// Pack circles about the origin, returning their centers, and the indices of the circles on the
+// front chain.  This is packSiblings from d3.  The front chain is a circular doubly-linked list,
+// represented by the vectors next and prev.
+
+fn pack_siblings(r: &[f64]) -> (Vec<(f64, f64)>, Vec<usize>) {
+    let n = r.len();
+    let mut c = vec![(0.0, 0.0); n];
+    if n <= 1 {
+        return (c, (0..n).collect());
+    }
+    c[0] = (-r[1], 0.0);
+    c[1] = (r[0], 0.0);
+    if n == 2 {
+        return (c, vec![0, 1]);
+    }
+    let circle = |c: &Vec<(f64, f64)>, i: usize| (c[i].0, c[i].1, r[i]);
+    c[2] = place(circle(&c, 1), circle(&c, 0), r[2]);
+
+    // Initialize the front chain using the first three circles.
+
+    let mut next = vec![0; n];
+    let mut prev = vec![0; n];
+    next[0] = 1;
+    next[1] = 2;
+    next[2] = 0;
+    prev[0] = 2;
+    prev[1] = 0;
+    prev[2] = 1;
+    let (mut a, mut b) = (0, 1);
+
+    // Score a node on the front chain by the distance from the origin to the weighted midpoint
+    // of it and its successor.
+
+    let score = |c: &Vec<(f64, f64)>, next: &Vec<usize>, i: usize| {
+        let j = next[i];
+        let ab = r[i] + r[j];
+        let dx = (c[i].0 * r[j] + c[j].0 * r[i]) / ab;
+        let dy = (c[i].1 * r[j] + c[j].1 * r[i]) / ab;
+        dx * dx + dy * dy
+    };
+
+    // Attempt to place each remaining circle.
+
+    let mut i = 3;
+    while i < n {
+        let p = place(circle(&c, a), circle(&c, b), r[i]);
+        c[i] = p;
+        let ci = (p.0, p.1, r[i]);
+
+        // Find the closest intersecting circle on the front chain, if any.  A circle is closer
+        // if its weight (the sum of the radii along the chain) is smaller.  If there is an
+        // intersection, cut the chain there and try again.
+
+        let (mut j, mut k) = (next[b], prev[a]);
+        let (mut sj, mut sk) = (r[b], r[a]);
+        let mut hit = false;
+        loop {
+            if sj <= sk {
+                if intersects(circle(&c, j), ci) {
+                    b = j;
+                    next[a] = b;
+                    prev[b] = a;
+                    hit = true;
+                    break;
+                }
+                sj += r[j];
+                j = next[j];
+            } else {
+                if intersects(circle(&c, k), ci) {
+                    a = k;
+                    next[a] = b;
+                    prev[b] = a;
+                    hit = true;
+                    break;
+                }
+                sk += r[k];
+                k = prev[k];
+            }
+            if j == next[k] {
+                break;
+            }
+        }
+        if hit {
+            continue;
+        }
+
+        // Success: insert the new circle between a and b.
+
+        prev[i] = a;
+        next[i] = b;
+        next[a] = i;
+        prev[b] = i;
+        b = i;
+
+        // Compute the new closest circle pair to the centroid.
+
+        let mut aa = score(&c, &next, a);
+        let mut x = next[b];
+        while x != b {
+            let s = score(&c, &next, x);
+            if s < aa {
+                a = x;
+                aa = s;
+            }
+            x = next[x];
+        }
+        b = next[a];
+        i += 1;
+    }
+
+    // Traverse the front chain.
+
+    let mut chain = vec![b];
+    let mut x = next[b];
+    while x != b {
+        chain.push(x);
+        x = next[x];
+    }
+    (c, chain)
+}
+
+// Find the smallest circle enclosing the given circles.  This is packEnclose from d3, which is
+// Welzl's algorithm, generalized to circles.  The order in which the circles are processed is
+// randomized using the seed, but the answer does not depend on it, up to rounding.
+
+pub fn enclose(circles: &[Circle], seed: u64) -> Circle {
+    let mut circles = circles.to_vec();
+    let mut x = seed;
+    for i in (1..circles.len()).rev() {
+        x = lcg(x);
+        circles.swap(i, ((x >> 33) % (i as u64 + 1)) as usize);
+    }
+    let mut e: Option<Circle> = None;
+    let mut basis = Vec::<Circle>::new();
+    let mut i = 0;
+    while i < circles.len() {
+        let p = circles[i];
+        match e {
+            Some(e) if encloses_weak(e, p) => i += 1,
+            _ => {
+                basis = extend_basis(&basis, p);
+                e = Some(enclose_basis(&basis));
+                i = 0;
+            }
+        }
+    }
+    e.unwrap_or((0.0, 0.0, 0.0))
+}
+
+fn extend_basis(basis: &[Circle], p: Circle) -> Vec<Circle> {
+    if encloses_weak_all(p, basis) {
+        return vec![p];
+    }
+
+    // If we get here then the basis must have at least one element.
+
+    for i in 0..basis.len() {
+        if encloses_not(p, basis[i]) && encloses_weak_all(enclose_basis2(basis[i], p), basis) {
+            return vec![basis[i], p];
+        }
+    }
+
+    // If we get here then the basis must have at least two elements.
+
+    for i in 0..basis.len() {
+        for j in i + 1..basis.len() {
+            if encloses_not(enclose_basis2(basis[i], basis[j]), p)
+                && encloses_not(enclose_basis2(basis[i], p), basis[j])
+                && encloses_not(enclose_basis2(basis[j], p), basis[i])
+                && encloses_weak_all(enclose_basis3(basis[i], basis[j], p), basis)
+            {
+                return vec![basis[i], basis[j], p];
+            }
+        }
+    }
+    panic!("Circle enclosure failed, which should not be possible.");
+}
+
+fn encloses_not(a: Circle, b: Circle) -> bool {
+    let dr = a.2 - b.2;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    dr < 0.0 || dr * dr < dx * dx + dy * dy
+}
+
+fn encloses_weak(a: Circle, b: Circle) -> bool {
+    let dr = a.2 - b.2 + a.2.max(b.2).max(1.0) * 1e-9;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    dr > 0.0 && dr * dr > dx * dx + dy * dy
+}
+
+fn encloses_weak_all(a: Circle, basis: &[Circle]) -> bool {
+    basis.iter().all(|b| encloses_weak(a, *b))
+}
+
+fn enclose_basis(basis: &[Circle]) -> Circle {
+    match basis.len() {
+        1 => basis[0],
+        2 => enclose_basis2(basis[0], basis[1]),
+        _ => enclose_basis3(basis[0], basis[1], basis[2]),
+    }
+}
+
+fn enclose_basis2(a: Circle, b: Circle) -> Circle {
+    let (x1, y1, r1) = a;
+    let (x2, y2, r2) = b;
+    let (x21, y21, r21) = (x2 - x1, y2 - y1, r2 - r1);
+    let l = (x21 * x21 + y21 * y21).sqrt();
+    (
+        (x1 + x2 + x21 / l * r21) / 2.0,
+        (y1 + y2 + y21 / l * r21) / 2.0,
+        (l + r1 + r2) / 2.0,
+    )
+}
+
+fn enclose_basis3(a: Circle, b: Circle, c: Circle) -> Circle {
+    let (x1, y1, r1) = a;
+    let (x2, y2, r2) = b;
+    let (x3, y3, r3) = c;
+    let (a2, a3, b2, b3) = (x1 - x2, x1 - x3, y1 - y2, y1 - y3);
+    let (c2, c3) = (r2 - r1, r3 - r1);
+    let d1 = x1 * x1 + y1 * y1 - r1 * r1;
+    let d2 = d1 - x2 * x2 - y2 * y2 + r2 * r2;
+    let d3 = d1 - x3 * x3 - y3 * y3 + r3 * r3;
+    let ab = a3 * b2 - a2 * b3;
+    let xa = (b2 * d3 - b3 * d2) / (ab * 2.0) - x1;
+    let xb = (b3 * c2 - b2 * c3) / ab;
+    let ya = (a3 * d2 - a2 * d3) / (ab * 2.0) - y1;
+    let yb = (a2 * c3 - a3 * c2) / ab;
+    let qa = xb * xb + yb * yb - 1.0;
+    let qb = 2.0 * (r1 + xa * xb + ya * yb);
+    let qc = xa * xa + ya * ya - r1 * r1;
+    let r = if qa.abs() > 1e-6 {
+        -(qb + (qb * qb - 4.0 * qa * qc).sqrt()) / (2.0 * qa)
+    } else {
+        -qc / qb
+    };
+    (x1 + xa + xb * r, y1 + ya + yb * r, r)
+}
+
+// Pack circles using the front chain.  The pack is centered on its enclosing circle, and then
+// rotated by an angle determined by the seed (no rotation if the seed is zero).  If quad is
+// specified, it is then translated into the first quadrant.  Finally, if there are blacklisted
+// polygons, it is translated by the smallest offset we can find, searching outward in rings, for
+// which no circle touches a polygon.
+
+fn pack_circles_front_chain(
+    r: &[f64],
+    blacklist: &[Polygon],
+    quad: bool,
+    seed: u64,
+) -> Vec<(f64, f64)> {
+    if r.is_empty() {
+        return Vec::new();
+    }
+    let (mut c, chain) = pack_siblings(r);
+    let front = chain
+        .iter()
+        .map(|&i| (c[i].0, c[i].1, r[i]))
+        .collect::<Vec<_>>();
+    let e = enclose(&front, seed);
+    let theta = if seed == 0 {
+        0.0
+    } else {
+        2.0 * std::f64::consts::PI * (lcg(seed) >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let (sin, cos) = theta.sin_cos();
+    for p in c.iter_mut() {
+        let (x, y) = (p.0 - e.0, p.1 - e.1);
+        *p = (x * cos - y * sin, x * sin + y * cos);
+    }
+    if quad {
+        let mut dx = f64::MIN;
+        let mut dy = f64::MIN;
+        for i in 0..c.len() {
+            dx = dx.max(r[i] - c[i].0);
+            dy = dy.max(r[i] - c[i].1);
+        }
+        for p in c.iter_mut() {
+            p.0 += dx;
+            p.1 += dy;
+        }
+    }
+    if !blacklist.is_empty() {
+        let step = *r.last().unwrap();
+        let fits = |dx: f64, dy: f64| {
+            (0..c.len()).into_par_iter().all(|i| {
+                let m = Point {
+                    x: c[i].0 + dx,
+                    y: c[i].1 + dy,
+                };
+                blacklist.iter().all(|p| !p.touches_disk(m, r[i]))
+            })
+        };
+        let mut ring = 0;
+        let offset = 'search: loop {
+            let d = ring as f64 * step;
+            let arc = if quad {
+                std::f64::consts::FRAC_PI_2
+            } else {
+                2.0 * std::f64::consts::PI
+            };
+            let nangles = ((arc * d / step).ceil() as usize).max(1);
+            for k in 0..nangles {
+                let phi = arc * k as f64 / nangles as f64;
+                let (dx, dy) = (d * phi.cos(), d * phi.sin());
+                if fits(dx, dy) {
+                    break 'search (dx, dy);
+                }
+            }
+            ring += 1;
+        };
+        for p in c.iter_mut() {
+            p.0 += offset.0;
+            p.1 += offset.1;
+        }
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_front_chain() {
+        let mut r = (0..100)
+            .map(|i| 1.0 + ((i * 37) % 11) as f64)
+            .collect::<Vec<f64>>();
+        r.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        for quad in [false, true].iter() {
+            for seed in [0, 1].iter() {
+                let layout = PlotLayout {
+                    algorithm: PackAlgorithm::FrontChain,
+                    seed: *seed,
+                    stats: false,
+                };
+                let c = pack_circles(&r, &Vec::new(), *quad, &layout);
+                assert!(c == pack_circles(&r, &Vec::new(), *quad, &layout));
+                for i in 0..r.len() {
+                    for j in i + 1..r.len() {
+                        let d = ((c[i].0 - c[j].0).powi(2) + (c[i].1 - c[j].1).powi(2)).sqrt();
+                        assert!(d > r[i] + r[j] - 1e-4);
+                    }
+                    if *quad {
+                        assert!(c[i].0 >= r[i] - 1e-9 && c[i].1 >= r[i] - 1e-9);
+                    }
+                }
+                assert!(area_ratio(&r, &c) > 0.6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_enclose() {
+        let e = enclose(&[(-1.0, 0.0, 1.0), (1.0, 0.0, 1.0), (0.0, 0.5, 0.5)], 3);
+        assert!(e.0.abs() < 1e-9 && e.1.abs() < 1e-9 && (e.2 - 2.0).abs() < 1e-9);
+    }
+}
@@ -5,14 +5,15 @@
 // In some cases, by eye, you can see rounder forms that could be created by relocating some of
 // the cells.
 
-use crate::assign_cell_color::{VAR_HIGH, VAR_LOW};
+use crate::assign_cell_color::PlotColorContext;
 use crate::cat_var::setup_cat_var;
 use crate::circles_to_svg::circles_to_svg;
 use crate::colors::*;
+use crate::convert_svg_to_pdf::convert_svg_to_pdf;
 use crate::convert_svg_to_png::convert_svg_to_png;
 use crate::group_colors::make_group_colors;
 use crate::legend::add_legend_for_color_by_variable;
-use crate::pack_circles::pack_circles;
+use crate::pack_circles::{area_ratio, pack_circles, PlotLayout};
 use crate::plot_utils::build_clusters;
 use crate::polygon::{enclosing_polygon, Polygon};
 use crate::string_width::arial_width;
@@ -36,6 +37,7 @@ use vector_utils::{
 pub fn plot_clonotypes(
     ctl: &EncloneControl,
     plot_opt: &PlotOpt, // overrides ctl
+    layout: &PlotLayout,
     refdata: &RefData,
     // exacts: One entry for each clonotype.
     exacts: &Vec<Vec<usize>>,
@@ -118,11 +120,16 @@ pub fn plot_clonotypes(
         &mut cat_var_labels,
     );
 
+    // Determine the range of values of the variable used for coloring, if any.
+
+    let color_ctx = PlotColorContext::new(plot_opt, out_datas);
+
     // Build one cluster for each clonotype.
 
     let mut clusters = build_clusters(
         ctl,
         plot_opt,
+        &color_ctx,
         refdata,
         exacts,
         exact_clonotypes,
@@ -303,7 +310,7 @@ pub fn plot_clonotypes(
                     radiiy.push(radiix[i]);
                     indices.push(i);
                 }
-                let centersy = pack_circles(&radiiy, &blacklist, plot_opt.plot_quad);
+                let centersy = pack_circles(&radiiy, &blacklist, plot_opt.plot_quad, layout);
                 let mut left = 0.0_f64;
                 for j in 0..centersy.len() {
                     left = left.max(-centersy[j].0 + radiiy[j]);
@@ -321,7 +328,7 @@ pub fn plot_clonotypes(
                 xstart += right + HSEP;
             }
         } else {
-            centersx = pack_circles(&radiix, &blacklist, plot_opt.plot_quad);
+            centersx = pack_circles(&radiix, &blacklist, plot_opt.plot_quad, layout);
         }
         for i in 0..ids.len() {
             centers[ids[i]] = centersx[i];
@@ -574,6 +581,32 @@ pub fn plot_clonotypes(
         }
     }
 
+    // Report the tightness of the layout, as finally placed.  If HONEY_IN moved the cells, each
+    // cluster is moved by the mean displacement of its cells.  The y coordinates have been
+    // negated, which does not change the area ratio.
+
+    if layout.stats {
+        let mut final_centers = Vec::<(f64, f64)>::new();
+        let mut k = 0;
+        for i in 0..clusters.len() {
+            let n = clusters[i].coords.len();
+            let (mut dx, mut dy) = (0.0, 0.0);
+            for j in 0..n {
+                dx += center[k].0 - clusters[i].coords[j].0;
+                dy += center[k].1 + clusters[i].coords[j].1;
+                k += 1;
+            }
+            final_centers.push((centers[i].0 + dx / n as f64, -centers[i].1 + dy / n as f64));
+        }
+        eprintln!(
+            "\nPLOT_LAYOUT={},seed={}: {} clusters, area ratio = {:.3}",
+            layout.name(),
+            layout.seed,
+            radii.len(),
+            area_ratio(&radii, &final_centers)
+        );
+    }
+
     // Implement HONEY_OUT.
 
     if !plot_opt.honey_out.is_empty() {
@@ -637,6 +670,7 @@ pub fn plot_clonotypes(
         HEIGHT,
         BOUNDARY,
         plot_opt.plot_file == "gui" || plot_opt.plot_file == "gui_stdout",
+        ctl.origin_info.n() > 1,
         by_var,
         var,
         &barcode_to_var_value,
@@ -742,10 +776,18 @@ pub fn plot_clonotypes(
         reorder_color_list(&mut dcx);
     }
     if by_var && plot_opt.use_legend {
-        if VAR_LOW.lock().unwrap()[0].1 == f64::MAX && VAR_HIGH.lock().unwrap()[0].1 == f64::MIN {
+        let (low, high) = color_ctx.var_range.unwrap_or((f64::MAX, f64::MIN));
+        if low == f64::MAX && high == f64::MIN {
             return Err("\nVariable used for color in plot has no numeric values.\n".to_string());
         }
-        add_legend_for_color_by_variable(plot_opt, svg, &color, actual_width, actual_height);
+        add_legend_for_color_by_variable(
+            plot_opt,
+            &color_ctx,
+            svg,
+            &color,
+            actual_width,
+            actual_height,
+        );
     } else if plot_opt.use_legend
         || (plot_opt.plot_by_isotype && !plot_opt.plot_by_isotype_nolegend)
         || plot_opt.plot_by_mark
@@ -881,8 +923,6 @@ pub fn plot_clonotypes(
         }
         *svg += "</svg>";
     }
-    VAR_LOW.lock().unwrap().clear();
-    VAR_HIGH.lock().unwrap().clear();
 
     // Output the svg or png file.
 
@@ -901,7 +941,9 @@ pub fn plot_clonotypes(
             ));
         }
         let mut f = f.unwrap();
-        if !plot_opt.plot_file.ends_with(".png") {
+        if plot_opt.plot_file.ends_with(".pdf") {
+            f.write_all(&convert_svg_to_pdf(svg)).unwrap();
+        } else if !plot_opt.plot_file.ends_with(".png") {
             let mut f = BufWriter::new(f);
             fwriteln!(f, "{}", svg);
         } else {
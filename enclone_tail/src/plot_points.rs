@@ -13,12 +13,23 @@
 // If symmetric = true, produce a square plot having the same range and tic marks on both axes.
 //
 // points = {(point size, point color, x, y)}
+//
+// The function plot_points_and_lines additionally draws lines on top of the points.
 
 use crate::string_width::arial_width;
 use crate::ticks::ticks;
 use plotters::prelude::*;
 use std::cmp::max;
 
+// A polygonal line to be drawn on a plot, in plot coordinates.
+
+#[derive(Clone)]
+pub struct PlotLine {
+    pub points: Vec<(f32, f32)>,
+    pub color: (u8, u8, u8),
+    pub width: u32,
+}
+
 pub fn plot_points(
     points: &Vec<(u32, (u8, u8, u8), f32, f32)>,
     xvar: &str,
@@ -35,6 +46,40 @@ pub fn plot_points(
     // optional margin:
     // It is a bug that this has to be passed sometimes.
     margin: Option<u32>,
+) -> Result<(), String> {
+    plot_points_and_lines(
+        points,
+        &[],
+        xvar,
+        yvar,
+        svg,
+        symmetric,
+        title,
+        xlow,
+        xhigh,
+        ylow,
+        yhigh,
+        margin,
+    )
+}
+
+pub fn plot_points_and_lines(
+    points: &Vec<(u32, (u8, u8, u8), f32, f32)>,
+    lines: &[PlotLine],
+    xvar: &str,
+    yvar: &str,
+    svg: &mut String,
+    symmetric: bool,
+    // title may be specified:
+    title: Option<String>,
+    // plot boundaries may be specified:
+    xlow: Option<f32>,
+    xhigh: Option<f32>,
+    ylow: Option<f32>,
+    yhigh: Option<f32>,
+    // optional margin:
+    // It is a bug that this has to be passed sometimes.
+    margin: Option<u32>,
 ) -> Result<(), String> {
     // Requirements.
 
@@ -206,5 +251,14 @@ pub fn plot_points(
             .unwrap();
         i = j;
     }
+    for line in lines.iter() {
+        let color = RGBColor(line.color.0, line.color.1, line.color.2);
+        chart
+            .draw_series(LineSeries::new(
+                line.points.iter().cloned(),
+                color.stroke_width(line.width),
+            ))
+            .unwrap();
+    }
     Ok(())
 }
@@ -1,14 +1,21 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 
-use crate::assign_cell_color::assign_cell_color;
+use crate::assign_cell_color::{assign_cell_color, PlotColorContext};
 use crate::colors::*;
+use crate::convert_svg_to_pdf::convert_svgs_to_pdf;
 use crate::hex::hex_coord;
 use crate::{substitute_enclone_color, TextUtils};
 use enclone_core::defs::{EncloneControl, ExactClonotype, PlotOpt};
+use io_utils::{fwrite, open_for_write_new};
 use std::collections::HashMap;
+use std::io::Write;
 use vdj_ann::refx::RefData;
 use vector_utils::{bin_position, make_freq, sort_sync2};
 
+// The chain types, indexed by the rtype of a reference segment.
+
+pub const CHAIN_TYPES: [&str; 5] = ["IGH", "IGK", "IGL", "TRA", "TRB"];
+
 #[derive(Clone)]
 pub struct PlotCluster {
     pub clonotype_index: usize,         // index of the clonotype
@@ -23,6 +30,7 @@ pub struct PlotCluster {
 pub fn build_clusters(
     ctl: &EncloneControl,
     plot_opt: &PlotOpt, // overrides ctl
+    color_ctx: &PlotColorContext,
     refdata: &RefData,
     exacts: &Vec<Vec<usize>>,
     exact_clonotypes: &Vec<ExactClonotype>,
@@ -98,6 +106,7 @@ pub fn build_clusters(
                         color = assign_cell_color(
                             ctl,
                             plot_opt,
+                            color_ctx,
                             refdata,
                             const_names,
                             dsx,
@@ -167,3 +176,54 @@ pub fn build_clusters(
     }
     clusters
 }
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Send an svg plot to its destination, following the conventions for plot filenames:
+// stdout or gui_stdout = print it, gui = save it for the GUI, *.pdf = convert it to pdf and
+// write to the file, anything else = write to the file.
+
+pub fn emit_svg(svg: String, filename: &str, svgs: &mut Vec<String>) {
+    if filename == "stdout" || filename == "gui_stdout" {
+        for line in svg.lines() {
+            println!("{}", line);
+        }
+    } else if filename == "gui" {
+        svgs.push(svg);
+    } else if filename.ends_with(".pdf") {
+        write_pdf(&[svg], filename);
+    } else {
+        let mut f = open_for_write_new![&filename];
+        fwrite!(f, "{}", svg);
+    }
+}
+
+// Write svg figures to a pdf file, one per page.
+
+pub fn write_pdf(figures: &[String], filename: &str) {
+    let mut f = open_for_write_new![&filename];
+    f.write_all(&convert_svgs_to_pdf(figures)).unwrap();
+}
+
+// Escape text for inclusion in svg.
+
+pub fn svg_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Start an svg having the given width and height, on a white background.  The caller adds the
+// closing </svg>.
+
+pub fn svg_header(width: f64, height: f64) -> String {
+    format!(
+        "<svg version=\"1.1\"\n\
+         baseProfile=\"full\"\n\
+         width=\"{:.0}\" height=\"{:.0}\"\n\
+         xmlns=\"http://www.w3.org/2000/svg\">\n\
+         <rect x=\"0\" y=\"0\" width=\"{:.0}\" height=\"{:.0}\" fill=\"white\" />\n",
+        width, height, width, height
+    )
+}
@@ -0,0 +1,134 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute PLOT_XY, which makes a scatter plot of two variables, with one point for each exact
+// subclonotype for which both are defined and numeric, in the groups that are shown (see
+// group_and_print_clonotypes).  The options are taken from the given PlotOpt, which overrides ctl.
+
+use crate::plot_points::plot_points;
+use crate::plot_utils::emit_svg;
+use enclone_core::defs::PlotOpt;
+use std::collections::HashMap;
+use string_utils::TextUtils;
+
+// The GUI tooltip note for a point, given the index u of its exact subclonotype in its
+// clonotype, and the index j of its clonotype in its group.  Note that the GUI expects the
+// former as group_id.
+
+pub fn tooltip(u: usize, j: usize) -> String {
+    format!(
+        "data-tooltip='{{\"group_id\":\"{}\",\"clonotype_id\":\"{}\"}}'",
+        u + 1,
+        j + 1,
+    )
+}
+
+// Add tooltip notes to the circles in an svg, which are the points, in order.
+
+pub fn add_tooltips(svg: &str, comments: &[String]) -> String {
+    let mut svg2 = String::new();
+    let mut count = 0;
+    for line in svg.lines() {
+        let mut s = line.to_string();
+        if s.starts_with("<circle ") {
+            s = format!("<circle {}{}", comments[count], s.after("<circle"));
+            count += 1;
+        }
+        svg2 += &format!("{}\n", s);
+    }
+    svg2
+}
+
+// Make the svg for PLOT_XY.
+
+pub fn plot_xy_svg(
+    plot_opt: &PlotOpt,
+    groups: &Vec<Vec<(i32, String)>>,
+    shown: &[bool],
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+) -> Result<String, String> {
+    // Gather the points, and for the GUI, tooltip notes that identify their clonotypes.
+
+    let xvar = &plot_opt.plot_xy_xvar;
+    let yvar = &plot_opt.plot_xy_yvar;
+    let mut vals = Vec::<(u32, (u8, u8, u8), f32, f32)>::new();
+    let mut comments = Vec::<String>::new();
+    for i in 0..groups.len() {
+        if !shown[i] {
+            continue;
+        }
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            for (u, p) in out_datas[oo].iter().enumerate() {
+                if !p.contains_key(xvar) || !p.contains_key(yvar) {
+                    continue;
+                }
+                let (x, y) = (&p[xvar], &p[yvar]);
+                if x.parse::<f64>().is_err() || y.parse::<f64>().is_err() {
+                    continue;
+                }
+                let (mut x, mut y) = (x.force_f64(), y.force_f64());
+                if plot_opt.plot_xy_x_log10 {
+                    if x <= 0.0 {
+                        continue;
+                    }
+                    x = x.log10();
+                }
+                if plot_opt.plot_xy_y_log10 {
+                    if y <= 0.0 {
+                        continue;
+                    }
+                    y = y.log10();
+                }
+                vals.push((4, (255, 0, 0), x as f32, y as f32));
+                comments.push(tooltip(u, j));
+            }
+        }
+    }
+
+    // Make the plot.
+
+    let mut xvar = xvar.clone();
+    if plot_opt.plot_xy_x_log10 {
+        xvar = format!("log10({})", xvar);
+    }
+    let mut yvar = yvar.clone();
+    if plot_opt.plot_xy_y_log10 {
+        yvar = format!("log10({})", yvar);
+    }
+    let mut svg = String::new();
+    plot_points(
+        &vals,
+        &xvar,
+        &yvar,
+        &mut svg,
+        plot_opt.plot_xy_sym,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+
+    // Add tooltip notes for the GUI.
+
+    if plot_opt.plot_xy_filename == "gui" {
+        svg = add_tooltips(&svg, &comments);
+    }
+    Ok(svg)
+}
+
+pub fn plot_xy(
+    plot_opt: &PlotOpt,
+    groups: &Vec<Vec<(i32, String)>>,
+    shown: &[bool],
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    if plot_opt.plot_xy_filename.is_empty() {
+        return Ok(());
+    }
+    let svg = plot_xy_svg(plot_opt, groups, shown, out_datas)?;
+    emit_svg(svg, &plot_opt.plot_xy_filename, svgs);
+    Ok(())
+}
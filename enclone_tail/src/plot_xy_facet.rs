@@ -0,0 +1,417 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute PLOT_XY, in the case where PLOT_XY_FACET, PLOT_XY_FIT or PLOT_XY_DENSITY is given.
+//
+// As for PLOT_XY, there is one point for each exact subclonotype having numeric values for both
+// variables, in the groups that are shown, and for the GUI, each point has a tooltip note.  If
+// PLOT_XY_FACET=var, the points are split into panels, one for each value of var, arranged in a
+// grid, with the same axes for all panels.  If var is dataset, origin or donor, an exact
+// subclonotype whose cells come from several datasets (etc.) appears in each of their panels.
+// Otherwise var is a variable, whose value for the exact subclonotype is used.
+//
+// PLOT_XY_FIT adds the least squares line to each panel, and shows R² in the panel title.
+// PLOT_XY_DENSITY adds contours of a gaussian kernel density estimate, at 25%, 50% and 75% of
+// its maximum.
+
+use crate::plot_points::{plot_points_and_lines, PlotLine};
+use crate::plot_utils::{emit_svg, svg_header};
+use crate::plot_xy::{add_tooltips, tooltip};
+use crate::tail_opts::TailOpts;
+use enclone_core::defs::{EncloneControl, ExactClonotype};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use string_utils::TextUtils;
+
+type Points = Vec<(u32, (u8, u8, u8), f32, f32)>;
+
+// Extend a range a little bit, following plot_points.
+
+fn extend_range(low: f32, high: f32) -> (f32, f32) {
+    let range_ext = 0.02;
+    let (mut low, mut high) = (low, high);
+    if low > 0.0 && low / (high - low) < range_ext {
+        low = 0.0;
+    } else if low > 0.0 {
+        low *= 1.0 - range_ext;
+    } else {
+        low *= 1.0 + range_ext;
+    }
+    if high > 0.0 {
+        high *= 1.0 + range_ext;
+    } else {
+        high *= 1.0 - range_ext;
+    }
+    if low == high {
+        high = low + 1.0;
+    }
+    (low, high)
+}
+
+// Fit y = a + bx by least squares, returning (a, b, R²), or None if x is constant.
+
+fn fit_line(points: &Points) -> Option<(f64, f64, f64)> {
+    let n = points.len() as f64;
+    if n < 2.0 {
+        return None;
+    }
+    let mx = points.iter().map(|p| p.2 as f64).sum::<f64>() / n;
+    let my = points.iter().map(|p| p.3 as f64).sum::<f64>() / n;
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    for p in points.iter() {
+        let (dx, dy) = (p.2 as f64 - mx, p.3 as f64 - my);
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+    }
+    if sxx == 0.0 {
+        return None;
+    }
+    let b = sxy / sxx;
+    let r2 = if syy == 0.0 {
+        1.0
+    } else {
+        sxy * sxy / (sxx * syy)
+    };
+    Some((my - b * mx, b, r2))
+}
+
+// Clip the line y = a + bx to the plot box.
+
+fn clip_line(a: f64, b: f64, xr: (f32, f32), yr: (f32, f32)) -> Option<PlotLine> {
+    let (xl, xh) = (xr.0 as f64, xr.1 as f64);
+    let (yl, yh) = (yr.0 as f64, yr.1 as f64);
+    let (mut xs, mut xe) = (xl, xh);
+    if b == 0.0 {
+        if a < yl || a > yh {
+            return None;
+        }
+    } else {
+        let (x1, x2) = ((yl - a) / b, (yh - a) / b);
+        xs = xs.max(x1.min(x2));
+        xe = xe.min(x1.max(x2));
+        if xs >= xe {
+            return None;
+        }
+    }
+    Some(PlotLine {
+        points: vec![
+            (xs as f32, (a + b * xs) as f32),
+            (xe as f32, (a + b * xe) as f32),
+        ],
+        color: (0, 0, 0),
+        width: 2,
+    })
+}
+
+// Compute density contours, as line segments, using marching squares on a grid.
+
+fn density_contours(points: &Points, xr: (f32, f32), yr: (f32, f32)) -> Vec<PlotLine> {
+    const GRID: usize = 40;
+    let n = points.len() as f64;
+    if points.len() < 10 {
+        return Vec::new();
+    }
+
+    // Choose bandwidths using Scott's rule.
+
+    let sd = |v: Vec<f64>| {
+        let m = v.iter().sum::<f64>() / n;
+        (v.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / (n - 1.0)).sqrt()
+    };
+    let mut hx = sd(points.iter().map(|p| p.2 as f64).collect()) * n.powf(-1.0 / 6.0);
+    let mut hy = sd(points.iter().map(|p| p.3 as f64).collect()) * n.powf(-1.0 / 6.0);
+    if hx == 0.0 {
+        hx = (xr.1 - xr.0) as f64 / 20.0;
+    }
+    if hy == 0.0 {
+        hy = (yr.1 - yr.0) as f64 / 20.0;
+    }
+
+    // Evaluate the density on the grid.
+
+    let gx = |i: usize| xr.0 as f64 + (xr.1 - xr.0) as f64 * i as f64 / (GRID - 1) as f64;
+    let gy = |j: usize| yr.0 as f64 + (yr.1 - yr.0) as f64 * j as f64 / (GRID - 1) as f64;
+    let mut d = vec![vec![0.0; GRID]; GRID];
+    let mut dmax = 0.0_f64;
+    for i in 0..GRID {
+        for j in 0..GRID {
+            let (x, y) = (gx(i), gy(j));
+            for p in points.iter() {
+                let (u, v) = ((p.2 as f64 - x) / hx, (p.3 as f64 - y) / hy);
+                d[i][j] += (-0.5 * (u * u + v * v)).exp();
+            }
+            dmax = dmax.max(d[i][j]);
+        }
+    }
+
+    // Trace the contours.  For each grid square, find where the contour crosses its edges, which
+    // are traversed counterclockwise from the bottom.  In the ambiguous case of four crossings,
+    // use the value at the center of the square to pair them.
+
+    let mut lines = Vec::<PlotLine>::new();
+    for frac in [0.25, 0.5, 0.75].iter() {
+        let level = frac * dmax;
+        for i in 0..GRID - 1 {
+            for j in 0..GRID - 1 {
+                let corners = [
+                    (gx(i), gy(j), d[i][j]),
+                    (gx(i + 1), gy(j), d[i + 1][j]),
+                    (gx(i + 1), gy(j + 1), d[i + 1][j + 1]),
+                    (gx(i), gy(j + 1), d[i][j + 1]),
+                ];
+                let mut cross = Vec::<(f32, f32)>::new();
+                for e in 0..4 {
+                    let (p, q) = (corners[e], corners[(e + 1) % 4]);
+                    if (p.2 < level) != (q.2 < level) {
+                        let t = (level - p.2) / (q.2 - p.2);
+                        cross.push((
+                            (p.0 + t * (q.0 - p.0)) as f32,
+                            (p.1 + t * (q.1 - p.1)) as f32,
+                        ));
+                    }
+                }
+                let mut segs = Vec::new();
+                if cross.len() == 2 {
+                    segs.push(vec![cross[0], cross[1]]);
+                } else if cross.len() == 4 {
+                    let center = corners.iter().map(|c| c.2).sum::<f64>() / 4.0;
+                    if (center < level) == (corners[0].2 < level) {
+                        segs.push(vec![cross[0], cross[1]]);
+                        segs.push(vec![cross[2], cross[3]]);
+                    } else {
+                        segs.push(vec![cross[0], cross[3]]);
+                        segs.push(vec![cross[1], cross[2]]);
+                    }
+                }
+                for s in segs {
+                    lines.push(PlotLine {
+                        points: s,
+                        color: (100, 100, 100),
+                        width: 1,
+                    });
+                }
+            }
+        }
+    }
+    lines
+}
+
+pub fn plot_xy_facet(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    groups: &Vec<Vec<(i32, String)>>,
+    shown: &[bool],
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    if ctl.plot_opt.plot_xy_filename.is_empty() || !tail_opts.plot_xy_extended() {
+        return Ok(());
+    }
+    let facet = &tail_opts.plot_xy_facet;
+    let (xvar, yvar) = (&ctl.plot_opt.plot_xy_xvar, &ctl.plot_opt.plot_xy_yvar);
+    let value = |p: &HashMap<String, String>, var: &String, log10: bool| -> Option<f32> {
+        let x = p.get(var)?.parse::<f64>().ok()?;
+        if !log10 {
+            Some(x as f32)
+        } else if x > 0.0 {
+            Some(x.log10() as f32)
+        } else {
+            None
+        }
+    };
+
+    // Gather points by category, and for the GUI, tooltip notes that identify their clonotypes.
+
+    let mut panels = BTreeMap::<String, (Points, Vec<String>)>::new();
+    for i in 0..groups.len() {
+        if !shown[i] {
+            continue;
+        }
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            for u in 0..out_datas[oo].len() {
+                let p = &out_datas[oo][u];
+                let x = value(p, xvar, ctl.plot_opt.plot_xy_x_log10);
+                let y = value(p, yvar, ctl.plot_opt.plot_xy_y_log10);
+                if x.is_none() || y.is_none() {
+                    continue;
+                }
+                let mut cats = BTreeSet::<String>::new();
+                let ex = &exact_clonotypes[exacts[oo][u]];
+                for m in 0..ex.ncells() {
+                    let li = ex.clones[m][0].dataset_index;
+                    match facet.as_str() {
+                        "" => cats.insert(String::new()),
+                        "dataset" => cats.insert(ctl.origin_info.dataset_id[li].clone()),
+                        "origin" => cats.insert(ctl.origin_info.origin_id[li].clone()),
+                        "donor" => cats.insert(ctl.origin_info.donor_id[li].clone()),
+                        _ => cats.insert(p.get(facet).cloned().unwrap_or_default()),
+                    };
+                }
+                for cat in cats {
+                    let panel = panels.entry(cat).or_default();
+                    panel.0.push((4, (255, 0, 0), x.unwrap(), y.unwrap()));
+                    panel.1.push(tooltip(u, j));
+                }
+            }
+        }
+    }
+    if panels.is_empty() {
+        return Err(format!(
+            "\nPlot of {} versus {} can't be carried out because there are no data points.\n",
+            xvar, yvar
+        ));
+    }
+
+    // Define the shared axes.
+
+    let (mut xlow, mut xhigh) = (f32::MAX, f32::MIN);
+    let (mut ylow, mut yhigh) = (f32::MAX, f32::MIN);
+    for p in panels.values().flat_map(|x| x.0.iter()) {
+        xlow = xlow.min(p.2);
+        xhigh = xhigh.max(p.2);
+        ylow = ylow.min(p.3);
+        yhigh = yhigh.max(p.3);
+    }
+    let (mut xlow, mut xhigh) = extend_range(xlow, xhigh);
+    let (mut ylow, mut yhigh) = extend_range(ylow, yhigh);
+    if ctl.plot_opt.plot_xy_sym {
+        xlow = xlow.min(ylow);
+        ylow = xlow;
+        xhigh = xhigh.max(yhigh);
+        yhigh = xhigh;
+    }
+    let mut xdesc = xvar.clone();
+    if ctl.plot_opt.plot_xy_x_log10 {
+        xdesc = format!("log10({})", xdesc);
+    }
+    let mut ydesc = yvar.clone();
+    if ctl.plot_opt.plot_xy_y_log10 {
+        ydesc = format!("log10({})", ydesc);
+    }
+
+    // Make the panels.
+
+    let mut svgp = Vec::<String>::new();
+    for (cat, (points, comments)) in panels.iter() {
+        let mut title = if facet.is_empty() {
+            format!("{} versus {}", xdesc, ydesc)
+        } else {
+            format!("{} = {}", facet, cat)
+        };
+        let mut lines = Vec::<PlotLine>::new();
+        if tail_opts.plot_xy_density {
+            lines.append(&mut density_contours(points, (xlow, xhigh), (ylow, yhigh)));
+        }
+        if tail_opts.plot_xy_fit {
+            match fit_line(points) {
+                Some((a, b, r2)) => {
+                    title = format!("{}, R² = {:.3}", title, r2);
+                    if let Some(line) = clip_line(a, b, (xlow, xhigh), (ylow, yhigh)) {
+                        lines.push(line);
+                    }
+                }
+                None => title = format!("{}, no fit", title),
+            }
+        }
+        let mut svg = String::new();
+        plot_points_and_lines(
+            points,
+            &lines,
+            &xdesc,
+            &ydesc,
+            &mut svg,
+            ctl.plot_opt.plot_xy_sym,
+            Some(title),
+            Some(xlow),
+            Some(xhigh),
+            Some(ylow),
+            Some(yhigh),
+            None,
+        )?;
+        if ctl.plot_opt.plot_xy_filename == "gui" {
+            svg = add_tooltips(&svg, comments);
+        }
+        svgp.push(svg);
+    }
+
+    // Arrange the panels in a grid, by nesting their svgs.
+
+    let svg = if svgp.len() == 1 {
+        svgp[0].clone()
+    } else {
+        let width = svgp[0].between("width=\"", "\"").force_f64();
+        let height = svgp[0].between("height=\"", "\"").force_f64();
+        let ncols = (svgp.len() as f64).sqrt().ceil() as usize;
+        let nrows = (svgp.len() + ncols - 1) / ncols;
+        let mut svg = svg_header(width * ncols as f64, height * nrows as f64);
+        for (k, s) in svgp.iter().enumerate() {
+            let (x, y) = (width * (k % ncols) as f64, height * (k / ncols) as f64);
+            svg += &format!("<svg x=\"{}\" y=\"{}\" {}\n", x, y, s.after("<svg "));
+        }
+        svg += "</svg>\n";
+        svg
+    };
+    emit_svg(svg, &ctl.plot_opt.plot_xy_filename, svgs);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(xy: &[(f32, f32)]) -> Points {
+        xy.iter().map(|p| (4, (255, 0, 0), p.0, p.1)).collect()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1.0e-6
+    }
+
+    #[test]
+    fn test_fit_line() {
+        let (a, b, r2) = fit_line(&points(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)])).unwrap();
+        assert!(close(a, 1.0) && close(b, 2.0) && close(r2, 1.0));
+        let (a, b, r2) =
+            fit_line(&points(&[(0.0, 0.0), (1.0, 1.0), (2.0, 0.0), (3.0, 1.0)])).unwrap();
+        assert!(close(a, 0.2) && close(b, 0.2) && close(r2, 0.2));
+        assert!(fit_line(&points(&[(1.0, 0.0), (1.0, 1.0)])).is_none());
+        assert!(fit_line(&points(&[(1.0, 0.0)])).is_none());
+    }
+
+    #[test]
+    fn test_clip_line() {
+        let (xr, yr) = ((0.0, 10.0), (0.0, 5.0));
+        let ends = |a: f64, b: f64| clip_line(a, b, xr, yr).map(|line| line.points);
+        assert_eq!(ends(0.0, 1.0), Some(vec![(0.0, 0.0), (5.0, 5.0)]));
+        assert_eq!(ends(5.0, -1.0), Some(vec![(0.0, 5.0), (5.0, 0.0)]));
+        assert_eq!(ends(3.0, 0.0), Some(vec![(0.0, 3.0), (10.0, 3.0)]));
+        assert_eq!(ends(7.0, 0.0), None);
+        assert_eq!(ends(20.0, 1.0), None);
+    }
+
+    #[test]
+    fn test_density_contours() {
+        let mut xy = vec![(5.0, 5.0)];
+        for dx in [-1.0, 0.0, 1.0].iter() {
+            for dy in [-1.0, 0.0, 1.0].iter() {
+                xy.push((5.0 + dx, 5.0 + dy));
+            }
+        }
+        let (xr, yr) = ((0.0, 10.0), (0.0, 10.0));
+        assert!(density_contours(&points(&xy[1..]), xr, yr).is_empty());
+        let lines = density_contours(&points(&xy), xr, yr);
+        assert!(!lines.is_empty());
+
+        // The contours are segments around the center of the points.
+
+        for line in lines.iter() {
+            assert_eq!(line.points.len(), 2);
+            for p in line.points.iter() {
+                let d = ((p.0 - 5.0).powi(2) + (p.1 - 5.0).powi(2)).sqrt();
+                assert!(d > 0.1 && d < 3.0);
+            }
+        }
+    }
+}
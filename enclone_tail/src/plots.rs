@@ -0,0 +1,544 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute PLOTS.  Each PLOTS=spec argument defines one additional plot, with its own options
+// and output file, so that any number of plots may be made from a single run.  A spec has one
+// of the forms
+//
+// honey:out=filename[,width],color-spec[,legend=none][,split=origin|dataset][,quad]
+// xy:xvar,yvar,filename[,sym]
+// sim_mat:filename,var1,...,varn
+//
+// which make a honeycomb plot, as for HONEY, a scatter plot, as for PLOT_XY, and a similarity
+// matrix plot, as for SIM_MAT_PLOT.  The color-spec is as for HONEY, i.e. one of
+//
+// color=dataset
+// color=var,[abbr:]name[,turbo][,minmax,min,max]
+// color=catvar,var1+...+varn,maxcat:n
+//
+// and in addition color=isotype, which colors as for PLOT_BY_ISOTYPE.  If color-spec is omitted,
+// cells are colored as for PLOT.  As for PLOT_XY, xvar and yvar may have the form log10(var).
+//
+// The plots are made in parallel.  Plots whose output is stdout are printed in the order given.
+// Plots whose output is the same pdf file become the pages of that file, in the order given.
+
+use crate::plot::plot_clonotypes;
+use crate::plot_utils::{emit_svg, write_pdf};
+use crate::plot_xy::plot_xy_svg;
+use crate::sim_mat_plot::sim_mat_svg;
+use crate::tail_opts::TailOpts;
+use enclone_core::cell_color::{
+    CellColor, ColorByCategoricalVariableValue, ColorByDataset, ColorByVariableValue,
+};
+use enclone_core::defs::{EncloneControl, ExactClonotype, PlotOpt};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use string_utils::TextUtils;
+use vdj_ann::refx::RefData;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlotsColor {
+    Default,
+    Dataset,
+    Isotype,
+    Var {
+        var: String,
+        display_var: String,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    CatVar {
+        vars: Vec<String>,
+        maxcat: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlotsSpec {
+    Honey {
+        out: String,
+        png_width: Option<usize>,
+        color: PlotsColor,
+        legend: bool,
+        split_by_origin: bool,
+        split_by_dataset: bool,
+        quad: bool,
+    },
+    Xy {
+        xvar: String,
+        yvar: String,
+        x_log10: bool,
+        y_log10: bool,
+        out: String,
+        sym: bool,
+    },
+    SimMat {
+        out: String,
+        vars: Vec<String>,
+    },
+}
+
+fn err(spec: &str, msg: &str) -> String {
+    format!("\nThe argument PLOTS={} is invalid: {}.\n", spec, msg)
+}
+
+// Parse a variable for an xy plot, allowing log10(var).
+
+fn xy_var(x: &str) -> (String, bool) {
+    if x.starts_with("log10(") && x.ends_with(')') {
+        (x.between("log10(", ")").to_string(), true)
+    } else {
+        (x.to_string(), false)
+    }
+}
+
+fn parse_bound(spec: &str, x: Option<&&str>) -> Result<Option<f64>, String> {
+    match x {
+        None | Some(&"") => Ok(None),
+        Some(x) => match x.parse::<f64>() {
+            Ok(v) => Ok(Some(v)),
+            Err(_) => Err(err(spec, &format!("{} is not a number", x))),
+        },
+    }
+}
+
+impl PlotsSpec {
+    pub fn parse(spec: &str) -> Result<PlotsSpec, String> {
+        if !spec.contains(':') {
+            return Err(err(spec, "it must have the form kind:fields"));
+        }
+        let (kind, rest) = (spec.before(":"), spec.after(":"));
+        let fields = rest.split(',').collect::<Vec<&str>>();
+        match kind {
+            "honey" => {
+                let mut out = String::new();
+                let mut png_width = None;
+                let mut color = PlotsColor::Default;
+                let mut legend = true;
+                let (mut split_by_origin, mut split_by_dataset) = (false, false);
+                let mut quad = false;
+                let mut i = 0;
+                while i < fields.len() {
+                    let f = fields[i];
+                    i += 1;
+                    if f.starts_with("out=") {
+                        out = f.after("out=").to_string();
+                        if let Some(w) = fields.get(i) {
+                            if let Ok(w) = w.parse::<usize>() {
+                                png_width = Some(w);
+                                i += 1;
+                            }
+                        }
+                    } else if f == "color=dataset" {
+                        color = PlotsColor::Dataset;
+                    } else if f == "color=isotype" {
+                        color = PlotsColor::Isotype;
+                    } else if f == "color=var" {
+                        let name = match fields.get(i) {
+                            Some(x) if !x.is_empty() => x.to_string(),
+                            _ => return Err(err(spec, "color=var must be followed by a name")),
+                        };
+                        i += 1;
+                        let (mut display_var, mut var) = (name.clone(), name.clone());
+                        if name.contains(':') {
+                            display_var = name.before(":").to_string();
+                            var = name.after(":").to_string();
+                        }
+                        if fields.get(i) == Some(&"turbo") || fields.get(i) == Some(&"") {
+                            i += 1;
+                        }
+                        let (mut min, mut max) = (None, None);
+                        if fields.get(i) == Some(&"minmax") {
+                            min = parse_bound(spec, fields.get(i + 1))?;
+                            max = parse_bound(spec, fields.get(i + 2))?;
+                            i = (i + 3).min(fields.len());
+                        }
+                        color = PlotsColor::Var {
+                            var,
+                            display_var,
+                            min,
+                            max,
+                        };
+                    } else if f == "color=catvar" {
+                        let vars = match fields.get(i) {
+                            Some(x) if !x.is_empty() => {
+                                x.split('+').map(str::to_string).collect::<Vec<_>>()
+                            }
+                            _ => return Err(err(spec, "color=catvar must be followed by names")),
+                        };
+                        i += 1;
+                        let mut maxcat = usize::MAX;
+                        if let Some(x) = fields.get(i) {
+                            if x.starts_with("maxcat:") {
+                                maxcat = match x.after("maxcat:").parse::<usize>() {
+                                    Ok(n) if n > 0 => n,
+                                    _ => return Err(err(spec, "maxcat must be positive")),
+                                };
+                                i += 1;
+                            }
+                        }
+                        color = PlotsColor::CatVar { vars, maxcat };
+                    } else if f == "legend=none" {
+                        legend = false;
+                    } else if f == "split=origin" {
+                        split_by_origin = true;
+                    } else if f == "split=dataset" {
+                        split_by_dataset = true;
+                    } else if f == "quad" {
+                        quad = true;
+                    } else if !f.is_empty() {
+                        return Err(err(spec, &format!("the field {} is not recognized", f)));
+                    }
+                }
+                if out.is_empty() {
+                    return Err(err(spec, "out=filename must be specified"));
+                }
+                if split_by_origin && split_by_dataset {
+                    return Err(err(spec, "only one split may be specified"));
+                }
+                Ok(PlotsSpec::Honey {
+                    out,
+                    png_width,
+                    color,
+                    legend,
+                    split_by_origin,
+                    split_by_dataset,
+                    quad,
+                })
+            }
+            "xy" => {
+                let sym = fields.len() == 4 && fields[3] == "sym";
+                if (fields.len() != 3 && !sym) || fields[..3].contains(&"") {
+                    return Err(err(spec, "the form must be xy:xvar,yvar,filename[,sym]"));
+                }
+                let (xvar, x_log10) = xy_var(fields[0]);
+                let (yvar, y_log10) = xy_var(fields[1]);
+                Ok(PlotsSpec::Xy {
+                    xvar,
+                    yvar,
+                    x_log10,
+                    y_log10,
+                    out: fields[2].to_string(),
+                    sym,
+                })
+            }
+            "sim_mat" => {
+                if fields.len() < 2 || fields.contains(&"") {
+                    return Err(err(spec, "the form must be sim_mat:filename,var1,...,varn"));
+                }
+                Ok(PlotsSpec::SimMat {
+                    out: fields[0].to_string(),
+                    vars: fields[1..].iter().map(|x| x.to_string()).collect(),
+                })
+            }
+            _ => Err(err(spec, "the kind must be honey, xy or sim_mat")),
+        }
+    }
+
+    // Return the output filename.
+
+    pub fn out(&self) -> &str {
+        match self {
+            PlotsSpec::Honey { out, .. } => out,
+            PlotsSpec::Xy { out, .. } => out,
+            PlotsSpec::SimMat { out, .. } => out,
+        }
+    }
+
+    // Return the variables whose values need to be computed.
+
+    pub fn vars(&self) -> Vec<String> {
+        match self {
+            PlotsSpec::Honey { color, .. } => match color {
+                PlotsColor::Var { var, .. } => vec![var.clone()],
+                PlotsColor::CatVar { vars, .. } => vars.clone(),
+                _ => Vec::new(),
+            },
+            PlotsSpec::Xy { xvar, yvar, .. } => vec![xvar.clone(), yvar.clone()],
+            PlotsSpec::SimMat { vars, .. } => vars.clone(),
+        }
+    }
+
+    // Make the PlotOpt that defines the plot.  Options not set by the spec have their default
+    // values, so that the plot does not depend on PLOT, HONEY, PLOT_XY or SIM_MAT_PLOT.
+
+    pub fn plot_opt(&self) -> PlotOpt {
+        let mut p = PlotOpt::default();
+        match self {
+            PlotsSpec::Honey {
+                out,
+                png_width,
+                color,
+                legend,
+                split_by_origin,
+                split_by_dataset,
+                quad,
+            } => {
+                p.plot_file = out.clone();
+                p.png_width = *png_width;
+                match color {
+                    PlotsColor::Default => {}
+                    PlotsColor::Dataset => {
+                        p.cell_color = CellColor::ByDataset(ColorByDataset::default());
+                    }
+                    PlotsColor::Isotype => p.plot_by_isotype = true,
+                    PlotsColor::Var {
+                        var,
+                        display_var,
+                        min,
+                        max,
+                    } => {
+                        // Other fields, such as the color map, have their default values.
+
+                        p.cell_color = CellColor::ByVariableValue(ColorByVariableValue {
+                            var: var.clone(),
+                            display_var: display_var.clone(),
+                            min: *min,
+                            max: *max,
+                            ..Default::default()
+                        });
+                    }
+                    PlotsColor::CatVar { vars, maxcat } => {
+                        p.cell_color = CellColor::ByCategoricalVariableValue(
+                            ColorByCategoricalVariableValue {
+                                vars: vars.clone(),
+                                maxcat: *maxcat,
+                            },
+                        );
+                    }
+                }
+                p.use_legend = *legend;
+                p.plot_by_isotype_nolegend = !*legend;
+                p.split_plot_by_origin = *split_by_origin;
+                p.split_plot_by_dataset = *split_by_dataset;
+                p.plot_quad = *quad;
+            }
+            PlotsSpec::Xy {
+                xvar,
+                yvar,
+                x_log10,
+                y_log10,
+                out,
+                sym,
+            } => {
+                p.plot_xy_xvar = xvar.clone();
+                p.plot_xy_yvar = yvar.clone();
+                p.plot_xy_x_log10 = *x_log10;
+                p.plot_xy_y_log10 = *y_log10;
+                p.plot_xy_filename = out.clone();
+                p.plot_xy_sym = *sym;
+            }
+            PlotsSpec::SimMat { out, vars } => {
+                p.sim_mat_plot_file = out.clone();
+                p.sim_mat_plot_vars = vars.clone();
+            }
+        }
+        p
+    }
+}
+
+// Return true if a plot filename directs output to stdout.
+
+pub fn is_stdout(filename: &str) -> bool {
+    ["stdout", "stdout.png", "gui_stdout"].contains(&filename)
+}
+
+pub fn make_plots(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    refdata: &RefData,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+    groups: &Vec<Vec<(i32, String)>>,
+    shown: &[bool],
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    // Make the plots.  Honeycomb plots are written to files by plot_clonotypes, except for pdf
+    // files, which may have several pages, and are written below.
+
+    let results = tail_opts
+        .plots
+        .par_iter()
+        .map(|spec| -> Result<String, String> {
+            let mut plot_opt = spec.plot_opt();
+            if plot_opt.plot_file.ends_with(".pdf") {
+                plot_opt.plot_file = "stdout".to_string();
+            }
+            let mut svg = String::new();
+            match spec {
+                PlotsSpec::Honey { .. } => plot_clonotypes(
+                    ctl,
+                    &plot_opt,
+                    &tail_opts.plot_layout,
+                    refdata,
+                    exacts,
+                    exact_clonotypes,
+                    out_datas,
+                    groups,
+                    &mut svg,
+                )?,
+                PlotsSpec::Xy { .. } => svg = plot_xy_svg(&plot_opt, groups, shown, out_datas)?,
+                PlotsSpec::SimMat { .. } => {
+                    svg = sim_mat_svg(&plot_opt, groups, out_datas).unwrap()
+                }
+            }
+            Ok(svg)
+        })
+        .collect::<Vec<Result<String, String>>>();
+
+    // Send the plots to their destinations.
+
+    let mut pdfs = Vec::<(String, Vec<String>)>::new();
+    for (spec, svg) in tail_opts.plots.iter().zip(results) {
+        let svg = svg?;
+        let out = spec.out();
+        if out.ends_with(".pdf") {
+            match pdfs.iter_mut().find(|x| x.0 == out) {
+                Some(x) => x.1.push(svg),
+                None => pdfs.push((out.to_string(), vec![svg])),
+            }
+            continue;
+        }
+        match spec {
+            PlotsSpec::Honey { .. } => {
+                if out == "stdout" || out == "gui_stdout" {
+                    print!("{}", svg);
+                } else if out == "gui" {
+                    svgs.push(svg);
+                }
+            }
+            _ => emit_svg(svg, out, svgs),
+        }
+    }
+    for (filename, pages) in pdfs.iter() {
+        write_pdf(pages, filename);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plots_spec_parse() {
+        // Honeycomb plots.
+
+        assert_eq!(
+            PlotsSpec::parse("honey:out=plot.png,1000,color=dataset,legend=none,split=origin,quad"),
+            Ok(PlotsSpec::Honey {
+                out: "plot.png".to_string(),
+                png_width: Some(1000),
+                color: PlotsColor::Dataset,
+                legend: false,
+                split_by_origin: true,
+                split_by_dataset: false,
+                quad: true,
+            })
+        );
+        let spec = PlotsSpec::parse("honey:out=plot.svg").unwrap();
+        assert_eq!(
+            spec,
+            PlotsSpec::Honey {
+                out: "plot.svg".to_string(),
+                png_width: None,
+                color: PlotsColor::Default,
+                legend: true,
+                split_by_origin: false,
+                split_by_dataset: false,
+                quad: false,
+            }
+        );
+        assert_eq!(spec.out(), "plot.svg");
+        assert!(spec.vars().is_empty());
+        let spec =
+            PlotsSpec::parse("honey:color=var,abbr:u_cell1,turbo,minmax,0,10,out=a.svg").unwrap();
+        assert_eq!(
+            spec,
+            PlotsSpec::Honey {
+                out: "a.svg".to_string(),
+                png_width: None,
+                color: PlotsColor::Var {
+                    var: "u_cell1".to_string(),
+                    display_var: "abbr".to_string(),
+                    min: Some(0.0),
+                    max: Some(10.0),
+                },
+                legend: true,
+                split_by_origin: false,
+                split_by_dataset: false,
+                quad: false,
+            }
+        );
+        assert_eq!(spec.vars(), vec!["u_cell1"]);
+        match PlotsSpec::parse("honey:out=a.svg,color=var,u1,minmax,,5").unwrap() {
+            PlotsSpec::Honey { color, .. } => assert_eq!(
+                color,
+                PlotsColor::Var {
+                    var: "u1".to_string(),
+                    display_var: "u1".to_string(),
+                    min: None,
+                    max: Some(5.0),
+                }
+            ),
+            _ => panic!("expected a honeycomb plot"),
+        }
+        let spec = PlotsSpec::parse("honey:out=a.svg,color=catvar,dref+cdr3_len,maxcat:5").unwrap();
+        match &spec {
+            PlotsSpec::Honey { color, .. } => assert_eq!(
+                *color,
+                PlotsColor::CatVar {
+                    vars: vec!["dref".to_string(), "cdr3_len".to_string()],
+                    maxcat: 5,
+                }
+            ),
+            _ => panic!("expected a honeycomb plot"),
+        }
+        assert_eq!(spec.vars(), vec!["dref", "cdr3_len"]);
+
+        // Xy and similarity matrix plots.
+
+        let spec = PlotsSpec::parse("xy:log10(u1),u2,plot.svg,sym").unwrap();
+        assert_eq!(
+            spec,
+            PlotsSpec::Xy {
+                xvar: "u1".to_string(),
+                yvar: "u2".to_string(),
+                x_log10: true,
+                y_log10: false,
+                out: "plot.svg".to_string(),
+                sym: true,
+            }
+        );
+        assert_eq!(spec.vars(), vec!["u1", "u2"]);
+        assert_eq!(
+            PlotsSpec::parse("sim_mat:mat.svg,u1,u2,u3"),
+            Ok(PlotsSpec::SimMat {
+                out: "mat.svg".to_string(),
+                vars: vec!["u1".to_string(), "u2".to_string(), "u3".to_string()],
+            })
+        );
+
+        // Invalid specs.
+
+        for spec in [
+            "honey",
+            "pie:out=a.svg",
+            "honey:color=dataset",
+            "honey:out=a.svg,split=origin,split=dataset",
+            "honey:out=a.svg,color=var",
+            "honey:out=a.svg,color=var,u1,minmax,a,1",
+            "honey:out=a.svg,color=catvar,dref,maxcat:0",
+            "honey:out=a.svg,bogus",
+            "xy:u1,plot.svg",
+            "xy:u1,u2,plot.svg,bogus",
+            "xy:u1,,plot.svg",
+            "sim_mat:mat.svg",
+            "sim_mat:mat.svg,u1,",
+        ]
+        .iter()
+        {
+            assert!(PlotsSpec::parse(spec).is_err(), "{} should not parse", spec);
+        }
+    }
+}
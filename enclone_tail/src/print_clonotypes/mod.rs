@@ -19,12 +19,14 @@ mod proc_lvar_auto;
 
 use self::finish_table::{finish_table, Sr};
 use self::gene_scan::{gene_scan_test, InSet};
+pub use self::print_utils1::aa_classes;
 use self::print_utils1::{compute_field_types, extra_args, start_gen};
 use self::print_utils2::row_fill;
 use self::print_utils3::{consensus_codon_cdr3, get_extra_parseables, process_complete};
 use self::print_utils4::{build_show_aa, compute_bu, compute_some_stats, SomeStats};
 use self::print_utils5::vars_and_shares;
 use crate::mammalian_fixed_len::mammalian_fixed_len_peer_groups;
+use crate::tail_opts::TailOpts;
 use enclone_args::proc_args_check::involves_gex_fb;
 use enclone_core::allowed_vars::{CVARS_ALLOWED, CVARS_ALLOWED_PCELL, LVARS_ALLOWED};
 use enclone_core::defs::ColInfo;
@@ -63,7 +65,7 @@ pub struct EncloneOrbitProcessor {
 }
 
 impl EncloneOrbitProcessor {
-    pub fn new(setup: &EncloneSetup, vdj_cells: &[Vec<String>]) -> Self {
+    pub fn new(setup: &EncloneSetup, tail_opts: &TailOpts, vdj_cells: &[Vec<String>]) -> Self {
         let EncloneSetup {
             ctl,
             ann: _,
@@ -76,7 +78,7 @@ impl EncloneOrbitProcessor {
 
         // Compute extra args.
 
-        let extra_args = extra_args(ctl);
+        let extra_args = extra_args(ctl, tail_opts);
 
         // Determine if any lvars need gex info.
 
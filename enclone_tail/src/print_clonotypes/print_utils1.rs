@@ -1,5 +1,6 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 
+use crate::tail_opts::TailOpts;
 use amino::codon_to_aa;
 use ansi_escape::{
     emit_bold_escape, emit_eight_bit_color_escape, emit_end_escape, emit_red_escape,
@@ -851,7 +852,7 @@ pub fn cdr3_aa_con(
 
 // ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
-pub fn extra_args(ctl: &EncloneControl) -> Vec<String> {
+pub fn extra_args(ctl: &EncloneControl, tail_opts: &TailOpts) -> Vec<String> {
     let mut extra_args = ctl.gen_opt.tree.clone();
     if !ctl.plot_opt.plot_xy_filename.is_empty() {
         extra_args.push(ctl.plot_opt.plot_xy_xvar.clone());
@@ -874,6 +875,7 @@ pub fn extra_args(ctl: &EncloneControl) -> Vec<String> {
         extra_args.extend(gene_scan_opts.control.var.iter().cloned());
     }
     extra_args.append(&mut ctl.plot_opt.sim_mat_plot_vars.clone());
+    extra_args.append(&mut tail_opts.vars());
     for i in 0..ctl.gen_opt.var_def.len() {
         let x = &ctl.gen_opt.var_def[i].2;
         for v in &vars_of_node(x) {
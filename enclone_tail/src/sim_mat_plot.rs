@@ -2,11 +2,10 @@
 //
 // Execute SIM_MAT_PLOT.
 
-use enclone_core::defs::EncloneControl;
-use io_utils::{fwrite, open_for_write_new};
+use crate::plot_utils::emit_svg;
+use enclone_core::defs::PlotOpt;
 use std::collections::HashMap;
 
-use std::io::Write;
 use string_utils::{stringme, TextUtils};
 use tables::print_tabular;
 
@@ -23,14 +22,25 @@ fn hex_color(r: u8, g: u8, b: u8) -> String {
 }
 
 pub fn sim_mat_plot(
-    ctl: &EncloneControl,
+    plot_opt: &PlotOpt, // overrides ctl
     groups: &Vec<Vec<(i32, String)>>,
     out_datas: &Vec<Vec<HashMap<String, String>>>,
     svgs: &mut Vec<String>,
 ) {
-    if !ctl.plot_opt.sim_mat_plot_file.is_empty() {
-        let filename = &ctl.plot_opt.sim_mat_plot_file;
-        let vars = &ctl.plot_opt.sim_mat_plot_vars;
+    if let Some(svg) = sim_mat_svg(plot_opt, groups, out_datas) {
+        emit_svg(svg, &plot_opt.sim_mat_plot_file, svgs);
+    }
+}
+
+// Make the svg for SIM_MAT_PLOT, if it is requested.
+
+pub fn sim_mat_svg(
+    plot_opt: &PlotOpt,
+    groups: &Vec<Vec<(i32, String)>>,
+    out_datas: &Vec<Vec<HashMap<String, String>>>,
+) -> Option<String> {
+    if !plot_opt.sim_mat_plot_file.is_empty() {
+        let vars = &plot_opt.sim_mat_plot_vars;
         let n = vars.len();
         let mut mat = Vec::<Vec<f64>>::new();
         for i in 0..groups.len() {
@@ -140,7 +150,7 @@ pub fn sim_mat_plot(
 
         // Load font if not GUI.
 
-        if ctl.plot_opt.sim_mat_plot_file != "gui" {
+        if plot_opt.sim_mat_plot_file != "gui" {
             svg += "\
               <defs>\n\
                 <style type=\"text/css\">\n\
@@ -220,15 +230,8 @@ pub fn sim_mat_plot(
         // Finish.
 
         svg += "</svg>";
-        if filename == "stdout" || filename == "gui_stdout" {
-            for line in svg.lines() {
-                println!("{}", line);
-            }
-        } else if filename == "gui" {
-            svgs.push(svg);
-        } else {
-            let mut f = open_for_write_new![&filename];
-            fwrite!(f, "{}", svg);
-        }
+        Some(svg)
+    } else {
+        None
     }
 }
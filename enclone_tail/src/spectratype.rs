@@ -0,0 +1,392 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Execute SPECTRATYPE.  Make a grid of histograms of CDR3 amino acid length, with one row for
+// each chain type, and one column for each origin (or dataset, or just one column, according to
+// SPECTRATYPE_BY).  Each cell contributes one count for each of its chains.  The length is that
+// of the cdr3_aa field, which includes the flanking C and W/F.
+//
+// If SPECTRATYPE_COLOR=vfamily, the bars are stacked by V gene family, e.g. IGHV3, and a legend
+// is shown at the right.  The x axis is shared by all panels, whereas each panel has its own
+// y axis.
+//
+// If SPECTRATYPE_CSV is given, the counts underlying the plot are also written there.
+
+use crate::colors::default_colors;
+use crate::plot_utils::{emit_svg, svg_escape, svg_header, CHAIN_TYPES};
+use crate::string_width::arial_width;
+use crate::tail_opts::TailOpts;
+use crate::ticks::ticks;
+use enclone_core::defs::{EncloneControl, ExactClonotype};
+use io_utils::{fwriteln, open_for_write_new};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use string_utils::TextUtils;
+use vdj_ann::refx::RefData;
+
+// Find the family of a V gene, by truncating its name at the first dash or slash, so that for
+// example IGHV3-23 and IGHV3/OR16-9 are both in family IGHV3.
+
+fn v_family(name: &str) -> String {
+    match name.find(['-', '/']) {
+        Some(p) => name[..p].to_string(),
+        None => name.to_string(),
+    }
+}
+
+// Draw one histogram panel, with its upper left corner at (x0, y0).  The ith stack of bars
+// represents length lmin + i, and consists of (family index, count) pairs.
+
+fn draw_panel(
+    svg: &mut String,
+    x0: f64,
+    y0: f64,
+    width: f64,
+    height: f64,
+    title: &str,
+    lmin: usize,
+    stacks: &[Vec<(usize, usize)>],
+    colors: &[String],
+    families: &[String],
+) {
+    let font = "arial";
+    let font_size = 12.0;
+    let axis_ticks = 4;
+
+    // Define the y axis.
+
+    let mut ymax = 1.0_f64;
+    for s in stacks.iter() {
+        ymax = ymax.max(s.iter().map(|x| x.1).sum::<usize>() as f64);
+    }
+    let mut yticks = vec!["0".to_string()];
+    yticks.append(&mut ticks(0.0, ymax as f32, axis_ticks, false));
+    for t in yticks.iter() {
+        ymax = ymax.max(t.force_f64());
+    }
+    let ypos = |y: f64| y0 + height * (1.0 - y / ymax);
+    for t in yticks.iter() {
+        let y = ypos(t.force_f64());
+        *svg += &format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" \
+             stroke=\"rgb(220,220,220)\" stroke-width=\"1\" />\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+             text-anchor=\"end\">{}</text>\n",
+            x0,
+            y,
+            x0 + width,
+            y,
+            x0 - 4.0,
+            y + font_size / 3.0,
+            font,
+            font_size,
+            t
+        );
+    }
+
+    // Draw the bars.
+
+    let bar = width / stacks.len() as f64;
+    for (i, s) in stacks.iter().enumerate() {
+        let mut base = 0;
+        for (f, n) in s.iter() {
+            let (ylow, yhigh) = (ypos(base as f64), ypos((base + n) as f64));
+            let mut tip = format!("length {}: {}", lmin + i, n);
+            if !families[*f].is_empty() {
+                tip = format!("{}, {}", families[*f], tip);
+            }
+            *svg += &format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" \
+                 stroke=\"white\" stroke-width=\"0.5\"><title>{}</title></rect>\n",
+                x0 + i as f64 * bar,
+                yhigh,
+                bar,
+                ylow - yhigh,
+                colors[*f],
+                svg_escape(&tip)
+            );
+            base += n;
+        }
+    }
+
+    // Draw the x axis.  Ticks are placed at the centers of bars.
+
+    let lmax = lmin + stacks.len() - 1;
+    for t in ticks(lmin as f32, lmax as f32, 6, false).iter() {
+        let l = t.force_f64();
+        if l.fract() != 0.0 {
+            continue;
+        }
+        let x = x0 + (l - lmin as f64 + 0.5) * bar;
+        *svg += &format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+             text-anchor=\"middle\">{}</text>\n",
+            x,
+            y0 + height,
+            x,
+            y0 + height + 4.0,
+            x,
+            y0 + height + 6.0 + font_size,
+            font,
+            font_size,
+            t
+        );
+    }
+    *svg += &format!(
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n\
+         <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" />\n\
+         <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"{}\" \
+         text-anchor=\"middle\">{}</text>\n",
+        x0,
+        y0,
+        x0,
+        y0 + height,
+        x0,
+        y0 + height,
+        x0 + width,
+        y0 + height,
+        x0 + width / 2.0,
+        y0 - 6.0,
+        font,
+        font_size + 2.0,
+        svg_escape(title)
+    );
+}
+
+pub fn plot_spectratype(
+    ctl: &EncloneControl,
+    tail_opts: &TailOpts,
+    refdata: &RefData,
+    groups: &Vec<Vec<(i32, String)>>,
+    exacts: &Vec<Vec<usize>>,
+    exact_clonotypes: &Vec<ExactClonotype>,
+    svgs: &mut Vec<String>,
+) -> Result<(), String> {
+    if tail_opts.spectratype.is_empty() {
+        return Ok(());
+    }
+    let by = if tail_opts.spectratype_by.is_empty() {
+        "origin"
+    } else {
+        tail_opts.spectratype_by.as_str()
+    };
+
+    // Tabulate counts, keyed by (chain type index, category, length, V family).  If we are not
+    // coloring by V family, the family is empty.
+
+    let mut counts = BTreeMap::<(usize, String, usize, String), usize>::new();
+    for i in 0..groups.len() {
+        for j in 0..groups[i].len() {
+            let oo = groups[i][j].0 as usize;
+            for u in 0..exacts[oo].len() {
+                let ex = &exact_clonotypes[exacts[oo][u]];
+                for m in 0..ex.ncells() {
+                    let cat = match by {
+                        "origin" => match ex.clones[m][0].origin_index {
+                            Some(x) => ctl.origin_info.origin_list[x].clone(),
+                            None => "unknown".to_string(),
+                        },
+                        "dataset" => {
+                            ctl.origin_info.dataset_id[ex.clones[m][0].dataset_index].clone()
+                        }
+                        _ => "all".to_string(),
+                    };
+                    for k in 0..ex.share.len() {
+                        let s = &ex.share[k];
+                        let chain = refdata.rtype[s.v_ref_id] as usize;
+                        let family = if tail_opts.spectratype_by_vfamily {
+                            v_family(&refdata.name[s.v_ref_id])
+                        } else {
+                            String::new()
+                        };
+                        *counts
+                            .entry((chain, cat.clone(), s.cdr3_aa.len(), family))
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+    if counts.is_empty() {
+        return Err("\nSPECTRATYPE: there are no chains to plot.\n".to_string());
+    }
+
+    // Write the table.
+
+    if !tail_opts.spectratype_csv.is_empty() {
+        let mut f = open_for_write_new![&tail_opts.spectratype_csv];
+        if tail_opts.spectratype_by_vfamily {
+            fwriteln!(f, "chain,{},cdr3_aa_len,v_family,cells", by);
+        } else {
+            fwriteln!(f, "chain,{},cdr3_aa_len,cells", by);
+        }
+        for ((chain, cat, len, family), n) in counts.iter() {
+            let cat = if cat.contains(',') {
+                format!("\"{}\"", cat)
+            } else {
+                cat.clone()
+            };
+            if tail_opts.spectratype_by_vfamily {
+                fwriteln!(
+                    f,
+                    "{},{},{},{},{}",
+                    CHAIN_TYPES[*chain],
+                    cat,
+                    len,
+                    family,
+                    n
+                );
+            } else {
+                fwriteln!(f, "{},{},{},{}", CHAIN_TYPES[*chain], cat, len, n);
+            }
+        }
+    }
+
+    // Define the panels, the shared length range, and the colors.
+
+    let chains = counts.keys().map(|k| k.0).collect::<BTreeSet<_>>();
+    let cats = counts.keys().map(|k| k.1.clone()).collect::<BTreeSet<_>>();
+    let families = counts.keys().map(|k| k.3.clone()).collect::<BTreeSet<_>>();
+    let chains = chains.into_iter().collect::<Vec<_>>();
+    let cats = cats.into_iter().collect::<Vec<_>>();
+    let families = families.into_iter().collect::<Vec<_>>();
+    let lmin = counts.keys().map(|k| k.2).min().unwrap();
+    let lmax = counts.keys().map(|k| k.2).max().unwrap();
+    let colors = if tail_opts.spectratype_by_vfamily {
+        let dc = default_colors();
+        (0..families.len())
+            .map(|i| {
+                let c = &dc[i % dc.len()];
+                format!("rgb({},{},{})", c[0], c[1], c[2])
+            })
+            .collect::<Vec<_>>()
+    } else {
+        vec!["rgb(0,95,175)".to_string()]
+    };
+
+    // Lay out the grid.
+
+    let font_size = 12.0;
+    let margin = 20.0;
+    let (panel_width, panel_height) = (300.0, 180.0);
+    let (hsep, vsep) = (70.0, 60.0);
+    let mut legend_width = 0.0;
+    if tail_opts.spectratype_by_vfamily {
+        for f in families.iter() {
+            legend_width = f64::max(legend_width, arial_width(f, font_size) + 40.0);
+        }
+    }
+    let x0 = margin + 2.0 * font_size + 40.0;
+    let y0 = margin + 40.0;
+    let width = x0 + cats.len() as f64 * (panel_width + hsep) + legend_width + margin;
+    let height = y0 + chains.len() as f64 * (panel_height + vsep) + margin;
+    let mut svg = svg_header(width, height);
+    svg += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"arial\" font-size=\"20\" \
+         text-anchor=\"middle\">CDR3 length distribution</text>\n\
+         <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"arial\" font-size=\"{}\" \
+         text-anchor=\"middle\">CDR3 amino acid length</text>\n\
+         <text transform=\"translate({:.1},{:.1}) rotate(-90)\" font-family=\"arial\" \
+         font-size=\"{}\" text-anchor=\"middle\">cells</text>\n",
+        width / 2.0,
+        margin + 10.0,
+        x0 + cats.len() as f64 * (panel_width + hsep) / 2.0,
+        height - margin,
+        font_size,
+        margin + font_size,
+        y0 + chains.len() as f64 * (panel_height + vsep) / 2.0,
+        font_size
+    );
+
+    // Draw the panels.
+
+    for (r, chain) in chains.iter().enumerate() {
+        for (c, cat) in cats.iter().enumerate() {
+            let mut stacks = vec![Vec::<(usize, usize)>::new(); lmax - lmin + 1];
+            for (fi, family) in families.iter().enumerate() {
+                for len in lmin..=lmax {
+                    let key = (*chain, cat.clone(), len, family.clone());
+                    if let Some(n) = counts.get(&key) {
+                        stacks[len - lmin].push((fi, *n));
+                    }
+                }
+            }
+            let mut title = CHAIN_TYPES[*chain].to_string();
+            if by != "none" {
+                title = format!("{} / {}", title, cat);
+            }
+            draw_panel(
+                &mut svg,
+                x0 + c as f64 * (panel_width + hsep),
+                y0 + r as f64 * (panel_height + vsep),
+                panel_width,
+                panel_height,
+                &title,
+                lmin,
+                &stacks,
+                &colors,
+                &families,
+            );
+        }
+    }
+
+    // Draw the legend.
+
+    if tail_opts.spectratype_by_vfamily {
+        let lx = x0 + cats.len() as f64 * (panel_width + hsep);
+        for (k, f) in families.iter().enumerate() {
+            let ly = y0 + k as f64 * (font_size + 6.0);
+            svg += &format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" fill=\"{}\" />\n\
+                 <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"arial\" \
+                 font-size=\"{}\">{}</text>\n",
+                lx,
+                ly,
+                font_size,
+                font_size,
+                colors[k],
+                lx + font_size + 6.0,
+                ly + font_size - 2.0,
+                font_size,
+                svg_escape(f)
+            );
+        }
+    }
+    svg += "</svg>\n";
+    emit_svg(svg, &tail_opts.spectratype, svgs);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v_family() {
+        assert_eq!(v_family("IGHV3-23"), "IGHV3");
+        assert_eq!(v_family("IGHV3/OR16-9"), "IGHV3");
+        assert_eq!(v_family("TRBV20-1"), "TRBV20");
+        assert_eq!(v_family("IGKV2D-29"), "IGKV2D");
+        assert_eq!(v_family("TRAV1"), "TRAV1");
+    }
+
+    #[test]
+    fn test_draw_panel() {
+        // Three lengths, the second of which has no cells, and the third of which is stacked
+        // from two families.  The tallest stack reaches the top of the panel.
+
+        let stacks = vec![vec![(0, 2)], vec![], vec![(0, 1), (1, 3)]];
+        let colors = vec!["red".to_string(), "blue".to_string()];
+        let families = vec!["IGHV1".to_string(), "IGHV3".to_string()];
+        let mut svg = String::new();
+        draw_panel(
+            &mut svg, 0.0, 0.0, 30.0, 100.0, "IGH", 10, &stacks, &colors, &families,
+        );
+        assert_eq!(svg.matches("<rect ").count(), 3);
+        assert!(svg.contains("<title>IGHV1, length 10: 2</title>"));
+        assert!(svg.contains(
+            "<rect x=\"20.0\" y=\"0.0\" width=\"10.0\" height=\"75.0\" fill=\"blue\" \
+             stroke=\"white\" stroke-width=\"0.5\"><title>IGHV3, length 12: 3</title></rect>"
+        ));
+    }
+}
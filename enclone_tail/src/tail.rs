@@ -3,6 +3,7 @@
 // Group and print clonotypes.  For now, limited grouping functionality.
 
 use crate::group::group_and_print_clonotypes;
+use crate::tail_opts::TailOpts;
 
 use enclone_core::defs::{ColInfo, EncloneControl, ExactClonotype, GexInfo};
 
@@ -32,6 +33,7 @@ pub fn tail_code(
     rsi: &Vec<ColInfo>,
     exact_clonotypes: &Vec<ExactClonotype>,
     ctl: &EncloneControl,
+    tail_opts: &TailOpts,
     out_datas: &mut Vec<Vec<HashMap<String, String>>>,
     join_info: &Vec<JoinInfo>,
     gex_info: &GexInfo,
@@ -58,6 +60,7 @@ pub fn tail_code(
         rsi,
         exact_clonotypes,
         ctl,
+        tail_opts,
         out_datas,
         join_info,
         gex_info,
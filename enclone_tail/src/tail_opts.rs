@@ -0,0 +1,324 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Options that are processed in this crate, rather than by proc_args.  These are removed from
+// the argument list before it is passed to proc_args, and then passed to tail_code.
+
+use crate::pack_circles::{PackAlgorithm, PlotLayout};
+use crate::plots::{is_stdout, PlotsSpec};
+use enclone_core::defs::EncloneControl;
+use enclone_help::help_registry::ArgType;
+use enclone_help::tail_args::tail_args;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TailOpts {
+    // ANNDATA=stem: write per-cell obs table to stem.csv and stem.h5
+    pub anndata: String,
+    // ANNDATA_VARS=x1,...,xn: variables to include in the obs table
+    pub anndata_vars: Vec<String>,
+    // PLOT_VGENE=filename and PLOT_JGENE=filename: gene usage bar charts
+    pub plot_vgene: String,
+    pub plot_jgene: String,
+    // PLOT_VJ_HEATMAP=filename and PLOT_HL_HEATMAP=filename: gene pairing heatmaps
+    pub plot_vj_heatmap: String,
+    pub plot_hl_heatmap: String,
+    // PLOT_GENE_WEIGHT=clonotypes: count clonotypes rather than cells in gene plots
+    pub gene_plot_by_clonotype: bool,
+    // PLOT_GENE_BY=origin: split gene usage bar charts by origin rather than dataset
+    pub gene_plot_by_origin: bool,
+    // PLOT_GENE_CHAIN=chain_type: restrict gene usage plots to one chain type, e.g. IGH
+    pub gene_plot_chain: String,
+    // SPECTRATYPE=filename: CDR3 length histograms, faceted by chain type and origin
+    pub spectratype: String,
+    // SPECTRATYPE_CSV=filename: write the table underlying SPECTRATYPE
+    pub spectratype_csv: String,
+    // SPECTRATYPE_BY=origin|dataset|none: how to split SPECTRATYPE histograms, default origin
+    pub spectratype_by: String,
+    // SPECTRATYPE_COLOR=vfamily: stack SPECTRATYPE histogram bars by V gene family
+    pub spectratype_by_vfamily: bool,
+    // LOGO=filename: sequence logos for CDR3s
+    pub logo: String,
+    // LOGO_BY=all|group|clonotype: which sets of CDR3s to make logos for, default all
+    pub logo_by: String,
+    // PEER_LOGO=filename: sequence logos for the peer groups of the V genes that are shown
+    pub peer_logo: String,
+    // ALLUVIAL=filename: clonotype frequencies across origins, drawn as ribbons
+    pub alluvial: String,
+    // ALLUVIAL_CSV=filename: write the table underlying ALLUVIAL
+    pub alluvial_csv: String,
+    // ALLUVIAL_TOP=n: number of clonotypes to show in ALLUVIAL, default 10
+    pub alluvial_top: Option<usize>,
+    // ALLUVIAL_ORDER=o1,...,on: origins to show in ALLUVIAL, in order
+    pub alluvial_order: Vec<String>,
+    // HTML_PLOT=filename: honeycomb plot as an interactive html page
+    pub html_plot: String,
+    // HTML_PLOT_VARS=x1,...,xn: per-cell variables to show when hovering in HTML_PLOT
+    pub html_plot_vars: Vec<String>,
+    // PLOT_XY_FACET=var: split PLOT_XY into panels, one for each value of var, which may be
+    // dataset, origin, donor or a variable
+    pub plot_xy_facet: String,
+    // PLOT_XY_FIT: add a least squares line and R² to PLOT_XY
+    pub plot_xy_fit: bool,
+    // PLOT_XY_DENSITY: add density contours to PLOT_XY
+    pub plot_xy_density: bool,
+    // PLOTS=spec: an additional honeycomb, xy or similarity matrix plot; may be repeated
+    pub plots: Vec<PlotsSpec>,
+    // PLOT_LAYOUT=random|frontchain[,seed=n][,stats]: circle packing layout for honeycomb plots
+    pub plot_layout: PlotLayout,
+}
+
+impl TailOpts {
+    // Return the variables whose values need to be computed for these options.
+
+    pub fn vars(&self) -> Vec<String> {
+        let mut vars = Vec::<String>::new();
+        if !self.anndata.is_empty() {
+            vars.append(&mut self.anndata_vars.clone());
+        }
+        if !self.html_plot.is_empty() {
+            vars.append(&mut self.html_plot_vars.clone());
+        }
+        if !["", "dataset", "origin", "donor"].contains(&self.plot_xy_facet.as_str()) {
+            vars.push(self.plot_xy_facet.clone());
+        }
+        for p in self.plots.iter() {
+            vars.append(&mut p.vars());
+        }
+        vars
+    }
+
+    // Check for consistency, after all arguments have been processed.
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.anndata.is_empty() && !self.anndata_vars.is_empty() {
+            return Err("\nANNDATA_VARS may only be used with ANNDATA.\n".to_string());
+        }
+        if self.spectratype.is_empty()
+            && (!self.spectratype_csv.is_empty()
+                || !self.spectratype_by.is_empty()
+                || self.spectratype_by_vfamily)
+        {
+            return Err(
+                "\nSPECTRATYPE_CSV, SPECTRATYPE_BY and SPECTRATYPE_COLOR may only be used with \
+                 SPECTRATYPE.\n"
+                    .to_string(),
+            );
+        }
+        if self.logo.is_empty() && !self.logo_by.is_empty() {
+            return Err("\nLOGO_BY may only be used with LOGO.\n".to_string());
+        }
+        if self.alluvial.is_empty()
+            && (!self.alluvial_csv.is_empty()
+                || self.alluvial_top.is_some()
+                || !self.alluvial_order.is_empty())
+        {
+            return Err(
+                "\nALLUVIAL_CSV, ALLUVIAL_TOP and ALLUVIAL_ORDER may only be used with ALLUVIAL.\n"
+                    .to_string(),
+            );
+        }
+        if self.html_plot.is_empty() && !self.html_plot_vars.is_empty() {
+            return Err("\nHTML_PLOT_VARS may only be used with HTML_PLOT.\n".to_string());
+        }
+        if self.plots.iter().filter(|p| is_stdout(p.out())).count() > 1 {
+            return Err(
+                "\nAt most one PLOTS argument may direct its output to stdout.\n".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    // Return true if PLOT_XY is to be made with the options here, rather than by the default
+    // code path.
+
+    pub fn plot_xy_extended(&self) -> bool {
+        !self.plot_xy_facet.is_empty() || self.plot_xy_fit || self.plot_xy_density
+    }
+
+    // Check for consistency with options processed by proc_args.
+
+    pub fn check_ctl(&self, ctl: &EncloneControl) -> Result<(), String> {
+        if self.plot_xy_extended() && ctl.plot_opt.plot_xy_filename.is_empty() {
+            return Err(
+                "\nPLOT_XY_FACET, PLOT_XY_FIT and PLOT_XY_DENSITY may only be used with PLOT_XY.\n"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    // Store the value of an argument in tail_args().  For a flag, the value is empty.
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let list = || {
+            value
+                .split(',')
+                .filter(|x| !x.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<String>>()
+        };
+        match key {
+            "ANNDATA" => {
+                if value.is_empty() {
+                    return Err("\nANNDATA requires a nonempty filename stem.\n".to_string());
+                }
+                self.anndata = value.to_string();
+            }
+            "ANNDATA_VARS" => self.anndata_vars = list(),
+            "PLOT_VGENE" => self.plot_vgene = value.to_string(),
+            "PLOT_JGENE" => self.plot_jgene = value.to_string(),
+            "PLOT_VJ_HEATMAP" => self.plot_vj_heatmap = value.to_string(),
+            "PLOT_HL_HEATMAP" => self.plot_hl_heatmap = value.to_string(),
+            "PLOT_GENE_WEIGHT" => self.gene_plot_by_clonotype = value == "clonotypes",
+            "PLOT_GENE_BY" => self.gene_plot_by_origin = value == "origin",
+            "PLOT_GENE_CHAIN" => self.gene_plot_chain = value.to_string(),
+            "SPECTRATYPE" => self.spectratype = value.to_string(),
+            "SPECTRATYPE_CSV" => self.spectratype_csv = value.to_string(),
+            "SPECTRATYPE_BY" => self.spectratype_by = value.to_string(),
+            "SPECTRATYPE_COLOR" => self.spectratype_by_vfamily = true,
+            "LOGO" => self.logo = value.to_string(),
+            "LOGO_BY" => self.logo_by = value.to_string(),
+            "PEER_LOGO" => self.peer_logo = value.to_string(),
+            "ALLUVIAL" => self.alluvial = value.to_string(),
+            "ALLUVIAL_CSV" => self.alluvial_csv = value.to_string(),
+            "ALLUVIAL_TOP" => match value.parse::<usize>() {
+                Ok(n) if n > 0 => self.alluvial_top = Some(n),
+                _ => {
+                    return Err(
+                        "\nThe value of ALLUVIAL_TOP must be a positive integer.\n".to_string()
+                    )
+                }
+            },
+            "ALLUVIAL_ORDER" => self.alluvial_order = list(),
+            "HTML_PLOT" => {
+                if value.is_empty() {
+                    return Err("\nHTML_PLOT requires a nonempty filename.\n".to_string());
+                }
+                self.html_plot = value.to_string();
+            }
+            "HTML_PLOT_VARS" => self.html_plot_vars = list(),
+            "PLOT_XY_FACET" => self.plot_xy_facet = value.to_string(),
+            "PLOT_XY_FIT" => self.plot_xy_fit = true,
+            "PLOT_XY_DENSITY" => self.plot_xy_density = true,
+            "PLOTS" => self.plots.push(PlotsSpec::parse(value)?),
+            "PLOT_LAYOUT" => self.plot_layout = parse_plot_layout(value)?,
+            _ => panic!(
+                "The argument {} is in tail_args(), but its value is not stored.",
+                key
+            ),
+        }
+        Ok(())
+    }
+}
+
+// Process an argument.  Return true if it was recognized, which is the case if it is in
+// tail_args().  Whether it has a value, and if so whether the value is one of the declared values,
+// is checked here, using its ArgDoc, and the value is then stored.
+
+pub fn proc_tail_arg(arg: &str, opts: &mut TailOpts) -> Result<bool, String> {
+    let (key, value) = match arg.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (arg, None),
+    };
+    let a = match tail_args().find(|a| a.key() == key) {
+        Some(a) => a,
+        None => return Ok(false),
+    };
+    match (a.value_type, value) {
+        (ArgType::Flag, None) => {}
+        (ArgType::Flag, Some(_)) => {
+            return Err(format!("\nThe argument {} does not take a value.\n", key));
+        }
+        (_, None) => {
+            return Err(format!(
+                "\nThe argument {} requires a value, as in {}.\n",
+                key, a.name
+            ));
+        }
+        (_, Some(value)) => {
+            if !a.values.is_empty() && !a.values.contains(&value) {
+                let n = a.values.len();
+                let mut values = a.values[..n - 1].join(", ");
+                if n > 1 {
+                    values += " or ";
+                }
+                values += a.values[n - 1];
+                return Err(format!("\nThe value of {} must be {}.\n", key, values));
+            }
+        }
+    }
+    opts.set(key, value.unwrap_or(""))?;
+    Ok(true)
+}
+
+// Parse the value of PLOT_LAYOUT.
+
+fn parse_plot_layout(spec: &str) -> Result<PlotLayout, String> {
+    let mut layout = PlotLayout::default();
+    for (i, x) in spec.split(',').enumerate() {
+        if i == 0 {
+            layout.algorithm = match x {
+                "random" => PackAlgorithm::Random,
+                "frontchain" => PackAlgorithm::FrontChain,
+                _ => {
+                    return Err(
+                        "\nThe value of PLOT_LAYOUT must start with random or frontchain.\n"
+                            .to_string(),
+                    )
+                }
+            };
+        } else if x == "stats" {
+            layout.stats = true;
+        } else if let Some(Ok(seed)) = x.strip_prefix("seed=").map(str::parse::<u64>) {
+            layout.seed = seed;
+        } else {
+            return Err(format!(
+                "\nUnrecognized field {} in PLOT_LAYOUT.  The allowed fields after the layout \
+                 name are seed=n, where n is a nonnegative integer, and stats.\n",
+                x
+            ));
+        }
+    }
+    Ok(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proc_tail_arg() {
+        // Every argument in tail_args() is recognized and stored.
+
+        for a in tail_args() {
+            let arg = match a.value_type {
+                ArgType::Flag => a.key().to_string(),
+                _ if a.key() == "PLOTS" => "PLOTS=xy:u1,u2,plot.svg".to_string(),
+                _ if !a.default.is_empty() => format!("{}={}", a.key(), a.default),
+                _ if !a.values.is_empty() => format!("{}={}", a.key(), a.values[0]),
+                _ => format!("{}=x", a.key()),
+            };
+            let mut opts = TailOpts::default();
+            assert_eq!(proc_tail_arg(&arg, &mut opts), Ok(true), "{}", arg);
+        }
+        let mut opts = TailOpts::default();
+        assert_eq!(proc_tail_arg("MIN_CELLS=3", &mut opts), Ok(false));
+        assert_eq!(proc_tail_arg("LOGO_BY=clonotype", &mut opts), Ok(true));
+        assert_eq!(opts.logo_by, "clonotype");
+        assert_eq!(
+            proc_tail_arg("PLOT_GENE_WEIGHT=clonotypes", &mut opts),
+            Ok(true)
+        );
+        assert!(opts.gene_plot_by_clonotype);
+        assert_eq!(
+            proc_tail_arg("LOGO_BY=donor", &mut opts),
+            Err("\nThe value of LOGO_BY must be all, group or clonotype.\n".to_string())
+        );
+        assert_eq!(
+            proc_tail_arg("SPECTRATYPE_COLOR=red", &mut opts),
+            Err("\nThe value of SPECTRATYPE_COLOR must be vfamily.\n".to_string())
+        );
+        assert!(proc_tail_arg("PLOT_XY_FIT=1", &mut opts).is_err());
+        assert!(proc_tail_arg("ALLUVIAL_TOP", &mut opts).is_err());
+        assert!(proc_tail_arg("ALLUVIAL_TOP=0", &mut opts).is_err());
+    }
+}
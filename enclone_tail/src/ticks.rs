@@ -2,6 +2,7 @@
 //
 // Find tick marks, given a range on an axis, and a maximum number of ticks.
 // The ticks lie in the range, and if the left end of the range is zero, zero is not included.
+// Likewise if the right end of the range is zero.
 //
 // This has been designed to approximately match the behavior of plotters 0.3.0 on a
 // set of test cases.
@@ -10,13 +11,19 @@
 
 use string_utils::strme;
 
-// Given x ≠ 0, find r and s such that x = r * 10^s, and 1.0 <= |r| < 10.
+// Given x ≠ 0, find r and s such that x = r * 10^s, and 1.0 <= |r| < 10.  For x very small or
+// very large, 10^-s is not representable as an f32, so we scale in f64.
 
 fn normalize_f32(x: f32, r: &mut f32, s: &mut isize) {
     assert!(x != 0.0);
     let y = x.abs();
     *s = y.log10().floor() as isize;
-    *r = x * 10.0_f32.powi(-(*s as i32));
+    let m = 10.0_f32.powi(-(*s as i32));
+    if m.is_normal() {
+        *r = x * m;
+    } else {
+        *r = (x as f64 * 10.0_f64.powi(-(*s as i32))) as f32;
+    }
 }
 
 pub fn ticks(low: f32, high: f32, max_ticks: usize, verbose: bool) -> Vec<String> {
@@ -27,9 +34,20 @@ pub fn ticks(low: f32, high: f32, max_ticks: usize, verbose: bool) -> Vec<String
     if low == high {
         return vec![format!("{}", low)];
     }
-    let mut low = low;
+    let (mut low, mut high) = (low, high);
     if low == 0.0 {
-        low = std::f32::MIN_POSITIVE * 1000.0; // tests fail without some multiplier
+        // Tests fail without some multiplier.
+        low = (std::f32::MIN_POSITIVE * 1000.0).min(high / 2.0);
+    } else if high == 0.0 {
+        high = (-std::f32::MIN_POSITIVE * 1000.0).max(low / 2.0);
+    }
+
+    // This happens if the range is too small to divide.
+
+    if low == 0.0 {
+        return vec![format!("{}", high)];
+    } else if high == 0.0 {
+        return vec![format!("{}", low)];
     }
 
     // Find ri and si such that:
@@ -172,6 +190,16 @@ mod tests {
         examples.push((-0.01, 0.1, vec!["0.00", "0.05", "0.10"]));
         examples.push((-0.03, 0.01, vec!["-0.03", "-0.02", "-0.01", "0.00", "0.01"]));
         examples.push((-0.2, 0.1, vec!["-0.2", "-0.1", "0.0", "0.1"]));
+        examples.push((-8.16, 0.0, vec!["-8", "-6", "-4", "-2"]));
+        examples.push((
+            0.0,
+            3.07e-43,
+            vec![
+                "0.00000000000000000000000000000000000000000020",
+                "0.00000000000000000000000000000000000000000025",
+                "0.00000000000000000000000000000000000000000030",
+            ],
+        ));
 
         // Test examples.
 
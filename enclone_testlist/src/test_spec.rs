@@ -0,0 +1,170 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+//
+// Structured declaration of an enclone regression test.  A test is an enclone command line,
+// together with what is expected of it, and is run by enclone_tools::run_test::run_spec.
+
+// What a test expects of enclone.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expect {
+    // stdout is the same as the golden file, and stderr is empty
+    Output,
+    // as for Output, but the golden file is expected to be (nearly) empty
+    Null,
+    // enclone exits with status 1
+    Fail,
+    // enclone exits with status 0, and its output is not checked
+    Ok,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TestSpec<'a> {
+    // test category e.g. "test" or "ext_test", which with num names the golden file
+    pub category: &'a str,
+    // test number
+    pub num: usize,
+    // info about the test
+    pub comments: &'a str,
+    // arguments for enclone; newlines and repeated blanks are ignored, and {TEST_FILES_VERSION}
+    // is replaced by its value
+    pub args: &'a str,
+    pub expect: Expect,
+    // the output is not supposed to ever change
+    pub set_in_stone: bool,
+    // don't add PRE for the test datasets
+    pub no_pre: bool,
+    // don't cap the number of cores
+    pub ncores: bool,
+    // tags, which may be used to select tests, see enclone_tools::run_test::spec_selected
+    pub tags: &'a [&'a str],
+}
+
+impl<'a> TestSpec<'a> {
+    pub const fn new(category: &'a str, num: usize, comments: &'a str, args: &'a str) -> Self {
+        TestSpec {
+            category,
+            num,
+            comments,
+            args,
+            expect: Expect::Output,
+            set_in_stone: false,
+            no_pre: false,
+            ncores: false,
+            tags: &[],
+        }
+    }
+
+    pub const fn expect_null(mut self) -> Self {
+        self.expect = Expect::Null;
+        self
+    }
+
+    pub const fn expect_fail(mut self) -> Self {
+        self.expect = Expect::Fail;
+        self
+    }
+
+    pub const fn expect_ok(mut self) -> Self {
+        self.expect = Expect::Ok;
+        self
+    }
+
+    pub const fn set_in_stone(mut self) -> Self {
+        self.set_in_stone = true;
+        self
+    }
+
+    pub const fn no_pre(mut self) -> Self {
+        self.no_pre = true;
+        self
+    }
+
+    pub const fn ncores(mut self) -> Self {
+        self.ncores = true;
+        self
+    }
+
+    pub const fn tags(mut self, tags: &'a [&'a str]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    // Golden file for the output of the test, relative to enclone_exec.
+
+    pub fn golden_file(&self) -> String {
+        format!(
+            "testx/inputs/outputs/enclone_{}{}_output",
+            self.category, self.num
+        )
+    }
+
+    // Arguments for enclone, as they are to be passed, but still as a single string.
+
+    pub fn command(&self, test_files_version: u8) -> String {
+        let mut args = self
+            .args
+            .replace('\n', "")
+            .replace("{TEST_FILES_VERSION}", &format!("{}", test_files_version));
+        while args.contains("  ") {
+            args = args.replace("  ", " ");
+        }
+        args
+    }
+
+    // Datasets that the test requires, meaning the numerical dataset ids that appear in BCR=,
+    // TCR=, GEX=, BCR_GEX= and TCR_GEX=.  These are found in the test data directory, unless
+    // no_pre is set.
+
+    pub fn datasets(&self) -> Vec<String> {
+        let mut ids = Vec::<String>::new();
+        for arg in self.args.split_whitespace() {
+            let Some((key, value)) = arg.split_once('=') else {
+                continue;
+            };
+            if !["BCR", "TCR", "GEX", "BCR_GEX", "TCR_GEX"].contains(&key) {
+                continue;
+            }
+            for id in value.trim_matches('"').split([',', ':', ';']) {
+                if !id.is_empty()
+                    && id.chars().all(|c| c.is_ascii_digit())
+                    && !ids.iter().any(|x| x == id)
+                {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_spec() {
+        const SPEC: TestSpec = TestSpec::new(
+            "test",
+            10,
+            "",
+            r###"BCR="123085;123089" GEX=123217
+             LVARSP=gex BC=testx/inputs/123077_cells.csv PRE=x/version{TEST_FILES_VERSION}"###,
+        )
+        .expect_ok()
+        .tags(&["per_cell"]);
+        assert_eq!(SPEC.expect, Expect::Ok);
+        assert_eq!(SPEC.tags, &["per_cell"]);
+        assert_eq!(
+            SPEC.golden_file(),
+            "testx/inputs/outputs/enclone_test10_output"
+        );
+        assert_eq!(
+            SPEC.command(15),
+            "BCR=\"123085;123089\" GEX=123217 LVARSP=gex BC=testx/inputs/123077_cells.csv \
+             PRE=x/version15"
+        );
+        assert_eq!(SPEC.datasets(), vec!["123085", "123089", "123217"]);
+        let spec = TestSpec::new("test", 1, "", "BCR=testx/inputs/flaky TCR=1:2,1");
+        assert_eq!(spec.datasets(), vec!["1", "2"]);
+    }
+}
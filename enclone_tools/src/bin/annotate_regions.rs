@@ -0,0 +1,97 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// annotate_regions chain_type sequence [option_1 ... option_n]
+//
+// where chain_type is IGH, IGK, IGL, TRA or TRB, and sequence is an amino acid or DNA sequence
+// of a V segment, or a fasta filename, and the following options are allowed:
+// NO_LEADER  the sequences do not include a leader
+// AA         treat the sequences as amino acids, even if they look like DNA
+//
+// The sequences may extend beyond the start of the CDR3.  For each sequence, print the FWR1,
+// CDR1, FWR2, CDR2 and FWR3 regions and the start of the CDR3, in amino acid coordinates,
+// together with confidence scores.  See enclone_denovo/src/vdj_regions.rs.
+
+use enclone_denovo::vdj_regions::{is_bases, v_regions, v_regions_from_bases};
+use fasta_tools::read_fasta_to_vec_vec_u8;
+use io_utils::path_exists;
+use std::env;
+use string_utils::strme;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!("\nusage = annotate_regions chain_type sequence [NO_LEADER] [AA]\n");
+        std::process::exit(1);
+    }
+    let chain_type = &args[1];
+    let mut has_leader = true;
+    let mut force_aa = false;
+    for i in 3..args.len() {
+        if args[i] == "NO_LEADER" {
+            has_leader = false;
+        } else if args[i] == "AA" {
+            force_aa = true;
+        } else {
+            eprintln!("\nIllegal argument {}.\n", args[i]);
+            std::process::exit(1);
+        }
+    }
+
+    // Get the sequences.  If the argument is not a file, and consists only of letters, it's a
+    // sequence.
+
+    let mut seqs = Vec::<Vec<u8>>::new();
+    let mut headers = Vec::<String>::new();
+    if !path_exists(&args[2]) {
+        if !args[2].chars().all(|c| c.is_ascii_alphabetic()) {
+            eprintln!("\nCan't find the file {}.\n", args[2]);
+            std::process::exit(1);
+        }
+        seqs.push(args[2].as_bytes().to_vec());
+        headers.push("seq".to_string());
+    } else {
+        let x = read_fasta_to_vec_vec_u8(&args[2]);
+        for i in (0..x.len()).step_by(2) {
+            headers.push(strme(&x[i]).to_string());
+            seqs.push(x[i + 1].clone());
+        }
+    }
+
+    // Annotate and print.
+
+    println!("id\tregion\tstart\tstop\tconfidence\tseq");
+    let mut fails = 0;
+    for i in 0..seqs.len() {
+        let r = if is_bases(&seqs[i]) && !force_aa {
+            v_regions_from_bases(&seqs[i], chain_type, has_leader)
+        } else {
+            v_regions(&seqs[i], chain_type, has_leader)
+        };
+        match r {
+            Err(e) => {
+                eprintln!("{}: {}", headers[i], e.trim());
+                fails += 1;
+            }
+            Ok(r) => {
+                for x in r.regions.iter() {
+                    println!(
+                        "{}\t{}\t{}\t{}\t{:.2}\t{}",
+                        headers[i],
+                        x.name,
+                        x.start,
+                        x.stop,
+                        x.confidence,
+                        strme(&r.aa[x.start..x.stop])
+                    );
+                }
+                println!(
+                    "{}\tCDR3\t{}\t\t{:.2}\t",
+                    headers[i], r.cdr3_start, r.cdr3_confidence
+                );
+            }
+        }
+    }
+    if fails > 0 {
+        std::process::exit(1);
+    }
+}
@@ -15,22 +15,22 @@
 //         better than another.)
 // REF=fasta_file_name = use this reference
 //
-// Annotate the given DNA sequences.
+// Annotate the given DNA sequences.  To annotate many sequences interactively, without
+// rebuilding the reference for each, use annotate_service.
 
 use annotate::*;
 use bio_edit::alignment::pairwise::*;
 use bio_edit::alignment::AlignmentOperation::*;
 use debruijn::dna_string::*;
 use debruijn::*;
+use enclone_tools::seq_annotation::{human_supp_regions, mouse_supp_regions};
 use fasta_tools::*;
-use flate2::read::GzDecoder;
 use io_utils::fwriteln;
 use itertools::Itertools;
 
 use refx::*;
 use std::cmp::min;
 use std::env;
-use std::io::Read;
 use std::io::Write;
 use string_utils::*;
 use vdj_ann::transcript::*;
@@ -284,26 +284,3 @@ fn main() {
         println!("{}", stringme(&log));
     }
 }
-
-/// Return the decompressed supp regions data for human.
-fn human_supp_regions() -> String {
-    decompress(include_bytes!(
-        "../../../supp_refs/human/supp_regions.fa.gz"
-    ))
-    .unwrap()
-}
-
-/// Return the decompressed supp regions data for mouse.
-fn mouse_supp_regions() -> String {
-    decompress(include_bytes!(
-        "../../../supp_refs/mouse/supp_regions.fa.gz"
-    ))
-    .unwrap()
-}
-
-fn decompress(data: &[u8]) -> std::io::Result<String> {
-    let mut reader = GzDecoder::new(data);
-    let mut contents = String::new();
-    reader.read_to_string(&mut contents)?;
-    Ok(contents)
-}
@@ -0,0 +1,119 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// annotate_service species [option_1 ... option_n]
+//
+// Long-lived version of annotate_seq.  The reference is built once, then sequences are read
+// from stdin, one request per line, and annotated, writing one JSON object per line to stdout.
+// Output is flushed after each line, so this may be driven interactively, e.g. from a notebook
+// via a subprocess pipe.
+//
+// species is human or mouse, and the following options are allowed:
+// IMGT   use IMGT reference
+// TCR    use only TCR reference
+// BCR    use only BCR reference
+// REF=fasta_file_name = use this reference
+//
+// A request is either a bare DNA sequence, or a JSON object
+// {"id": "name", "seq": "ACGT...", "rc": false}
+// where id and rc are optional.  An input line may also be a JSON array of such objects, in
+// which case the sequences are annotated in parallel and the responses written in order, one
+// per line.  Blank lines are ignored.
+//
+// Each response has the fields of SeqAnnotation in enclone_tools/src/seq_annotation.rs: id, len,
+// v_gene, d_gene, j_gene, c_gene, cdr3_aa, cdr3_nt, cdr3_start, productive, alignments and
+// error.  If a request cannot be parsed or annotated, error is set, and if the line is valid
+// JSON, its ids are still echoed.
+
+use enclone_tools::seq_annotation::{
+    annotate_request, human_supp_regions, mouse_supp_regions, parse_error_responses,
+    parse_requests, SeqAnnotation,
+};
+use rayon::prelude::*;
+use std::env;
+use std::io::{stdin, stdout, BufRead, Write};
+use string_utils::TextUtils;
+use vdj_ann::refx::{make_vdj_ref_data_core, RefData};
+use vdj_ann_ref::make_vdj_ref_data;
+
+fn main() {
+    // Parse args.
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("\nusage = annotate_service species [option_1 ... option_n]\n");
+        std::process::exit(1);
+    }
+    let species = args[1].to_string();
+    let mut imgt = false;
+    let (mut is_tcr, mut is_bcr) = (true, true);
+    let mut ref_fasta = String::new();
+    for j in 2..args.len() {
+        if args[j] == "IMGT" {
+            imgt = true;
+        } else if args[j] == "TCR" {
+            is_bcr = false;
+        } else if args[j] == "BCR" {
+            is_tcr = false;
+        } else if args[j].starts_with("REF=") {
+            ref_fasta = args[j].after("REF=").to_string();
+        } else {
+            eprintln!("\nIllegal argument {}.\n", args[j]);
+            std::process::exit(1);
+        }
+    }
+
+    // Make reference data, once.
+
+    let mut refdata = RefData::new();
+    if !ref_fasta.is_empty() {
+        let refx = std::fs::read_to_string(&ref_fasta).unwrap_or_else(|_| {
+            eprintln!("\nUnable to read reference file {}.\n", ref_fasta);
+            std::process::exit(1);
+        });
+        let ext_ref = String::new();
+        make_vdj_ref_data_core(&mut refdata, &refx, &ext_ref, is_tcr, is_bcr, None);
+    } else {
+        make_vdj_ref_data(
+            &mut refdata,
+            imgt,
+            &species,
+            false,
+            is_tcr,
+            is_bcr,
+            &human_supp_regions(),
+            &mouse_supp_regions(),
+        );
+    }
+    eprintln!("annotate_service: reference loaded, reading requests from stdin");
+
+    // Serve requests.
+
+    let stdin = stdin();
+    let stdout = stdout();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let responses = match parse_requests(&line) {
+            Ok(reqs) => reqs
+                .par_iter()
+                .map(|req| annotate_request(req, &refdata))
+                .collect::<Vec<SeqAnnotation>>(),
+            Err(e) => parse_error_responses(&line, &e),
+        };
+        // Stop if the client has gone away.
+
+        let mut out = stdout.lock();
+        let mut ok = true;
+        for r in responses.iter() {
+            ok = ok && writeln!(out, "{}", serde_json::to_string(r).unwrap()).is_ok();
+        }
+        if !ok || out.flush().is_err() {
+            break;
+        }
+    }
+}
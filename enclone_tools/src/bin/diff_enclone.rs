@@ -1,52 +1,65 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 
-// Run enclone twice on the same dataset, where the two runs differ either by
+// Run enclone twice on the same dataset (BCR or TCR), where the two runs differ either by
 // • an extra command-line argument (specified by OLD_PARAM=...)
 // • or a code version (specified by OLD_EXEC=..., as comparsed to whatever PATH gives).
-// Then diff the outputs, piping an organized version of visual output to less.
+// Then diff the clonotypes, piping an organized version of visual output to less.
 //
-// usage diff_clonotypes <argument list>
+// usage diff_enclone <argument list>
 // where exactly one of OLD_PARAM=... or OLD_EXEC... must appear in the list.
 //
-// As implemented, this only detects differences where a clonotype changes either its
-// barcodes or its number of chains.
+// Old and new clonotypes are matched by the barcodes they share, and each difference is reported
+// as a split, merge, rearrangement, change of membership (cells gained or lost) or change of
+// calls (V, J or CDR3, or the value of a lead variable).  See enclone_tools/src/clonotype_diff.rs.
+// The lead variables that are compared are those given by LVARS=... and LVARSP=...; they must
+// have per cell values.  Grouping arguments may be used, and then clonotypes are identified by
+// group and clonotype within the group.
+//
+// First summary statistics are printed, and then for each difference, a description of it,
+// followed by the old and new visual output for its barcodes.  Cells are identified by dataset
+// and barcode, but BARCODE= cannot name a dataset, so if there are several datasets, the visual
+// output may also show cells from other datasets that share those barcodes.
+//
+// Optional argument: HTML_REPORT=filename: also write the summary and differences as html.
 //
 // example:
 // diff_enclone BCR=123085 OLD_PARAM=NDOUBLET
-//
-// Do not use any of the grouping arguments.
 
 use enclone::misc1::setup_pager;
-use equiv::EquivRel;
-use itertools::Itertools;
-
-use std::collections::HashMap;
+use enclone_tools::clonotype_diff::{
+    describe_diff, describe_summary, diff_clonotypes, html_report, parse_clonotypes, pcols,
+};
 use std::env;
 use std::process::Command;
 use string_utils::{strme, TextUtils};
-use vector_utils::{bin_member, bin_position, next_diff1_2, unique_sort};
 
 fn main() {
     setup_pager(true);
     let mut args: Vec<String> = env::args().collect();
-    let (mut old_param, mut old_exec) = (None, None);
+    let (mut old_param, mut old_exec, mut html) = (None, None, None);
     let mut args2 = Vec::<String>::new();
-    let blacklist = ["PCELL", "POUT", "PCOLS", "BARCODE", "NOPAGER", "NOPRINT"];
+    let mut lvars = Vec::<String>::new();
+    let blacklist = [
+        "PCELL", "POUT", "PCOLS", "PCHAINS", "BARCODE", "NOPAGER", "NOPRINT",
+    ];
     for i in 1..args.len() {
         let mut a = args[i].clone();
         if a.contains('=') {
             a = a.before("=").to_string();
         }
-        for j in 0..blacklist.len() {
-            if a == blacklist[j] {
-                eprintln!("\nPlease do not use {} as an argument.\n", a);
-                std::process::exit(1);
-            }
+        if blacklist.contains(&a.as_str()) {
+            eprintln!("\nPlease do not use {} as an argument.\n", a);
+            std::process::exit(1);
+        }
+        if a == "LVARS" || a == "LVARSP" {
+            lvars.extend(args[i].after("=").split(',').map(str::to_string));
         }
         if args[i].starts_with("OLD_PARAM=") {
             old_param = Some(args[i].after("OLD_PARAM=").to_string());
         } else if args[i].starts_with("OLD_EXEC=") {
             old_exec = Some(args[i].after("OLD_EXEC=").to_string());
+        } else if args[i].starts_with("HTML_REPORT=") {
+            html = Some(args[i].after("HTML_REPORT=").to_string());
         } else {
             args2.push(args[i].clone());
         }
@@ -56,13 +69,11 @@ fn main() {
         eprintln!("\nExactly one of OLD_PARAM=... or OLD_EXEC=... must be specified.\n");
         std::process::exit(1);
     }
-    let pcols = "barcode,group_id,exact_subclonotype_id,nchains";
 
-    // Run enclone twice, once for "old" and once for "new".  Return the parseable output lines
-    // as outs.
+    // Run enclone, for pass 1 ("old") or pass 2 ("new"), with some additional arguments, and
+    // return its output.
 
-    let mut outs = Vec::<Vec<String>>::new();
-    for pass in 1..=2 {
+    let run = |pass: usize, extra: &[String]| -> String {
         let mut argsp = args.clone();
         if pass == 1 && old_param.is_some() {
             argsp.push(old_param.clone().unwrap());
@@ -75,10 +86,7 @@ fn main() {
         }
         let new = new
             .args(&argsp)
-            .arg("PCELL")
-            .arg(&format!("PCOLS={}", pcols))
-            .arg("POUT=stdout")
-            .arg("NOPRINT")
+            .args(extra)
             .output()
             .unwrap_or_else(|_| panic!("{}", "failed to execute enclone".to_string()));
         if new.status.code() != Some(0) {
@@ -88,140 +96,47 @@ fn main() {
             );
             std::process::exit(1);
         }
-        let out = strme(&new.stdout);
-        let mut lines = Vec::<String>::new();
-        for x in out.lines() {
-            lines.push(x.to_string());
-        }
-        outs.push(lines);
-    }
-    let outs_orig = outs.clone();
+        strme(&new.stdout).to_string()
+    };
 
-    // Map barcodes to groups and then remove the groups.  And sort.
-
-    let mut to_group = vec![HashMap::<String, String>::new(); 2];
-    let mut to_groupx = HashMap::<String, (String, usize)>::new();
-    for v in 0..2 {
-        for i in 0..outs[v].len() {
-            let barcode = outs[v][i].before(",").to_string();
-            let group = outs[v][i].after(",").before(",").to_string();
-            to_group[v].insert(barcode.clone(), group.clone());
-            to_groupx.insert(barcode, (group, v));
-            outs[v][i] = format!(
-                "{},{}",
-                outs[v][i].before(","),
-                outs[v][i].after(",").after(",")
-            );
-        }
-        outs[v].sort();
-    }
-
-    // Find barcodes that have different entries in the two outs.
-
-    let mut bc = Vec::<String>::new();
-    let mut group = vec![Vec::<(String, String)>::new(); 2];
-    for v1 in 0..2 {
-        let v2 = (v1 + 1) % 2;
-        let (lines1, lines2) = (&outs[v1], &outs[v2]);
-        for j in 0..lines1.len() {
-            if !bin_member(lines2, &lines1[j]) {
-                let b = lines1[j].before(",").to_string();
-                bc.push(b.clone());
-                group[v1].push((to_group[v1][&b].clone(), b));
-            }
-        }
-        group[v1].sort();
-    }
-    unique_sort(&mut bc);
+    // Get the clonotypes for old and new, and compare them.
 
-    // Group the barcodes.  If two barcodes are in the same clonotype for either run, then
-    // they get grouped together.
-
-    let mut e = EquivRel::new(bc.len() as i32);
-    for v in 0..2 {
-        let mut i = 0;
-        while i < group[v].len() {
-            let j = next_diff1_2(&group[v], i);
-            for k in i + 1..j {
-                e.join(
-                    bin_position(&bc, &group[v][i].1) as i32,
-                    bin_position(&bc, &group[v][k].1) as i32,
-                );
-            }
-            i = j;
-        }
-    }
-
-    // Define the groups.
-
-    let mut groups = Vec::<Vec<String>>::new();
-    let mut reps = Vec::<i32>::new();
-    e.orbit_reps(&mut reps);
-    for i in 0..reps.len() {
-        let mut o = Vec::<i32>::new();
-        e.orbit(reps[i], &mut o);
-        let mut b = Vec::<String>::new();
-        for j in 0..o.len() {
-            b.push(bc[o[j] as usize].clone());
-        }
-        groups.push(b);
+    let mut clonotypes = Vec::new();
+    for pass in 1..=2 {
+        let out = run(
+            pass,
+            &[
+                "PCELL".to_string(),
+                format!("PCOLS={}", pcols(&lvars)),
+                "POUT=stdout".to_string(),
+                "NOPRINT".to_string(),
+            ],
+        );
+        clonotypes.push(parse_clonotypes(&out, &lvars).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }));
     }
-
-    // Expand the groups to include the full clonotypes.
-
-    for j in 0..groups.len() {
-        let mut groupsx = Vec::<(String, usize)>::new();
-        for i in 0..groups[j].len() {
-            groupsx.push(to_groupx[&groups[j][i]].clone());
-        }
-        unique_sort(&mut groupsx);
-        for v in 0..2 {
-            for i in 0..outs_orig[v].len() {
-                let barcode = outs_orig[v][i].before(",").to_string();
-                let group = outs_orig[v][i].after(",").before(",").to_string();
-                if bin_member(&groupsx, &(group, v)) {
-                    groups[j].push(barcode.clone());
-                }
-            }
-        }
-        unique_sort(&mut groups[j]);
+    let (diffs, summary) = diff_clonotypes(&clonotypes[0], &clonotypes[1], &lvars);
+    println!("\n{}", describe_summary(&summary));
+    if let Some(html) = html {
+        let title = format!("diff_enclone {}", args.join(" "));
+        std::fs::write(&html, html_report(&title, &diffs, &summary))
+            .unwrap_or_else(|_| panic!("could not write {}", html));
     }
 
-    // For each group of barcodes, run enclone using the group, for both old and new.
+    // For each difference, run enclone using its barcodes, for both old and new.
 
-    println!();
-    for i in 0..groups.len() {
-        let bc_arg = format!("{}", groups[i].iter().format(","));
+    for (i, d) in diffs.iter().enumerate() {
+        println!("DIFFERENCE {}\n{}", i + 1, describe_diff(d));
+        let bc_arg = format!("BARCODE={}", d.barcodes.join(","));
         for pass in 1..=2 {
             if pass == 1 {
                 println!("OLD {}", i + 1);
             } else {
                 println!("NEW {}", i + 1);
             }
-            let mut argsp = args.clone();
-            if pass == 1 && old_param.clone().is_some() {
-                argsp.push(old_param.clone().unwrap());
-            }
-            let mut new;
-            if pass == 2 || old_param.is_some() {
-                new = Command::new("enclone")
-            } else {
-                new = Command::new(old_exec.clone().unwrap());
-            }
-            let new = new
-                .args(&argsp)
-                .arg(&format!("BARCODE={}", bc_arg))
-                .arg("NOPAGER")
-                .output()
-                .unwrap_or_else(|_| panic!("{}", "failed to execute enclone".to_string()));
-            if new.status.code() != Some(0) {
-                eprint!(
-                    "\nenclone call returned nonzero status code, stderr =\n{}",
-                    strme(&new.stderr),
-                );
-                std::process::exit(1);
-            }
-            print!("{}", strme(&new.stdout));
+            print!("{}", run(pass, &[bc_arg.clone(), "NOPAGER".to_string()]));
         }
     }
 }
@@ -1,159 +1,608 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 //
-// Given a BCR  all_contig_annotations.json file, and other arguments for enclone, such that
-// the corresponding enclone command fails, iteratively attempt to make the json file smaller,
-// while maintaining the fails.
+// Given enclone arguments for which enclone fails, iteratively shrink the VDJ inputs, while
+// maintaining the fail, and then write out the shrunken inputs, and a test case that uses them.
 //
-// We require that the enclone passes, whereas enclone.old fails.
+// Usage:
 //
-// Fail may be defined by (a) output containing specified text or (b) panic.
+// minimal_fail WORK=dir FAIL=condition [ENCLONE=exe] [ENCLONE2=exe] [NAME=name] args...
 //
-// This can be used to make tests.
+// where args are the arguments for enclone.  These must include BCR= or TCR=, which may list
+// several datasets, and may include GEX=, with one gene expression dataset for each VDJ dataset.
+// Datasets are found either directly or under PRE=.  BCR_GEX= and TCR_GEX= are not supported:
+// please use BCR= or TCR= together with GEX= instead.
 //
-// Argument 1: text (possibly in quotes) or "#PANIC".
-// Argument 2: path to the all_contig_annotations.json file.
-// Argument 3: working directory.
-// Argument 4-: additional arguments.
+// The fail condition may be:
+// panic    enclone panics
+// exit:n   enclone exits with status n
+// text:s   the standard output or standard error of enclone contains the text s
+// diff     the standard output or exit status of ENCLONE and ENCLONE2 differ.
 //
-// Some extensions you might make by temporarily editing this file:
-// 1. Change the name of the enclone executable.
-// 2. Add a second test using a different enclone executable.
-
-use io_utils::*;
+// ENCLONE defaults to enclone.  If ENCLONE2 is given, and the condition is not diff, we require
+// in addition that ENCLONE2 does not fail, so that for example ENCLONE might be an old version
+// and ENCLONE2 a version with a fix.
+//
+// Shrinking is done first by deleting all the contigs for a barcode, and then by deleting single
+// contigs, in chunks of decreasing size, as long as the fail persists.  The shrunken
+// all_contig_annotations.json files are written to dir/name/vdj1/outs, dir/name/vdj2/outs, etc.,
+// where name defaults to minimal_fail, along with the VDJ reference if there is one.  The gene
+// expression inputs are not changed during shrinking, but at the end, they are subsetted to the
+// barcodes that remain, and written to dir/name/gex1/outs, etc., provided that the fail persists.
+//
+// Finally an entry for enclone_testlist/src/main_testlist.rs is printed, with instructions for
+// adding it.
 
+use enclone_testlist::main_testlist::TESTS;
+use hdf5::types::FixedAscii;
+use io_utils::{fwriteln, open_for_write_new, open_maybe_compressed, path_exists};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::env;
-use std::fs::copy;
-use std::io::Write;
-use std::process::Command;
-use string_utils::*;
-use vector_utils::*;
-
-fn fails(
-    fail_condition: &str,
-    entries: &Vec<Value>,
-    using: &Vec<bool>,
-    work: &str,
-    working_json: &str,
-    extra: &Vec<String>,
-) -> bool {
-    let n = entries.len();
-    let mut entriesx = entries.clone();
-    let mut to_delete = vec![false; n];
-    for i in 0..n {
-        if !using[i] {
-            to_delete[i] = true;
-        }
-    }
-    erase_if(&mut entriesx, &to_delete);
-    {
-        let mut f = open_for_write_new![&working_json];
-        fwriteln!(f, "[");
-        for i in 0..entriesx.len() {
-            fwrite!(f, "{}", entriesx[i]);
-            if i < entriesx.len() - 1 {
-                fwrite!(f, ",");
+use std::fs::{copy, create_dir_all, read_to_string};
+use std::io::{Read, Write};
+use std::process::{Command, Output};
+use string_utils::strme;
+use vector_utils::{bin_member, unique_sort};
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Files in a gene expression outs directory that are copied as is, if present, and csv files that
+// are subsetted to the kept barcodes, if present.
+
+const GEX_COPY: [&str; 4] = [
+    "metrics_summary.csv",
+    "metrics_summary_json.json",
+    "per_feature_metrics.csv",
+    "feature_reference.csv",
+];
+const GEX_CSV: [&str; 4] = [
+    "analysis/clustering/graphclust/clusters.csv",
+    "analysis/clustering/gene_expression_graphclust/clusters.csv",
+    "analysis/pca/10_components/projection.csv",
+    "analysis/pca/gene_expression_10_components/projection.csv",
+];
+const GEX_H5: &str = "raw_feature_bc_matrix.h5";
+const ANN: &str = "all_contig_annotations.json";
+const REF_FILES: [&str; 2] = [
+    "vdj_reference/fasta/regions.fa",
+    "vdj_reference/reference.json",
+];
+
+enum Fail {
+    Panic,
+    Exit(i32),
+    Text(String),
+    Diff,
+}
+
+impl Fail {
+    fn parse(s: &str) -> Result<Fail, String> {
+        if s == "panic" {
+            Ok(Fail::Panic)
+        } else if s == "diff" {
+            Ok(Fail::Diff)
+        } else if let Some(t) = s.strip_prefix("text:") {
+            Ok(Fail::Text(t.to_string()))
+        } else if let Some(n) = s.strip_prefix("exit:") {
+            n.parse::<i32>()
+                .map(Fail::Exit)
+                .map_err(|_| format!("\nminimal_fail: illegal exit status in FAIL={}\n", s))
+        } else {
+            Err(format!(
+                "\nminimal_fail: FAIL={} is not one of panic, exit:n, text:s and diff\n",
+                s
+            ))
+        }
+    }
+
+    fn holds(&self, o: &Output) -> bool {
+        match self {
+            Fail::Panic => o.status.code() == Some(101),
+            Fail::Exit(n) => o.status.code() == Some(*n),
+            Fail::Text(t) => strme(&o.stdout).contains(t) || strme(&o.stderr).contains(t),
+            Fail::Diff => false,
+        }
+    }
+}
+
+// A list of datasets as given to enclone, e.g. 1,2;3, split into its dataset paths, so that it
+// can be rebuilt with different paths but the same separators.
+
+struct DatasetList {
+    value: String,
+    paths: Vec<String>,
+}
+
+impl DatasetList {
+    fn new(value: &str) -> DatasetList {
+        DatasetList {
+            value: value.to_string(),
+            paths: value.split([',', ':', ';']).map(str::to_string).collect(),
+        }
+    }
+
+    fn with_paths(&self, paths: &[String]) -> String {
+        let mut s = paths[0].clone();
+        let mut i = 0;
+        for c in self.value.chars() {
+            if [',', ':', ';'].contains(&c) {
+                i += 1;
+                s.push(c);
+                s += &paths[i];
             }
-            fwriteln!(f, "");
         }
-        fwriteln!(f, "]");
+        s
     }
+}
 
-    // Execute old enclone.
+// Find the outs directory of a dataset, which is the directory or its outs subdirectory, either
+// as given, or under one of the PRE directories, that contains the given file.
 
-    let o = Command::new("enclone.old")
-        .arg(&format!("BCR={}", work))
-        .args(extra)
-        .output()
-        .expect("failed to execute old enclone");
+fn find_outs(path: &str, pre: &[String], file: &str) -> Result<String, String> {
+    let mut dirs = vec![path.to_string()];
+    for p in pre.iter() {
+        dirs.push(format!("{}/{}", p, path));
+    }
+    for d in dirs.iter() {
+        for outs in [d.clone(), format!("{}/outs", d)] {
+            if path_exists(&format!("{}/{}", outs, file))
+                || path_exists(&format!("{}/{}.lz4", outs, file))
+            {
+                return Ok(outs);
+            }
+        }
+    }
+    Err(format!(
+        "\nminimal_fail: could not find {} for the dataset {}\n",
+        file, path
+    ))
+}
 
-    // Test for lack of status code.  It is not clear if this can happen.  It is possible that
-    // it only happened on old code run with new rust, and reflects some sort of incompatibility
-    // therein.
+// Read an all_contig_annotations.json file, which may be a json array or a stream of json
+// objects, and may be lz4 compressed.
 
-    if o.status.code().is_none() {
-        return false;
+fn read_entries(outs: &str) -> Result<Vec<Value>, String> {
+    let mut json = format!("{}/{}", outs, ANN);
+    if !path_exists(&json) {
+        json = format!("{}.lz4", json);
     }
+    let mut contents = String::new();
+    open_maybe_compressed(&json)
+        .read_to_string(&mut contents)
+        .map_err(|e| format!("\nminimal_fail: could not read {}: {}\n", json, e))?;
+    let mut entries = Vec::<Value>::new();
+    for v in serde_json::Deserializer::from_str(&contents).into_iter::<Value>() {
+        match v {
+            Ok(Value::Array(x)) => entries.extend(x),
+            Ok(x) => entries.push(x),
+            Err(e) => return Err(format!("\nminimal_fail: could not parse {}: {}\n", json, e)),
+        }
+    }
+    Ok(entries)
+}
 
-    // Check for fail.
+fn write_entries(json: &str, entries: &[Value], keep: &[bool]) {
+    let mut f = open_for_write_new![&json];
+    fwriteln!(f, "[");
+    let kept = entries
+        .iter()
+        .zip(keep.iter())
+        .filter(|(_, k)| **k)
+        .map(|(e, _)| e.to_string())
+        .collect::<Vec<_>>();
+    fwriteln!(f, "{}", kept.join(",\n"));
+    fwriteln!(f, "]");
+}
+
+fn kept_barcodes(entries: &[Value], keep: &[bool]) -> Vec<String> {
+    let mut barcodes = Vec::<String>::new();
+    for (e, k) in entries.iter().zip(keep.iter()) {
+        if let (true, Some(bc)) = (*k, e["barcode"].as_str()) {
+            barcodes.push(bc.to_string());
+        }
+    }
+    unique_sort(&mut barcodes);
+    barcodes
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
 
-    let status = o.status.code().unwrap();
-    let panicked = status == 101;
-    let out = strme(&o.stdout);
+// Subset gene expression data to the given barcodes.
 
-    let mut failed = false;
-    if fail_condition == "#PANIC" {
-        failed = panicked;
-    } else if out.contains(fail_condition) {
-        failed = true;
+fn write_fixed<const N: usize>(g: &hdf5::Group, name: &str, x: &[String]) -> hdf5::Result<()> {
+    let mut y = Vec::<FixedAscii<N>>::with_capacity(x.len());
+    for s in x.iter() {
+        y.push(FixedAscii::<N>::from_ascii(s.as_bytes()).map_err(|e| e.to_string())?);
     }
-    if failed {
-        copy(working_json, format!("{}.good", working_json)).unwrap();
+    g.new_dataset_builder()
+        .with_data(y.as_slice())
+        .create(name)?;
+    Ok(())
+}
+
+fn write_strings(g: &hdf5::Group, name: &str, x: &[String]) -> hdf5::Result<()> {
+    let max = x.iter().map(String::len).max().unwrap_or(0);
+    if max <= 16 {
+        write_fixed::<16>(g, name, x)
+    } else if max <= 64 {
+        write_fixed::<64>(g, name, x)
+    } else {
+        write_fixed::<256>(g, name, x)
     }
-    failed
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let fail_condition = &args[1];
-    let json = &args[2];
-    let work = &args[3];
-    let mut extra = Vec::<String>::new();
-    for i in 4..args.len() {
-        extra.push(args[i].clone());
-    }
-    let json = std::fs::read_to_string(json).unwrap();
-    let v: Value = serde_json::from_str(&json).unwrap();
-    let entries = v.as_array().unwrap();
-    let n = entries.len();
-    println!("len = {}", n);
-    let mut using = vec![true; n];
-
-    let outs = format!("{}/outs", work);
-    if !path_exists(&outs) {
-        std::fs::create_dir(&outs).unwrap();
-    }
-    let working_json = format!("{}/all_contig_annotations.json", outs);
-
-    let mut total = 0;
-    loop {
-        let mut progress = false;
-        for k in [10000, 1000, 100, 10, 1].iter() {
-            let k = *k;
-            if (total == 0 || total > k) && n >= k {
-                for i in (0..=n - k).step_by(k) {
-                    let mut usingx = using.clone();
-                    for j in 0..k {
-                        usingx[i + j] = false;
-                    }
-                    if usingx != using {
-                        println!("trying to delete {}", k);
-                        let panicked = fails(
-                            fail_condition,
-                            entries,
-                            &usingx,
-                            work,
-                            &working_json,
-                            &extra,
-                        );
-                        if panicked {
-                            using = usingx;
-                            total = 0;
-                            for x in using.iter() {
-                                if *x {
-                                    total += 1;
-                                }
-                            }
-                            println!("deleted {}, leaving {}", k, total);
-                            progress = true;
+fn read_strings(g: &hdf5::Group, name: &str) -> hdf5::Result<Vec<String>> {
+    let x = g.dataset(name)?.read_raw::<FixedAscii<256>>()?;
+    Ok(x.iter().map(|s| s.as_str().to_string()).collect())
+}
+
+// Rewrite the matrix, keeping only the columns for the given barcodes.  The features are copied
+// unchanged.
+
+fn subset_h5(h5: &str, h5_out: &str, barcodes: &[String]) -> hdf5::Result<()> {
+    let file = hdf5::File::open(h5)?;
+    let m = file.group("matrix")?;
+    let bcs = read_strings(&m, "barcodes")?;
+    let data = m.dataset("data")?.read_raw::<i32>()?;
+    let indices = m.dataset("indices")?.read_raw::<i64>()?;
+    let indptr = m.dataset("indptr")?.read_raw::<i64>()?;
+    let shape = m.dataset("shape")?.read_raw::<i32>()?;
+    let (mut bcs_out, mut data_out, mut indices_out) = (Vec::new(), Vec::new(), Vec::new());
+    let mut indptr_out = vec![0_i64];
+    for (j, bc) in bcs.iter().enumerate() {
+        if bin_member(barcodes, bc) {
+            let (start, stop) = (indptr[j] as usize, indptr[j + 1] as usize);
+            bcs_out.push(bc.clone());
+            data_out.extend_from_slice(&data[start..stop]);
+            indices_out.extend_from_slice(&indices[start..stop]);
+            indptr_out.push(data_out.len() as i64);
+        }
+    }
+    let file_out = hdf5::File::create(h5_out)?;
+    let m_out = file_out.create_group("matrix")?;
+    write_strings(&m_out, "barcodes", &bcs_out)?;
+    m_out
+        .new_dataset_builder()
+        .with_data(data_out.as_slice())
+        .create("data")?;
+    m_out
+        .new_dataset_builder()
+        .with_data(indices_out.as_slice())
+        .create("indices")?;
+    m_out
+        .new_dataset_builder()
+        .with_data(indptr_out.as_slice())
+        .create("indptr")?;
+    m_out
+        .new_dataset_builder()
+        .with_data(&[shape[0], bcs_out.len() as i32][..])
+        .create("shape")?;
+    let f = m.group("features")?;
+    let f_out = m_out.create_group("features")?;
+    for name in f.member_names()? {
+        if f.dataset(&name).is_ok() {
+            write_strings(&f_out, &name, &read_strings(&f, &name)?)?;
+        }
+    }
+    Ok(())
+}
+
+fn subset_csv(csv: &str, csv_out: &str, barcodes: &[String]) -> Result<(), String> {
+    let lines = read_to_string(csv).map_err(|e| format!("\ncould not read {}: {}\n", csv, e))?;
+    let mut f = open_for_write_new![&csv_out];
+    for (i, line) in lines.lines().enumerate() {
+        let bc = line.split(',').next().unwrap().to_string();
+        if i == 0 || bin_member(barcodes, &bc) {
+            fwriteln!(f, "{}", line);
+        }
+    }
+    Ok(())
+}
+
+fn subset_gex(outs: &str, outs_out: &str, barcodes: &[String]) -> Result<(), String> {
+    let err = |e: String| format!("\nminimal_fail: could not subset {}: {}\n", outs, e);
+    for file in GEX_COPY.iter().chain(GEX_CSV.iter()) {
+        let (src, dst) = (
+            format!("{}/{}", outs, file),
+            format!("{}/{}", outs_out, file),
+        );
+        if !path_exists(&src) {
+            continue;
+        }
+        create_dir_all(&dst[..dst.rfind('/').unwrap()]).map_err(|e| err(e.to_string()))?;
+        if GEX_CSV.contains(file) {
+            subset_csv(&src, &dst, barcodes).map_err(err)?;
+        } else {
+            copy(&src, &dst).map_err(|e| err(e.to_string()))?;
+        }
+    }
+    subset_h5(
+        &format!("{}/{}", outs, GEX_H5),
+        &format!("{}/{}", outs_out, GEX_H5),
+        barcodes,
+    )
+    .map_err(|e| err(e.to_string()))
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+struct Shrinker {
+    fail: Fail,
+    enclone: String,
+    enclone2: Option<String>,
+    // BCR or TCR
+    chain: String,
+    vdj: DatasetList,
+    gex: Option<DatasetList>,
+    // the other arguments for enclone
+    extra: Vec<String>,
+    // where the shrunken inputs go
+    dir: String,
+    entries: Vec<Vec<Value>>,
+}
+
+impl Shrinker {
+    fn vdj_dir(&self, k: usize) -> String {
+        format!("{}/vdj{}/outs", self.dir, k + 1)
+    }
+
+    fn gex_dir(&self, k: usize) -> String {
+        format!("{}/gex{}/outs", self.dir, k + 1)
+    }
+
+    fn run(&self, enclone: &str, gex: Option<&str>) -> Output {
+        let n = self.entries.len();
+        let vdj = (0..n).map(|k| self.vdj_dir(k)).collect::<Vec<_>>();
+        let mut args = vec![format!("{}={}", self.chain, self.vdj.with_paths(&vdj))];
+        if let Some(gex) = gex {
+            args.push(format!("GEX={}", gex));
+        }
+        args.extend(self.extra.iter().cloned());
+        args.push("NO_KILL".to_string());
+        Command::new(enclone)
+            .args(&args)
+            .output()
+            .unwrap_or_else(|e| panic!("failed to execute {}: {}", enclone, e))
+    }
+
+    // Write the VDJ inputs defined by keep.
+
+    fn write(&self, keep: &[Vec<bool>]) {
+        for (k, entries) in self.entries.iter().enumerate() {
+            write_entries(&format!("{}/{}", self.vdj_dir(k), ANN), entries, &keep[k]);
+        }
+    }
+
+    // Write the VDJ inputs defined by keep, and test if enclone fails on them.
+
+    fn fails(&self, keep: &[Vec<bool>], gex: Option<&str>) -> bool {
+        self.write(keep);
+        let o = self.run(&self.enclone, gex);
+        if o.status.code().is_none() {
+            return false;
+        }
+        match (&self.fail, &self.enclone2) {
+            (Fail::Diff, Some(enclone2)) => {
+                let o2 = self.run(enclone2, gex);
+                o2.status.code() != o.status.code() || o2.stdout != o.stdout
+            }
+            (fail, Some(enclone2)) => fail.holds(&o) && !fail.holds(&self.run(enclone2, gex)),
+            (fail, None) => fail.holds(&o),
+        }
+    }
+
+    // Delete units, in chunks of decreasing size, for as long as the fail persists.  A unit is a
+    // list of (dataset, entry) pairs.  At least one entry is kept in each dataset.
+
+    fn shrink(&self, units: &[Vec<(usize, usize)>], keep: &mut Vec<Vec<bool>>, gex: Option<&str>) {
+        let n = units.len();
+        loop {
+            let mut progress = false;
+            for k in [10000, 1000, 100, 10, 1] {
+                for i in (0..n).step_by(k) {
+                    let mut keepx = keep.clone();
+                    for unit in units[i..n.min(i + k)].iter() {
+                        for &(d, j) in unit.iter() {
+                            keepx[d][j] = false;
                         }
                     }
+                    if keepx == *keep || keepx.iter().any(|x| !x.contains(&true)) {
+                        continue;
+                    }
+                    println!("trying to delete {}", k);
+                    if self.fails(&keepx, gex) {
+                        *keep = keepx;
+                        let total = keep.iter().flatten().filter(|x| **x).count();
+                        println!("deleted {}, leaving {} contigs", k, total);
+                        progress = true;
+                    }
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+    }
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+fn minimal_fail(args: &[String]) -> Result<(), String> {
+    let (mut work, mut fail, mut name) = (None, None, "minimal_fail".to_string());
+    let (mut enclone, mut enclone2) = ("enclone".to_string(), None);
+    let (mut chain, mut vdj, mut gex) = (None, None, None);
+    let (mut pre, mut extra) = (Vec::new(), Vec::new());
+    for arg in args.iter() {
+        let (key, value) = arg.split_once('=').unwrap_or((arg, ""));
+        match key {
+            "WORK" => work = Some(value.to_string()),
+            "FAIL" => fail = Some(Fail::parse(value)?),
+            "NAME" => name = value.to_string(),
+            "ENCLONE" => enclone = value.to_string(),
+            "ENCLONE2" => enclone2 = Some(value.to_string()),
+            "BCR" | "TCR" => {
+                if chain.is_some() {
+                    return Err("\nminimal_fail: please specify only one of BCR= and TCR=\n".into());
                 }
+                chain = Some(key.to_string());
+                vdj = Some(DatasetList::new(value));
             }
+            "GEX" => gex = Some(DatasetList::new(value)),
+            "BCR_GEX" | "TCR_GEX" => {
+                return Err(format!(
+                    "\nminimal_fail: {}= is not supported, please use {}= and GEX= instead\n",
+                    key,
+                    &key[0..3]
+                ));
+            }
+            "PRE" => pre = value.split(',').map(str::to_string).collect(),
+            _ => extra.push(arg.clone()),
+        }
+    }
+    let (Some(work), Some(fail), Some(chain), Some(vdj)) = (work, fail, chain, vdj) else {
+        return Err("\nminimal_fail: WORK=, FAIL= and one of BCR= or TCR= are required\n".into());
+    };
+    if matches!(fail, Fail::Diff) && enclone2.is_none() {
+        return Err("\nminimal_fail: FAIL=diff requires ENCLONE2=\n".into());
+    }
+    if let Some(gex) = gex.as_ref() {
+        if gex.paths.len() != vdj.paths.len() {
+            return Err("\nminimal_fail: GEX= must list one dataset for each VDJ dataset\n".into());
+        }
+    }
+
+    // Find the inputs, and set up the directory for the shrunken ones.
+
+    let dir = format!("{}/{}", work, name);
+    let builtin_ref = extra
+        .iter()
+        .any(|x| x == "BUILT_IN" || x.starts_with("REF="));
+    let mut entries = Vec::new();
+    for (k, path) in vdj.paths.iter().enumerate() {
+        let outs = find_outs(path, &pre, ANN)?;
+        entries.push(read_entries(&outs)?);
+        let outs_out = format!("{}/vdj{}/outs", dir, k + 1);
+        create_dir_all(&outs_out).map_err(|e| format!("\ncould not create {}: {}\n", dir, e))?;
+        for file in REF_FILES.iter() {
+            let src = format!("{}/{}", outs, file);
+            if !builtin_ref && path_exists(&src) {
+                let dst = format!("{}/{}", outs_out, file);
+                create_dir_all(&dst[..dst.rfind('/').unwrap()]).unwrap();
+                copy(&src, &dst).unwrap();
+            }
+        }
+    }
+    let mut gex_outs = Vec::new();
+    if let Some(gex) = gex.as_ref() {
+        for path in gex.paths.iter() {
+            gex_outs.push(find_outs(path, &pre, GEX_H5)?);
+        }
+    }
+    let full_gex = gex.as_ref().map(|g| g.with_paths(&gex_outs));
+    let s = Shrinker {
+        fail,
+        enclone,
+        enclone2,
+        chain,
+        vdj,
+        gex,
+        extra,
+        dir,
+        entries,
+    };
+
+    // Shrink, first by barcode and then by contig.
+
+    let mut keep = s
+        .entries
+        .iter()
+        .map(|x| vec![true; x.len()])
+        .collect::<Vec<_>>();
+    let total = keep.iter().flatten().count();
+    println!("starting with {} contigs", total);
+    if !s.fails(&keep, full_gex.as_deref()) {
+        return Err("\nminimal_fail: the fail does not occur on the full inputs\n".into());
+    }
+    let mut by_barcode = HashMap::<(usize, String), usize>::new();
+    let mut units = Vec::<Vec<(usize, usize)>>::new();
+    for (d, entries) in s.entries.iter().enumerate() {
+        for (j, e) in entries.iter().enumerate() {
+            let bc = e["barcode"].as_str().unwrap_or_default().to_string();
+            let u = *by_barcode.entry((d, bc)).or_insert(units.len());
+            if u == units.len() {
+                units.push(Vec::new());
+            }
+            units[u].push((d, j));
+        }
+    }
+    s.shrink(&units, &mut keep, full_gex.as_deref());
+    let units = (0..s.entries.len())
+        .flat_map(|d| (0..s.entries[d].len()).map(move |j| vec![(d, j)]))
+        .collect::<Vec<_>>();
+    s.shrink(&units, &mut keep, full_gex.as_deref());
+
+    // Subset the gene expression data, and check that the fail persists.
+
+    let mut gex_subsetted = false;
+    if let Some(gex) = s.gex.as_ref() {
+        for (k, outs) in gex_outs.iter().enumerate() {
+            subset_gex(outs, &s.gex_dir(k), &kept_barcodes(&s.entries[k], &keep[k]))?;
         }
-        if !progress {
-            break;
+        let gex_dirs = (0..gex_outs.len())
+            .map(|k| s.gex_dir(k))
+            .collect::<Vec<_>>();
+        gex_subsetted = s.fails(&keep, Some(&gex.with_paths(&gex_dirs)));
+        if !gex_subsetted {
+            println!(
+                "\nWARNING: the fail does not occur using the gene expression data subsetted to \
+                 the remaining barcodes, so the full gene expression data are used below."
+            );
         }
     }
-    println!("\nfinal = {}\n", total);
+    s.write(&keep);
+    let total = keep.iter().flatten().filter(|x| **x).count();
+    println!("\nfinal = {} contigs, in {}\n", total, s.dir);
+
+    // Print the test.
+
+    let n = s.entries.len();
+    let test_dir = |kind: &str, k: usize| format!("testx/inputs/{}/{}{}", name, kind, k + 1);
+    let vdj_dirs = (0..n).map(|k| test_dir("vdj", k)).collect::<Vec<_>>();
+    let mut cmd = vec![format!("{}={}", s.chain, s.vdj.with_paths(&vdj_dirs))];
+    if let Some(gex) = s.gex.as_ref() {
+        if gex_subsetted {
+            let gex_dirs = (0..n).map(|k| test_dir("gex", k)).collect::<Vec<_>>();
+            cmd.push(format!("GEX={}", gex.with_paths(&gex_dirs)));
+        } else {
+            cmd.push(format!("GEX={}", gex.value));
+        }
+    }
+    cmd.extend(s.extra.iter().cloned());
+    let num = TESTS.len() + 1;
+    println!(
+        "To make a test, copy {} to enclone_exec/testx/inputs/{}, add this entry at the end of \
+         enclone_testlist/src/main_testlist.rs,\n",
+        s.dir, name
+    );
+    println!(
+        "            test_enclone_{} => $crate::main_testlist::main_test({}, \"found by \
+         minimal_fail\",\n            r###\"{}\"###),\n",
+        num,
+        num,
+        cmd.join(" ")
+    );
+    println!(
+        "and once the bug is fixed, create the golden file by running\n\n\
+         UPDATE_GOLDEN=1 cargo test -p enclone test_enclone_{} -- --exact\n",
+        num
+    );
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let Err(e) = minimal_fail(&args[1..]) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,58 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Regenerate the outputs of the examples that are shown in the help pages, see
+// enclone_help/src/help_examples.rs.  Run from the top level of the repo, after ./build.
+//
+// Each example is run using the test datasets.  If an output changed, its file
+// in enclone_help/src is rewritten.  The examples that are only on the site are instead
+// regenerated by merge_html BUILD.  Then ./build needs to be run again, to recompile enclone and
+// rebuild the help pages that show the examples.
+//
+// If supplied the single argument CHECK, only report which outputs have changed, and exit with
+// status 1 if any have.
+
+use enclone_help::help_examples::{help_example_args, help_example_drift, help_page_examples};
+use enclone_testlist::TEST_FILES_VERSION;
+use std::env;
+use std::fs::write;
+use std::process::Command;
+use string_utils::strme;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let check = args.len() == 2 && args[1] == "CHECK";
+    let pre = format!("enclone-data/big_inputs/version{}", TEST_FILES_VERSION);
+    let mut changed = false;
+    for ex in help_page_examples() {
+        let new = Command::new("target/debug/enclone")
+            .args(&help_example_args(ex, &pre))
+            .output()
+            .unwrap_or_else(|_| panic!("{}", "failed to execute enclone".to_string()));
+        if new.status.code() != Some(0) {
+            eprintln!("\nupdate_help_examples: enclone {} failed", ex.args);
+            eprintln!("stderr:\n{}", strme(&new.stderr));
+            std::process::exit(1);
+        }
+        let new = strme(&new.stdout);
+        if let Some(drift) = help_example_drift(ex, new) {
+            println!("\n{}", drift);
+            changed = true;
+            if !check {
+                write(ex.file, new).unwrap_or_else(|_| panic!("could not write {}", ex.file));
+                println!("updated {}", ex.file);
+            }
+        }
+    }
+    if !changed {
+        println!("\nall help examples are up to date\n");
+        return;
+    }
+    if check {
+        println!("\nPlease run target/debug/update_help_examples.\n");
+        std::process::exit(1);
+    }
+    println!(
+        "\nNow please run ./build, which recompiles enclone, since the outputs are compiled into \
+         it, and then rebuilds the help pages.\n"
+    );
+}
@@ -0,0 +1,647 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Compare the clonotypes found by two enclone runs on the same data, as seen in parseable output
+// with PCELL, see diff_enclone.
+//
+// Clonotypes are identified by group_id.clonotype_id, so grouping may be used.  Old and new
+// clonotypes are matched by the barcodes they share: an old and a new clonotype are linked if
+// they share a barcode, and each connected set of linked clonotypes is compared as a unit.  A unit
+// that has one old and one new clonotype, with the same cells, with the same V, J and CDR3 calls,
+// and with the same values of the compared lead variables, is unchanged.  Otherwise it is a
+// difference, of one of these kinds:
+// • split: one old clonotype became several new ones
+// • merge: several old clonotypes became one new one
+// • rearranged: several old clonotypes became several new ones
+// • membership: one old clonotype became one new one, but cells were gained or lost
+// • calls: the cells are the same, but the V, J or CDR3 calls, or lead variable values, changed
+// In addition, a clonotype all of whose cells are new, or all of whose cells were lost, is a unit
+// by itself, of kind membership.
+//
+// Cells are identified by dataset and barcode, since the same barcode may appear in several
+// datasets.  In reports, a cell is named by its barcode if there is one dataset, and otherwise by
+// barcode-dataset, as in the ANNDATA index.
+
+use equiv::EquivRel;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// Chain fields that are requested for each chain, and the number of chains.  The latter is
+// enclone's default for parseable output.
+
+pub const CHAIN_FIELDS: [&str; 3] = ["v_name", "j_name", "cdr3_aa"];
+pub const PCHAINS: usize = 4;
+
+/// The parseable output fields to request from enclone, with PCELL, given the lead variables to
+/// compare.
+pub fn pcols(lvars: &[String]) -> String {
+    let mut cols = vec![
+        "barcode".to_string(),
+        "datasets".into(),
+        "group_id".into(),
+        "clonotype_id".into(),
+    ];
+    for i in 1..=PCHAINS {
+        for f in CHAIN_FIELDS.iter() {
+            cols.push(format!("{}{}", f, i));
+        }
+    }
+    cols.extend(lvars.iter().cloned());
+    cols.join(",")
+}
+
+// A chain, as V, J and CDR3.
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Chain {
+    pub v: String,
+    pub j: String,
+    pub cdr3: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub barcode: String,
+    pub dataset: String,
+    // index of the clonotype in Clonotypes::names
+    pub clonotype: usize,
+    // sorted, so that a change in chain order is not a change
+    pub chains: Vec<Chain>,
+    // values of the compared lead variables
+    pub lvars: Vec<String>,
+}
+
+// The clonotypes of one enclone run.
+
+#[derive(Default)]
+pub struct Clonotypes {
+    // group_id.clonotype_id, in order of appearance
+    pub names: Vec<String>,
+    pub cells: Vec<Cell>,
+    // for each clonotype, the indices in cells of its cells, in increasing order
+    pub members: Vec<Vec<usize>>,
+    // (dataset, barcode) to index in cells
+    pub index: HashMap<(String, String), usize>,
+    // true if the cells come from more than one dataset
+    pub multi: bool,
+}
+
+impl Cell {
+    fn key(&self) -> (String, String) {
+        (self.dataset.clone(), self.barcode.clone())
+    }
+
+    // The name of the cell in reports.
+
+    fn name(&self, multi: bool) -> String {
+        if multi {
+            format!("{}-{}", self.barcode, self.dataset)
+        } else {
+            self.barcode.clone()
+        }
+    }
+}
+
+impl Clonotypes {
+    // The indices in cells of the cells in some clonotypes, in increasing order.
+
+    fn cells_of(&self, clonotypes: &[usize]) -> Vec<usize> {
+        let mut x = clonotypes
+            .iter()
+            .flat_map(|&i| self.members[i].iter().cloned())
+            .collect::<Vec<_>>();
+        x.sort_unstable();
+        x
+    }
+}
+
+/// Parse the output of enclone, given PCELL, PCOLS=pcols(lvars), POUT=stdout and NOPRINT.
+pub fn parse_clonotypes(out: &str, lvars: &[String]) -> Result<Clonotypes, String> {
+    let mut lines = out.lines().filter(|line| !line.is_empty());
+    let Some(header) = lines.next() else {
+        return Ok(Clonotypes::default());
+    };
+    let header = header.split(',').collect::<Vec<_>>();
+    let pos = |f: &str| {
+        header
+            .iter()
+            .position(|x| *x == f)
+            .ok_or_else(|| format!("\nthe field {} is missing from the enclone output\n", f))
+    };
+    let (bc, ds, gid, cid) = (
+        pos("barcode")?,
+        pos("datasets")?,
+        pos("group_id")?,
+        pos("clonotype_id")?,
+    );
+    let mut chain_pos = Vec::new();
+    for i in 1..=PCHAINS {
+        let (v, j, cdr3) = (
+            pos(&format!("v_name{}", i))?,
+            pos(&format!("j_name{}", i))?,
+            pos(&format!("cdr3_aa{}", i))?,
+        );
+        chain_pos.push((v, j, cdr3));
+    }
+    let lvar_pos = lvars
+        .iter()
+        .map(|x| pos(x))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut c = Clonotypes::default();
+    let mut names = HashMap::<String, usize>::new();
+    for line in lines {
+        let fields = line.split(',').collect::<Vec<_>>();
+        if fields == header {
+            continue;
+        }
+        if fields.len() != header.len() {
+            return Err(format!("\nunexpected line in enclone output:\n{}\n", line));
+        }
+        let name = format!("{}.{}", fields[gid], fields[cid]);
+        let clonotype = *names.entry(name.clone()).or_insert(c.names.len());
+        if clonotype == c.names.len() {
+            c.names.push(name);
+            c.members.push(Vec::new());
+        }
+        c.members[clonotype].push(c.cells.len());
+        let mut chains = chain_pos
+            .iter()
+            .filter(|p| !fields[p.0].is_empty() || !fields[p.2].is_empty())
+            .map(|p| Chain {
+                v: fields[p.0].to_string(),
+                j: fields[p.1].to_string(),
+                cdr3: fields[p.2].to_string(),
+            })
+            .collect::<Vec<_>>();
+        chains.sort();
+        c.index.insert(
+            (fields[ds].to_string(), fields[bc].to_string()),
+            c.cells.len(),
+        );
+        c.multi |= !c.cells.is_empty() && c.cells[0].dataset != fields[ds];
+        c.cells.push(Cell {
+            barcode: fields[bc].to_string(),
+            dataset: fields[ds].to_string(),
+            clonotype,
+            chains,
+            lvars: lvar_pos.iter().map(|&p| fields[p].to_string()).collect(),
+        });
+    }
+    Ok(c)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    Split,
+    Merge,
+    Rearranged,
+    Membership,
+    Calls,
+}
+
+impl DiffKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiffKind::Split => "split",
+            DiffKind::Merge => "merge",
+            DiffKind::Rearranged => "rearranged",
+            DiffKind::Membership => "membership",
+            DiffKind::Calls => "calls",
+        }
+    }
+}
+
+// A difference between the old and new clonotypes, for one connected set of them.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClonotypeDiff {
+    pub kind: DiffKind,
+    // old and new clonotypes, with their number of cells
+    pub old: Vec<(String, usize)>,
+    pub new: Vec<(String, usize)>,
+    // names of the cells only in the old or only in the new clonotypes
+    pub lost: Vec<String>,
+    pub gained: Vec<String>,
+    // cell name, old chains, new chains
+    pub changed_calls: Vec<(String, String, String)>,
+    // cell name, lead variable, old value, new value
+    pub changed_lvars: Vec<(String, String, String, String)>,
+    // all the barcodes in the old and new clonotypes, sorted and without duplicates; BARCODE=
+    // does not name a dataset, so a barcode here selects its cells in every dataset
+    pub barcodes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffSummary {
+    pub old_clonotypes: usize,
+    pub new_clonotypes: usize,
+    pub old_cells: usize,
+    pub new_cells: usize,
+    pub unchanged: usize,
+    pub splits: usize,
+    pub merges: usize,
+    pub rearranged: usize,
+    pub membership: usize,
+    pub calls: usize,
+    pub lost_cells: usize,
+    pub gained_cells: usize,
+    // number of cells in both runs whose V, J or CDR3 calls changed
+    pub changed_v: usize,
+    pub changed_j: usize,
+    pub changed_cdr3: usize,
+    // for each compared lead variable, the number of cells in both runs whose value changed
+    pub changed_lvars: Vec<(String, usize)>,
+}
+
+// One field of the chains, sorted.
+
+fn calls(chains: &[Chain], f: fn(&Chain) -> &String) -> Vec<&String> {
+    let mut x = chains.iter().map(f).collect::<Vec<_>>();
+    x.sort();
+    x
+}
+
+fn chains_string(chains: &[Chain]) -> String {
+    let x = chains
+        .iter()
+        .map(|c| format!("{} {} {}", c.v, c.j, c.cdr3))
+        .collect::<Vec<_>>();
+    if x.is_empty() {
+        "(no chains)".to_string()
+    } else {
+        x.join(" + ")
+    }
+}
+
+/// Compare old and new clonotypes.  Return the differences, in the order of the old clonotypes,
+/// followed by those having only new clonotypes, together with summary statistics.
+pub fn diff_clonotypes(
+    old: &Clonotypes,
+    new: &Clonotypes,
+    lvars: &[String],
+) -> (Vec<ClonotypeDiff>, DiffSummary) {
+    let mut s = DiffSummary {
+        old_clonotypes: old.names.len(),
+        new_clonotypes: new.names.len(),
+        old_cells: old.cells.len(),
+        new_cells: new.cells.len(),
+        changed_lvars: lvars.iter().map(|x| (x.clone(), 0)).collect(),
+        ..Default::default()
+    };
+
+    // Link old clonotype i to new clonotype j, which is node n_old + j, if they share a cell.
+
+    let multi = old.multi || new.multi;
+    let n_old = old.names.len();
+    let mut e = EquivRel::new((n_old + new.names.len()) as i32);
+    for cell in old.cells.iter() {
+        if let Some(&k) = new.index.get(&cell.key()) {
+            e.join(
+                cell.clonotype as i32,
+                (n_old + new.cells[k].clonotype) as i32,
+            );
+        }
+    }
+    let mut reps = Vec::<i32>::new();
+    e.orbit_reps(&mut reps);
+    let mut units = reps
+        .iter()
+        .map(|&r| {
+            let mut o = Vec::<i32>::new();
+            e.orbit(r, &mut o);
+            o.sort_unstable();
+            o.iter().map(|&x| x as usize).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    units.sort();
+
+    // Compare each unit.
+
+    let mut diffs = Vec::new();
+    for unit in units.iter() {
+        let (olds, news): (Vec<usize>, Vec<usize>) = unit.iter().partition(|&&x| x < n_old);
+        let news = news.iter().map(|x| x - n_old).collect::<Vec<_>>();
+        let mut d = ClonotypeDiff {
+            kind: DiffKind::Calls,
+            old: olds
+                .iter()
+                .map(|&i| (old.names[i].clone(), old.members[i].len()))
+                .collect(),
+            new: news
+                .iter()
+                .map(|&j| (new.names[j].clone(), new.members[j].len()))
+                .collect(),
+            lost: Vec::new(),
+            gained: Vec::new(),
+            changed_calls: Vec::new(),
+            changed_lvars: Vec::new(),
+            barcodes: Vec::new(),
+        };
+        for m in old.cells_of(&olds) {
+            let cell = &old.cells[m];
+            d.barcodes.push(cell.barcode.clone());
+            let Some(&k) = new.index.get(&cell.key()) else {
+                d.lost.push(cell.name(multi));
+                continue;
+            };
+            let cell2 = &new.cells[k];
+            if cell.chains != cell2.chains {
+                let changed =
+                    |f: fn(&Chain) -> &String| calls(&cell.chains, f) != calls(&cell2.chains, f);
+                s.changed_v += changed(|c| &c.v) as usize;
+                s.changed_j += changed(|c| &c.j) as usize;
+                s.changed_cdr3 += changed(|c| &c.cdr3) as usize;
+                d.changed_calls.push((
+                    cell.name(multi),
+                    chains_string(&cell.chains),
+                    chains_string(&cell2.chains),
+                ));
+            }
+            for (l, lvar) in lvars.iter().enumerate() {
+                if cell.lvars[l] != cell2.lvars[l] {
+                    s.changed_lvars[l].1 += 1;
+                    d.changed_lvars.push((
+                        cell.name(multi),
+                        lvar.clone(),
+                        cell.lvars[l].clone(),
+                        cell2.lvars[l].clone(),
+                    ));
+                }
+            }
+        }
+        for m in new.cells_of(&news) {
+            let cell = &new.cells[m];
+            if !old.index.contains_key(&cell.key()) {
+                d.barcodes.push(cell.barcode.clone());
+                d.gained.push(cell.name(multi));
+            }
+        }
+        d.barcodes.sort();
+        d.barcodes.dedup();
+        d.kind = match (olds.len(), news.len()) {
+            (1, 1) | (0, _) | (_, 0) if !d.lost.is_empty() || !d.gained.is_empty() => {
+                DiffKind::Membership
+            }
+            (1, 1) => DiffKind::Calls,
+            (1, _) => DiffKind::Split,
+            (_, 1) => DiffKind::Merge,
+            _ => DiffKind::Rearranged,
+        };
+        s.lost_cells += d.lost.len();
+        s.gained_cells += d.gained.len();
+        if d.kind == DiffKind::Calls && d.changed_calls.is_empty() && d.changed_lvars.is_empty() {
+            s.unchanged += 1;
+            continue;
+        }
+        match d.kind {
+            DiffKind::Split => s.splits += 1,
+            DiffKind::Merge => s.merges += 1,
+            DiffKind::Rearranged => s.rearranged += 1,
+            DiffKind::Membership => s.membership += 1,
+            DiffKind::Calls => s.calls += 1,
+        }
+        diffs.push(d);
+    }
+    (diffs, s)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+fn clonotype_list(x: &[(String, usize)]) -> String {
+    if x.is_empty() {
+        return "none".to_string();
+    }
+    x.iter()
+        .map(|(name, n)| format!("{} ({} cells)", name, n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Describe a difference, in a few lines.
+pub fn describe_diff(d: &ClonotypeDiff) -> String {
+    let mut log = String::new();
+    writeln!(
+        log,
+        "{}: old {} → new {}",
+        d.kind.name(),
+        clonotype_list(&d.old),
+        clonotype_list(&d.new)
+    )
+    .unwrap();
+    if !d.lost.is_empty() {
+        writeln!(log, "lost cells: {}", d.lost.join(",")).unwrap();
+    }
+    if !d.gained.is_empty() {
+        writeln!(log, "gained cells: {}", d.gained.join(",")).unwrap();
+    }
+    for (bc, old, new) in d.changed_calls.iter() {
+        writeln!(log, "{}: {} → {}", bc, old, new).unwrap();
+    }
+    for (bc, lvar, old, new) in d.changed_lvars.iter() {
+        writeln!(log, "{}: {}: {} → {}", bc, lvar, old, new).unwrap();
+    }
+    log
+}
+
+fn summary_rows(s: &DiffSummary) -> Vec<(String, String)> {
+    let mut rows = vec![
+        (
+            "clonotypes".to_string(),
+            format!("{} → {}", s.old_clonotypes, s.new_clonotypes),
+        ),
+        (
+            "cells".to_string(),
+            format!("{} → {}", s.old_cells, s.new_cells),
+        ),
+        ("unchanged clonotypes".to_string(), s.unchanged.to_string()),
+        ("splits".to_string(), s.splits.to_string()),
+        ("merges".to_string(), s.merges.to_string()),
+        ("rearrangements".to_string(), s.rearranged.to_string()),
+        ("membership changes".to_string(), s.membership.to_string()),
+        ("call changes only".to_string(), s.calls.to_string()),
+        ("lost cells".to_string(), s.lost_cells.to_string()),
+        ("gained cells".to_string(), s.gained_cells.to_string()),
+        ("cells with changed V".to_string(), s.changed_v.to_string()),
+        ("cells with changed J".to_string(), s.changed_j.to_string()),
+        (
+            "cells with changed CDR3".to_string(),
+            s.changed_cdr3.to_string(),
+        ),
+    ];
+    for (lvar, n) in s.changed_lvars.iter() {
+        rows.push((format!("cells with changed {}", lvar), n.to_string()));
+    }
+    rows
+}
+
+/// Summarize the differences, as a table.
+pub fn describe_summary(s: &DiffSummary) -> String {
+    let rows = summary_rows(s);
+    let width = rows.iter().map(|r| r.0.chars().count()).max().unwrap_or(0);
+    let mut log = String::new();
+    for (name, value) in rows.iter() {
+        writeln!(log, "{:<width$}  {}", name, value, width = width).unwrap();
+    }
+    log
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Make an html report of the differences.
+pub fn html_report(title: &str, diffs: &[ClonotypeDiff], s: &DiffSummary) -> String {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n<title>{}</title>\n\
+         <style>\ntable {{ border-collapse: collapse; }}\n\
+         td, th {{ border: 1px solid #999; padding: 2px 8px; text-align: left; \
+         vertical-align: top; }}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n<h2>summary</h2>\n<table>",
+        escape_html(title),
+        escape_html(title)
+    )
+    .unwrap();
+    for (name, value) in summary_rows(s).iter() {
+        writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", name, value).unwrap();
+    }
+    writeln!(
+        html,
+        "</table>\n<h2>differences</h2>\n<table>\n<tr><th>#</th><th>kind</th><th>old</th>\
+         <th>new</th><th>lost</th><th>gained</th><th>details</th></tr>"
+    )
+    .unwrap();
+    for (i, d) in diffs.iter().enumerate() {
+        let mut details = Vec::<String>::new();
+        for (bc, old, new) in d.changed_calls.iter() {
+            details.push(format!("{}: {} → {}", bc, old, new));
+        }
+        for (bc, lvar, old, new) in d.changed_lvars.iter() {
+            details.push(format!("{}: {}: {} → {}", bc, lvar, old, new));
+        }
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td></tr>",
+            i + 1,
+            d.kind.name(),
+            escape_html(&clonotype_list(&d.old)),
+            escape_html(&clonotype_list(&d.new)),
+            d.lost.len(),
+            d.gained.len(),
+            details
+                .iter()
+                .map(|x| escape_html(x))
+                .collect::<Vec<_>>()
+                .join("<br>")
+        )
+        .unwrap();
+    }
+    writeln!(html, "</table>\n</body>\n</html>").unwrap();
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_clonotypes() {
+        let lvars = vec!["u1".to_string()];
+        let header = pcols(&lvars);
+        let chain = |v: &str, cdr3: &str| format!("{},IGHJ4,{},", v, cdr3);
+        let line = |bc: &str, g: usize, v: &str, u1: usize| {
+            format!(
+                "{},d1,{},1,{}{}{}",
+                bc,
+                g,
+                chain(v, "CARW"),
+                ",,,".repeat(3),
+                u1
+            )
+        };
+        let old_lines = [
+            header.clone(),
+            line("A-1", 1, "IGHV1", 5),
+            line("B-1", 1, "IGHV1", 5),
+            line("C-1", 2, "IGHV2", 5),
+            line("D-1", 2, "IGHV2", 5),
+            line("E-1", 3, "IGHV3", 5),
+            line("F-1", 4, "IGHV4", 5),
+            line("G-1", 5, "IGHV5", 5),
+        ];
+        let new = [
+            header,
+            line("A-1", 1, "IGHV1", 5),
+            line("B-1", 2, "IGHV1", 5),
+            line("C-1", 3, "IGHV2", 5),
+            line("D-1", 3, "IGHV2", 5),
+            line("E-1", 3, "IGHV3", 5),
+            line("F-1", 4, "IGHV9", 6),
+            line("H-1", 5, "IGHV5", 5),
+        ];
+        let old = parse_clonotypes(&old_lines.join("\n"), &lvars).unwrap();
+        let new = parse_clonotypes(&new.join("\n"), &lvars).unwrap();
+        assert_eq!(old.names, vec!["1.1", "2.1", "3.1", "4.1", "5.1"]);
+        assert_eq!(old.cells[0].chains.len(), 1);
+        assert_eq!(
+            old.members,
+            vec![vec![0, 1], vec![2, 3], vec![4], vec![5], vec![6]]
+        );
+        let (diffs, s) = diff_clonotypes(&old, &new, &lvars);
+        let kinds = diffs.iter().map(|d| d.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffKind::Split,
+                DiffKind::Merge,
+                DiffKind::Calls,
+                DiffKind::Membership,
+                DiffKind::Membership
+            ]
+        );
+        assert_eq!(
+            diffs[1].old,
+            vec![("2.1".to_string(), 2), ("3.1".to_string(), 1)]
+        );
+        assert_eq!(diffs[3].lost, vec!["G-1"]);
+        assert_eq!(diffs[4].gained, vec!["H-1"]);
+        assert_eq!(
+            describe_diff(&diffs[2]),
+            "calls: old 4.1 (1 cells) → new 4.1 (1 cells)\n\
+             F-1: IGHV4 IGHJ4 CARW → IGHV9 IGHJ4 CARW\n\
+             F-1: u1: 5 → 6\n"
+        );
+        assert_eq!((s.splits, s.merges, s.calls, s.membership), (1, 1, 1, 2));
+        assert_eq!((s.changed_v, s.changed_j, s.changed_cdr3), (1, 0, 0));
+        assert_eq!(s.changed_lvars, vec![("u1".to_string(), 1)]);
+        assert_eq!((s.lost_cells, s.gained_cells, s.unchanged), (1, 1, 0));
+        assert!(describe_summary(&s).contains("splits                   1\n"));
+        assert!(html_report("x", &diffs, &s).contains("<td>merge</td>"));
+
+        // The same barcode in two datasets is two cells.
+
+        let old = [
+            old_lines[0].clone(),
+            old_lines[1].clone(),
+            old_lines[1].replace(",d1,1,", ",d2,1,"),
+        ];
+        let new = [
+            old_lines[0].clone(),
+            old_lines[1].clone(),
+            old_lines[1].replace(",d1,1,", ",d2,2,"),
+        ];
+        let old = parse_clonotypes(&old.join("\n"), &lvars).unwrap();
+        let new = parse_clonotypes(&new.join("\n"), &lvars).unwrap();
+        assert!(old.multi);
+        assert_eq!((old.cells.len(), old.index.len()), (2, 2));
+        let (diffs, s) = diff_clonotypes(&old, &new, &lvars);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].kind, DiffKind::Split);
+        assert_eq!(diffs[0].barcodes, vec!["A-1"]);
+        assert_eq!((s.lost_cells, s.gained_cells), (0, 0));
+    }
+}
@@ -0,0 +1,516 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Describe how the output of a test differs from its golden file, in a way that makes reviewing
+// the change tractable.
+//
+// 1. Parseable output (POUT=stdout or POUT=stdouth) is compared row by row, where rows are
+// matched by clonotype and barcode (or position), and the changes are reported per column.
+// 2. The remaining text, which is mostly clonotype tables, has its ANSI escapes stripped and is
+// compared line by line, in the style of diff -u.  Each hunk is labeled by the clonotype it is
+// in, and changed lines are shown with their fields aligned and the changed fields marked.
+
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const CONTEXT: usize = 3;
+const MAX_LINES: usize = 200;
+// beyond this, don't look for common lines in the middle of a line diff
+const MAX_CELLS: usize = 4_000_000;
+
+/// Describe how new output differs from old output, given the arguments to enclone that were
+/// used to generate the new output.
+pub fn semantic_diff(old: &str, new: &str, args: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    let (old_plain, new_plain) = (strip_ansi(old), strip_ansi(new));
+    if old_plain == new_plain {
+        let mut log = String::new();
+        writeln!(
+            log,
+            "the outputs differ only in ANSI escape sequences, i.e. in colors or bold; \
+             here the escape character is shown as ESC:\n"
+        )
+        .unwrap();
+        log += &line_diff(&old.replace('\x1b', "ESC"), &new.replace('\x1b', "ESC"));
+        return log;
+    }
+    let pout = args.split_whitespace().find_map(|arg| match arg {
+        "POUT=stdout" => Some(true),
+        "POUT=stdouth" => Some(false),
+        _ => None,
+    });
+    let Some(csv) = pout else {
+        return line_diff(&old_plain, &new_plain);
+    };
+    let (old_rows, old_rest) = parse_pout(&old_plain, csv);
+    let (new_rows, new_rest) = parse_pout(&new_plain, csv);
+    let mut log = pout_diff(&old_rows, &new_rows);
+    if old_rest != new_rest {
+        if !log.is_empty() {
+            log += "\nother output:\n";
+        }
+        log += &line_diff(&old_rest, &new_rest);
+    }
+    log
+}
+
+/// Remove ANSI escape sequences.
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+        } else if chars.peek() == Some(&'[') {
+            chars.next();
+            for d in chars.by_ref() {
+                if ('@'..='~').contains(&d) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Parseable output.
+
+#[derive(Debug, PartialEq, Eq)]
+struct PoutRow {
+    // clonotype, e.g. "[1.2]", or empty if clonotypes are not printed
+    clonotype: String,
+    // what identifies the row within the clonotype: its barcode if there is one, and otherwise
+    // its position
+    key: String,
+    header: Vec<String>,
+    fields: Vec<String>,
+}
+
+fn split_fields(line: &str, csv: bool) -> Vec<String> {
+    if !csv {
+        return line.split_whitespace().map(str::to_string).collect();
+    }
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+// A header line of parseable output is a list of variable names.
+
+fn is_header(fields: &[String]) -> bool {
+    fields.iter().all(|f| {
+        !f.is_empty()
+            && f.chars()
+                .all(|c| c.is_alphanumeric() || "_%:.-".contains(c))
+    })
+}
+
+// Return the rows of parseable output, and the rest of the output.
+
+fn parse_pout(text: &str, csv: bool) -> (Vec<PoutRow>, String) {
+    let lines: Vec<&str> = text.lines().collect();
+    let (mut rows, mut rest) = (Vec::<PoutRow>::new(), String::new());
+    let mut clonotype = String::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with('[') && line.contains("] CLONOTYPE") {
+            clonotype = line[..=line.find(']').unwrap()].to_string();
+        }
+        let header = split_fields(line, csv);
+        let mut j = i + 1;
+        if is_header(&header) {
+            while j < lines.len()
+                && !lines[j].is_empty()
+                && split_fields(lines[j], csv).len() == header.len()
+            {
+                j += 1;
+            }
+        }
+        if j == i + 1 {
+            rest += line;
+            rest.push('\n');
+            i += 1;
+            continue;
+        }
+        let bc = header.iter().position(|h| h == "barcode");
+        for (k, line) in lines[i + 1..j].iter().enumerate() {
+            let fields = split_fields(line, csv);
+            let key = match bc {
+                Some(b) => fields[b].clone(),
+                None => format!("row {}", k + 1),
+            };
+            rows.push(PoutRow {
+                clonotype: clonotype.clone(),
+                key,
+                header: header.clone(),
+                fields,
+            });
+        }
+        i = j;
+    }
+    (rows, rest)
+}
+
+fn row_label(row: &PoutRow) -> String {
+    if row.clonotype.is_empty() {
+        row.key.clone()
+    } else {
+        format!("{} {}", row.clonotype, row.key)
+    }
+}
+
+fn pout_diff(old: &[PoutRow], new: &[PoutRow]) -> String {
+    let mut log = String::new();
+    let (mut changed, mut removed, mut added) = (0, 0, 0);
+    let mut lines = Vec::<String>::new();
+    let index = |rows: &[PoutRow]| -> HashMap<(String, String), usize> {
+        rows.iter()
+            .enumerate()
+            .map(|(i, r)| ((r.clonotype.clone(), r.key.clone()), i))
+            .collect()
+    };
+    let (old_index, new_index) = (index(old), index(new));
+
+    // Report the columns that were removed or added.
+
+    let columns = |rows: &[PoutRow]| -> Vec<String> {
+        let mut cols = Vec::<String>::new();
+        for h in rows.iter().flat_map(|r| r.header.iter()) {
+            if !cols.contains(h) {
+                cols.push(h.clone());
+            }
+        }
+        cols
+    };
+    let (old_cols, new_cols) = (columns(old), columns(new));
+    let missing = |x: &[String], y: &[String]| -> Vec<String> {
+        x.iter().filter(|c| !y.contains(c)).cloned().collect()
+    };
+    let (gone, came) = (missing(&old_cols, &new_cols), missing(&new_cols, &old_cols));
+    if !gone.is_empty() {
+        lines.push(format!("columns removed: {}", gone.join(", ")));
+    }
+    if !came.is_empty() {
+        lines.push(format!("columns added: {}", came.join(", ")));
+    }
+
+    // Report the rows that changed or were removed, in the old order, and then the rows that
+    // were added, in the new order.
+
+    let value = |r: &PoutRow, col: &str| -> Option<String> {
+        r.header
+            .iter()
+            .position(|h| h == col)
+            .map(|p| r.fields[p].clone())
+    };
+    for r in old.iter() {
+        match new_index.get(&(r.clonotype.clone(), r.key.clone())) {
+            None => {
+                removed += 1;
+                lines.push(format!("{}: removed: {}", row_label(r), r.fields.join(",")));
+            }
+            Some(&n) => {
+                let s = &new[n];
+                let mut diffs = Vec::<String>::new();
+                for col in old_cols.iter().filter(|c| new_cols.contains(c)) {
+                    let (x, y) = (value(r, col), value(s, col));
+                    if x != y {
+                        diffs.push(format!(
+                            "{col}: {} → {}",
+                            x.unwrap_or_default(),
+                            y.unwrap_or_default()
+                        ));
+                    }
+                }
+                if !diffs.is_empty() {
+                    changed += 1;
+                    lines.push(format!("{}: {}", row_label(r), diffs.join("; ")));
+                }
+            }
+        }
+    }
+    for s in new.iter() {
+        if !old_index.contains_key(&(s.clonotype.clone(), s.key.clone())) {
+            added += 1;
+            lines.push(format!("{}: added: {}", row_label(s), s.fields.join(",")));
+        }
+    }
+    if lines.is_empty() {
+        return log;
+    }
+    writeln!(
+        log,
+        "parseable output: {} of {} rows changed, {} removed and {} added",
+        changed,
+        old.len(),
+        removed,
+        added
+    )
+    .unwrap();
+    for (i, line) in lines.iter().enumerate() {
+        if i == MAX_LINES {
+            writeln!(log, "... ({} more)", lines.len() - i).unwrap();
+            break;
+        }
+        writeln!(log, "{line}").unwrap();
+    }
+    log
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+// Line diff.
+
+// Find an edit script turning a into b, as a list of lines, each marked as common (' '), old
+// ('-') or new ('+').  First strip common lines at the beginning and end, then find a longest
+// common subsequence of the rest.
+
+fn edit_script<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<(char, &'a str)> {
+    let mut pre = 0;
+    while pre < min(a.len(), b.len()) && a[pre] == b[pre] {
+        pre += 1;
+    }
+    let mut suf = 0;
+    while suf < min(a.len(), b.len()) - pre && a[a.len() - 1 - suf] == b[b.len() - 1 - suf] {
+        suf += 1;
+    }
+    let (am, bm) = (&a[pre..a.len() - suf], &b[pre..b.len() - suf]);
+    let mut ops: Vec<(char, &str)> = a[..pre].iter().map(|x| (' ', *x)).collect();
+    let (n, m) = (am.len(), bm.len());
+    if n * m <= MAX_CELLS {
+        let mut lcs = vec![vec![0_u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if am[i] == bm[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    max(lcs[i + 1][j], lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && am[i] == bm[j] {
+                ops.push((' ', am[i]));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push(('-', am[i]));
+                i += 1;
+            } else {
+                ops.push(('+', bm[j]));
+                j += 1;
+            }
+        }
+    } else {
+        ops.extend(am.iter().map(|x| ('-', *x)));
+        ops.extend(bm.iter().map(|x| ('+', *x)));
+    }
+    ops.extend(a[a.len() - suf..].iter().map(|x| (' ', *x)));
+    ops
+}
+
+// Show an old line and the new line that replaced it, with the changed fields marked, if they
+// have the same number of fields.  If the fields are at different positions, they are aligned.
+
+fn aligned_pair(old: &str, new: &str) -> Option<[String; 3]> {
+    let fields = |s: &str| -> Vec<(usize, String)> {
+        let mut fields = Vec::<(usize, String)>::new();
+        for (i, c) in s.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+            match fields.last_mut() {
+                Some((start, f)) if *start + f.chars().count() == i => f.push(c),
+                _ => fields.push((i, c.to_string())),
+            }
+        }
+        fields
+    };
+    let (x, y) = (fields(old), fields(new));
+    if x.len() != y.len() || x.len() <= 1 {
+        return None;
+    }
+    let mut out = [format!("-{old}"), format!("+{new}"), String::from(" ")];
+    let aligned = x.iter().zip(y.iter()).all(|(a, b)| a.0 == b.0);
+    if !aligned {
+        out = [String::from("-"), String::from("+"), String::from(" ")];
+    }
+    for (a, b) in x.iter().zip(y.iter()) {
+        let width = max(a.1.chars().count(), b.1.chars().count());
+        if !aligned {
+            if out[0].len() > 1 {
+                for s in out.iter_mut() {
+                    s.push(' ');
+                }
+            }
+            write!(out[0], "{:width$}", a.1).unwrap();
+            write!(out[1], "{:width$}", b.1).unwrap();
+        } else {
+            let pad = b.0 + 1 - out[2].chars().count();
+            out[2] += &" ".repeat(pad);
+        }
+        let mark = if a.1 == b.1 { " " } else { "^" };
+        out[2] += &mark.repeat(width);
+    }
+    out[2] = out[2].trim_end().to_string();
+    Some(out)
+}
+
+/// Show how new output differs from old output, in the style of diff -u, i.e. as hunks of
+/// changed lines, prefixed by - for old lines and + for new lines, with three lines of context.
+/// Each hunk is labeled by the last clonotype or group line before it.  Long diffs are truncated.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let (a, b): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+    let ops = edit_script(&a, &b);
+    let mut log = String::new();
+    let changed: Vec<usize> = (0..ops.len()).filter(|&i| ops[i].0 != ' ').collect();
+    if changed.is_empty() {
+        writeln!(log, "the outputs differ only in line endings").unwrap();
+        return log;
+    }
+    let (mut olds, mut news) = (Vec::<usize>::new(), Vec::<usize>::new());
+    let (mut o, mut p) = (0, 0);
+    for op in ops.iter() {
+        olds.push(o);
+        news.push(p);
+        if op.0 != '+' {
+            o += 1;
+        }
+        if op.0 != '-' {
+            p += 1;
+        }
+    }
+    let mut shown = 0;
+    let mut k = 0;
+    while k < changed.len() {
+        let start = changed[k].saturating_sub(CONTEXT);
+        let mut last = changed[k];
+        k += 1;
+        while k < changed.len() && changed[k] <= last + 2 * CONTEXT {
+            last = changed[k];
+            k += 1;
+        }
+        let stop = min(last + CONTEXT + 1, ops.len());
+        let hunk = &ops[start..stop];
+        let label = ops[..start]
+            .iter()
+            .rev()
+            .find(|(c, line)| *c != '+' && line.starts_with('[') && line.contains("] "))
+            .map(|(_, line)| format!(" {}", line.trim()))
+            .unwrap_or_default();
+        writeln!(
+            log,
+            "@@ -{},{} +{},{} @@{}",
+            olds[start] + 1,
+            hunk.iter().filter(|x| x.0 != '+').count(),
+            news[start] + 1,
+            hunk.iter().filter(|x| x.0 != '-').count(),
+            label
+        )
+        .unwrap();
+
+        // Print the hunk, aligning runs of old lines that are followed by the same number of new
+        // lines.
+
+        let mut i = 0;
+        while i < hunk.len() {
+            let mut lines = Vec::<String>::new();
+            let r = (i..hunk.len())
+                .find(|&j| hunk[j].0 != '-')
+                .unwrap_or(hunk.len());
+            let s = (r..hunk.len())
+                .find(|&j| hunk[j].0 != '+')
+                .unwrap_or(hunk.len());
+            if r > i && s - r == r - i {
+                let pairs: Vec<_> = (0..r - i)
+                    .map(|d| aligned_pair(hunk[i + d].1, hunk[r + d].1))
+                    .collect();
+                if pairs.iter().all(Option::is_some) {
+                    for pair in pairs.into_iter().flatten() {
+                        lines.extend(pair);
+                    }
+                    i = s;
+                }
+            }
+            if lines.is_empty() {
+                lines.push(format!("{}{}", hunk[i].0, hunk[i].1));
+                i += 1;
+            }
+            for line in lines {
+                if shown == MAX_LINES {
+                    writeln!(log, "... (diff truncated)").unwrap();
+                    return log;
+                }
+                writeln!(log, "{line}").unwrap();
+                shown += 1;
+            }
+        }
+    }
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_diff() {
+        assert_eq!(
+            strip_ansi("\x1b[1m\x1b[38;5;27m[1] GROUP\x1b[0m"),
+            "[1] GROUP"
+        );
+
+        // Text tables.
+
+        let old = "[1.1] CLONOTYPE = 2 CELLS\n│1  2 │ CAR\n│2  1 │ CAK\nx\ny\nz\nw\n";
+        let new = old.replace("│2  1 │ CAK", "│2  \x1b[1m3\x1b[0m │ CAK");
+        let diff = semantic_diff(old, &new, "BCR=123085");
+        assert_eq!(
+            diff,
+            "@@ -1,6 +1,6 @@\n \
+             [1.1] CLONOTYPE = 2 CELLS\n \
+             │1  2 │ CAR\n\
+             -│2  1 │ CAK\n\
+             +│2  3 │ CAK\n     \
+             ^\n \
+             x\n \
+             y\n \
+             z\n"
+        );
+        let diff = semantic_diff("a\n", "\x1b[1ma\x1b[0m\n", "");
+        assert!(diff.starts_with("the outputs differ only in ANSI"));
+
+        // Parseable output.
+
+        let old = "[1.1] CLONOTYPE = 2 CELLS\nbarcode,u1,r2\nA-1,3,4\nC-1,5,6\n\
+                   [2.1] CLONOTYPE = 1 CELLS\nbarcode,u1,r2\nG-1,1,2\n";
+        let new = "[1.1] CLONOTYPE = 2 CELLS\nbarcode,u1,r2\nA-1,3,4\nC-1,5,7\nT-1,0,0\n\
+                   [2.1] CLONOTYPE = 1 CELLS\nbarcode,u1,r2\nG-1,1,2\n";
+        assert_eq!(
+            semantic_diff(old, new, "POUT=stdout"),
+            "parseable output: 1 of 3 rows changed, 0 removed and 1 added\n\
+             [1.1] C-1: r2: 6 → 7\n\
+             [1.1] T-1: added: T-1,0,0\n"
+        );
+        let old = "cdr3_aa1  n\nCARW      2\n";
+        let new = "cdr3_aa1  n  u1\nCARW      2  7\n";
+        assert_eq!(
+            semantic_diff(old, new, "NOPRINT POUT=stdouth"),
+            "parseable output: 0 of 1 rows changed, 0 removed and 0 added\n\
+             columns added: u1\n"
+        );
+    }
+}
@@ -3,9 +3,12 @@
 use serde::{Deserialize, Serialize};
 use vdj_ann::annotate::ContigAnnotation;
 
+pub mod clonotype_diff;
+pub mod golden_diff;
 pub mod html;
 pub mod pdb;
 pub mod run_test;
+pub mod seq_annotation;
 
 /// Add an optional dataset string to a ContigAnnotation.
 #[derive(Serialize, Deserialize)]
@@ -1,33 +1,62 @@
 // Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
 
+use crate::golden_diff::semantic_diff;
 use enclone_core::parse_bsv;
+use enclone_testlist::test_spec::{Expect, TestSpec};
 use enclone_testlist::TEST_FILES_VERSION;
 use io_utils::{fwrite, fwriteln, path_exists};
 use rayon::prelude::*;
-use std::cmp::min;
+use std::env;
 use std::fmt::Write;
-use std::fs::read_to_string;
+use std::fs::{create_dir_all, read_to_string, write};
 use std::process::Command;
-use string_utils::strme;
+use string_utils::{strme, TextUtils};
 
-/// A test case is declared with a fixed test index, a comment, and the code to run.
-pub type TestCase<'a> = (usize, &'a str, &'a str);
-
-/// Run a collection of test cases in parallel.
-/// TODO: replace this by declaring test cases individually.
-pub fn run_tests(enclone: &str, test_name: &str, max_cores: usize, tests: &[TestCase]) {
+/// Run a collection of test cases in parallel.  The main tests are instead declared
+/// individually, see enclone_testlist/src/main_testlist.rs, and run by run_declared_test.
+pub fn run_tests(enclone: &str, max_cores: usize, tests: &[TestSpec]) {
     println!("running tests using {enclone}");
-    let failures: Vec<_> = tests
+    let results: Vec<_> = tests
         .par_iter()
-        .map(|(number, comments, args)| {
-            run_test(enclone, test_name, max_cores, *number, comments, args)
-        })
-        .filter_map(Result::err)
+        .map(|spec| run_spec(enclone, max_cores, spec))
         .collect();
-    for f in &failures {
-        print!("{}", f.log);
+    for r in &results {
+        match r {
+            Ok(res) | Err(res) => print!("{}", res.log),
+        }
+    }
+    assert!(results.iter().all(Result::is_ok));
+}
+
+/// Run a declared test, as the body of its #[test].  The test is skipped if it is not selected
+/// by the environment variable ENCLONE_TEST_TAGS, see spec_selected.
+pub fn run_declared_test(enclone: &str, spec: &TestSpec) {
+    let tags = env::var("ENCLONE_TEST_TAGS").ok();
+    if !spec_selected(spec, tags.as_deref()) {
+        println!(
+            "skipping enclone_{}{} because it does not have any of the tags {}",
+            spec.category,
+            spec.num,
+            tags.unwrap()
+        );
+        return;
+    }
+    match run_spec(enclone, 0, spec) {
+        Ok(res) => print!("{}", res.log),
+        Err(res) => {
+            print!("{}", res.log);
+            panic!("enclone_{}{} failed", spec.category, spec.num);
+        }
+    }
+}
+
+/// Determine if a test is selected by a comma-separated list of tags, which selects the tests
+/// having at least one of them.  No list selects all tests.
+pub fn spec_selected(spec: &TestSpec, tags: Option<&str>) -> bool {
+    match tags {
+        None => true,
+        Some(tags) => tags.split(',').any(|tag| spec.tags.contains(&tag.trim())),
     }
-    assert!(failures.is_empty());
 }
 
 #[derive(Default)]
@@ -39,50 +68,23 @@ pub struct TestResult {
 }
 
 /// Run a single test case.
-pub fn run_test(
+///
+/// If the environment variable UPDATE_GOLDEN is set to 1, a test whose output differs from its
+/// golden file, or that has no golden file, instead rewrites the golden file, unless its output
+/// is set in stone or it wrote to stderr.
+pub fn run_spec(
     enclone: &str,    // name of the enclone executable
-    name: &str,       // test category e.g. "test" or "ext_test"
     max_cores: usize, // max cores, or if 0, default value determined here
-    num: usize,       // test number
-    comments: &str,   // info about the test
-    test: &str,       // arguments for the test
+    spec: &TestSpec,
 ) -> Result<TestResult, TestResult> {
-    let mut test = test.replace('\n', "");
-    let mut expect_null = false;
-    let mut expect_fail = false;
-    let mut expect_ok = false;
-    let mut set_in_stone = false;
-    let mut no_pre = false;
-    let mut ncores = false;
-    if test.contains(" EXPECT_NULL") {
-        test = test.replace(" EXPECT_NULL", "");
-        expect_null = true;
-    }
-    if test.contains(" EXPECT_FAIL") {
-        test = test.replace(" EXPECT_FAIL", "");
-        expect_fail = true;
-    }
-    if test.contains(" EXPECT_OK") {
-        test = test.replace(" EXPECT_OK", "");
-        expect_ok = true;
-    }
-    if test.contains(" SET_IN_STONE") {
-        test = test.replace(" SET_IN_STONE", "");
-        set_in_stone = true;
-    }
-    if test.contains(" NO_PRE") {
-        test = test.replace(" NO_PRE", "");
-        no_pre = true;
-    }
-    if test.contains(" NCORES") {
-        test = test.replace(" NCORES", "");
-        ncores = true;
-    }
-    test = test.replace("{TEST_FILES_VERSION}", &format!("{}", TEST_FILES_VERSION));
-    while test.contains("  ") {
-        test = test.replace("  ", " ");
-    }
-    let out_file = format!("testx/inputs/outputs/enclone_{}{}_output", name, num);
+    let (name, num, comments) = (spec.category, spec.num, spec.comments);
+    let expect_null = spec.expect == Expect::Null;
+    let expect_fail = spec.expect == Expect::Fail;
+    let expect_ok = spec.expect == Expect::Ok;
+    let (set_in_stone, no_pre, ncores) = (spec.set_in_stone, spec.no_pre, spec.ncores);
+    let test = spec.command(TEST_FILES_VERSION);
+    let out_file = spec.golden_file();
+    let update = env::var("UPDATE_GOLDEN").as_deref() == Ok("1");
     let mut pre_arg = format!(
         "PRE=../enclone-data/big_inputs/version{}",
         TEST_FILES_VERSION
@@ -96,7 +98,30 @@ pub fn run_test(
         local_pre_arg = String::new();
     }
 
-    if !path_exists(&out_file) && !expect_fail && !expect_ok {
+    // Check that the datasets required by the test are present, as otherwise the test would
+    // fail in a confusing way.
+
+    if !no_pre && !test.contains("PRE=") {
+        let pre = pre_arg.after("PRE=");
+        let missing: Vec<_> = spec
+            .datasets()
+            .into_iter()
+            .filter(|id| !path_exists(&format!("{pre}/{id}")))
+            .collect();
+        if !missing.is_empty() {
+            return Err(TestResult {
+                log: format!(
+                    "\nSubtest {num} requires the datasets {}, which are not in {pre}.\n\
+                     Running it requires the extended test data, which is distributed with \
+                     enclone.\n",
+                    missing.join(","),
+                ),
+                ..Default::default()
+            });
+        }
+    }
+
+    if !path_exists(&out_file) && !expect_fail && !expect_ok && !update {
         return Err(TestResult {
             log: format!(
                 "\nYou need to create the output file {out_file}.\n\
@@ -104,15 +129,15 @@ pub fn run_test(
                  the top level of the enclone repo:\n\n\
                  enclone {local_pre_arg} {test} > enclone_exec/testx/inputs/outputs/enclone_{name}{num}_output; \
                  git add enclone_exec/testx/inputs/outputs/enclone_{name}{num}_output\n\n\
-                 If you just added a test to the TESTS group in testlist.rs, it would have been \
+                 If you just added a test to main_testlist.rs, it would have been \
                  faster if you had\nsimply typed run_last_test to get this information.  But you first \
-                 need to run ./build.",
+                 need to run ./build.  Or run the test with UPDATE_GOLDEN=1.",
             ),
             ..Default::default()
         });
     }
     let mut old = String::new();
-    if !expect_fail && !expect_ok {
+    if !expect_fail && !expect_ok && path_exists(&out_file) {
         old = read_to_string(&out_file).unwrap();
     }
     let args = parse_bsv(&test);
@@ -192,7 +217,7 @@ pub fn run_test(
     }
 
     // Process tests that yield the expected stdout.
-    if old == res.stdout {
+    if old == res.stdout && path_exists(&out_file) {
         if old.len() <= 1 && !expect_null {
             fwriteln!(
                 &mut res.log,
@@ -209,7 +234,20 @@ pub fn run_test(
         return Ok(res);
     }
 
-    // Process tests that yield unexpected stdout.
+    // Process tests that yield unexpected stdout.  In update mode, rewrite the golden file, and
+    // show how it changed.
+    if update && !set_in_stone && new.stderr.is_empty() {
+        if let Err(e) = write(&out_file, &res.stdout) {
+            res.log = format!("\nSubtest {num}: could not write {out_file}: {e}\n");
+            return Err(res);
+        }
+        fwriteln!(
+            &mut res.log,
+            "\nSubtest {num}: updated enclone_exec/{out_file}:\n"
+        );
+        fwrite!(&mut res.log, "{}", semantic_diff(&old, &res.stdout, &test));
+        return Ok(res);
+    }
     fwriteln!(&mut res.log, "\nSubtest {}: old and new differ", num);
     fwriteln!(
         &mut res.log,
@@ -217,64 +255,24 @@ pub fn run_test(
         old.len(),
         res.stdout.len()
     );
-    let oldc: Vec<_> = old.chars().collect();
-    let newc: Vec<_> = res.stdout.chars().collect();
     fwriteln!(
         &mut res.log,
-        "old has char length {} and new has char length {}",
-        oldc.len(),
-        newc.len()
+        "old has {} lines and new has {} lines; here is how they differ:\n",
+        old.lines().count(),
+        res.stdout.lines().count()
     );
-    for i in 0..min(oldc.len(), newc.len()) {
-        if oldc[i] != newc[i] {
-            fwriteln!(
-                &mut res.log,
-                "the first difference is at character {}: old = \"{}\", \
-                         new = \"{}\"\n",
-                i,
-                oldc[i],
-                newc[i]
-            );
-            fwriteln!(
-                &mut res.log,
-                "Note that this can be confusing if the differing character is in an \
-                            ANSI escape sequence.\n"
-            );
-            let mut jold = i;
-            while jold > 0 && oldc[jold] != '\n' {
-                jold -= 1;
-            }
-            let mut jnew = i;
-            while jnew > 0 && newc[jnew] != '\n' {
-                jnew -= 1;
-            }
-            let mut kold = i + 1;
-            while kold < oldc.len() && oldc[kold] != '\n' {
-                kold += 1;
-            }
-            let mut knew = i + 1;
-            while knew < newc.len() && newc[knew] != '\n' {
-                knew += 1;
-            }
-            let mut old_line = String::new();
-            for z in jold + 1..kold {
-                old_line.push(oldc[z]);
-            }
-            let mut new_line = String::new();
-            for z in jnew + 1..knew {
-                new_line.push(newc[z]);
-            }
-            fwriteln!(
-                &mut res.log,
-                "first differing line:\nold = {}\nnew = {}\n",
-                old_line,
-                new_line,
-            );
-            break;
-        }
+    fwrite!(&mut res.log, "{}", semantic_diff(&old, &res.stdout, &test));
+
+    // Save the new output, so that it can be examined, or copied to the golden file.
+
+    let saved = format!("testx/outputs/enclone_{name}{num}_output");
+    let saved = create_dir_all("testx/outputs")
+        .and_then(|_| write(&saved, &res.stdout))
+        .is_ok()
+        .then_some(saved);
+    if let Some(saved) = &saved {
+        fwriteln!(&mut res.log, "\nThe new output is in enclone_exec/{saved}.");
     }
-    fwrite!(&mut res.log, "old:\n{}", old);
-    fwrite!(&mut res.log, "new:\n{}", res.stdout);
     let new_err = String::from_utf8_lossy(&new.stderr).to_string();
     if !new_err.is_empty() {
         let lines = new_err.chars().filter(|c| *c == '\n').count() + 1;
@@ -304,7 +302,8 @@ pub fn run_test(
         fwriteln!(
             &mut res.log,
             "🔴 However, the output of this test was not supposed to have changed.\n\
-                     🔴 Please be extremely careful if you change it.\n",
+                     🔴 Please be extremely careful if you change it.  For this reason, \
+                     UPDATE_GOLDEN=1 does not change it.\n",
         );
     }
     fwriteln!(
@@ -316,11 +315,32 @@ pub fn run_test(
         name,
         num
     );
+    if let Some(saved) = &saved {
+        fwriteln!(
+            &mut res.log,
+            "or equivalently\n\ncp enclone_exec/{saved} enclone_exec/{out_file}\n"
+        );
+    }
     fwrite!(&mut res.log, "and then committing the changed file.  ");
-    fwriteln!(
-        &mut res.log,
-        "You can then retest using:\n\n\
-                 cargo test -p enclone enclone  -- --nocapture"
-    );
+    if !set_in_stone {
+        fwrite!(
+            &mut res.log,
+            "Alternatively, rerun the tests with UPDATE_GOLDEN=1, which rewrites all the \
+             changed outputs.  "
+        );
+    }
+    if name == "test" {
+        fwriteln!(
+            &mut res.log,
+            "You can then retest using:\n\n\
+                     cargo test -p enclone test_enclone_{num} -- --exact --nocapture"
+        );
+    } else {
+        fwriteln!(
+            &mut res.log,
+            "You can then retest using:\n\n\
+                     cargo test -p enclone enclone  -- --nocapture"
+        );
+    }
     Err(res)
 }
@@ -0,0 +1,271 @@
+// Copyright (c) 2021 10X Genomics, Inc. All rights reserved.
+
+// Shared code for the sequence annotation tools annotate_seq and annotate_service.
+
+use debruijn::dna_string::DnaString;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use string_utils::strme;
+use vdj_ann::annotate::{annotate_seq, get_cdr3_using_ann};
+use vdj_ann::refx::RefData;
+use vdj_ann::transcript::is_productive_contig;
+
+/// Return the decompressed supp regions data for human.
+pub fn human_supp_regions() -> String {
+    decompress(include_bytes!("../../supp_refs/human/supp_regions.fa.gz")).unwrap()
+}
+
+/// Return the decompressed supp regions data for mouse.
+pub fn mouse_supp_regions() -> String {
+    decompress(include_bytes!("../../supp_refs/mouse/supp_regions.fa.gz")).unwrap()
+}
+
+fn decompress(data: &[u8]) -> std::io::Result<String> {
+    let mut reader = GzDecoder::new(data);
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+// ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓
+
+/// A request to annotate one sequence.  The id is echoed back in the response.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SeqRequest {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub seq: String,
+    #[serde(default)]
+    pub rc: bool,
+}
+
+/// One alignment of a sequence to a reference segment.  Coordinates are zero-based.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SeqAlignment {
+    pub ref_id: i32,
+    pub ref_name: String,
+    pub region: String, // U, V, D, J or C
+    pub tig_start: usize,
+    pub ref_start: usize,
+    pub match_len: usize,
+    pub mismatches: usize,
+}
+
+/// The annotation of one sequence.  If the request could not be processed, only id and error
+/// are set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SeqAnnotation {
+    pub id: Option<String>,
+    pub len: usize,
+    pub v_gene: Option<String>,
+    pub d_gene: Option<String>,
+    pub j_gene: Option<String>,
+    pub c_gene: Option<String>,
+    pub cdr3_aa: Option<String>,
+    pub cdr3_nt: Option<String>,
+    pub cdr3_start: Option<usize>,
+    pub productive: bool,
+    pub alignments: Vec<SeqAlignment>,
+    pub error: Option<String>,
+}
+
+/// Parse one input line of annotate_service into a list of requests.  The line is a bare
+/// sequence, a JSON object, or a JSON array of objects.
+pub fn parse_requests(line: &str) -> Result<Vec<SeqRequest>, String> {
+    let line = line.trim();
+    if line.starts_with('[') {
+        serde_json::from_str::<Vec<SeqRequest>>(line).map_err(|e| e.to_string())
+    } else if line.starts_with('{') {
+        Ok(vec![
+            serde_json::from_str::<SeqRequest>(line).map_err(|e| e.to_string())?
+        ])
+    } else {
+        Ok(vec![SeqRequest {
+            seq: line.to_string(),
+            ..Default::default()
+        }])
+    }
+}
+
+/// The responses for an input line that parse_requests rejected, with the given error.  If the
+/// line is valid JSON, the ids it gives are echoed: for an array, there is one response for each
+/// element, and otherwise there is one response.
+pub fn parse_error_responses(line: &str, e: &str) -> Vec<SeqAnnotation> {
+    let response = |x: Option<&serde_json::Value>| SeqAnnotation {
+        id: x
+            .and_then(|x| x.get("id"))
+            .and_then(|id| id.as_str())
+            .map(str::to_string),
+        error: Some(format!("unable to parse request: {}", e)),
+        ..Default::default()
+    };
+    match serde_json::from_str::<serde_json::Value>(line.trim()) {
+        Ok(serde_json::Value::Array(x)) if !x.is_empty() => {
+            x.iter().map(|x| response(Some(x))).collect()
+        }
+        Ok(x) => vec![response(Some(&x))],
+        Err(_) => vec![response(None)],
+    }
+}
+
+fn region_type(refdata: &RefData, t: usize) -> &'static str {
+    if refdata.is_v(t) {
+        "V"
+    } else if refdata.is_d(t) {
+        "D"
+    } else if refdata.is_j(t) {
+        "J"
+    } else if refdata.is_c(t) {
+        "C"
+    } else {
+        "U"
+    }
+}
+
+/// Annotate a sequence, as in annotate_seq, but returning a structured result.  The gene calls
+/// are the first alignment of each type along the sequence.  The CDR3 fields are set only if a
+/// unique CDR3 is found.
+pub fn annotate_request(req: &SeqRequest, refdata: &RefData) -> SeqAnnotation {
+    let mut res = SeqAnnotation {
+        id: req.id.clone(),
+        ..Default::default()
+    };
+    let bases = req.seq.trim().to_ascii_uppercase();
+    if bases.is_empty() || !bases.bytes().all(|c| b"ACGT".contains(&c)) {
+        res.error = Some("sequence must be nonempty and consist only of A, C, G and T".to_string());
+        return res;
+    }
+    let mut seq = DnaString::from_dna_string(&bases);
+    if req.rc {
+        seq = seq.rc();
+    }
+    res.len = seq.len();
+    let ann = annotate_seq(&seq, refdata, true, false, true);
+    for a in ann.iter() {
+        let t = a.ref_id as usize;
+        let region = region_type(refdata, t);
+        let gene = match region {
+            "V" => Some(&mut res.v_gene),
+            "D" => Some(&mut res.d_gene),
+            "J" => Some(&mut res.j_gene),
+            "C" => Some(&mut res.c_gene),
+            _ => None,
+        };
+        if let Some(gene) = gene {
+            if gene.is_none() {
+                *gene = Some(refdata.name[t].clone());
+            }
+        }
+        res.alignments.push(SeqAlignment {
+            ref_id: refdata.id[t],
+            ref_name: refdata.name[t].clone(),
+            region: region.to_string(),
+            tig_start: a.tig_start as usize,
+            ref_start: a.ref_start as usize,
+            match_len: a.match_len as usize,
+            mismatches: a.mismatches as usize,
+        });
+    }
+    let cdr3 = get_cdr3_using_ann(&seq, refdata, &ann);
+    if cdr3.len() == 1 {
+        let start = cdr3[0].start_position_on_contig;
+        let stop = start + 3 * cdr3[0].aa_seq.len();
+        let x = seq.to_ascii_vec();
+        res.cdr3_aa = Some(strme(&cdr3[0].aa_seq).to_string());
+        res.cdr3_nt = Some(strme(&x[start..stop]).to_string());
+        res.cdr3_start = Some(start);
+    }
+    res.productive = is_productive_contig(&seq, refdata, &ann).0;
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vdj_ann_ref::make_vdj_ref_data;
+
+    // An IGK contig from enclone_exec/testx/inputs/flaky, TAGACCACAATCGGTT-1_contig_1.
+
+    const CONTIG: &str = "GGAGGAACTGCTCAGTTAGGACCCAGAGGGAACCATGGAAGCCCCAGCTCAGCTTCTCTTCCTCCTGCTA\
+        CTCTGGCTCCCAGATACCACCGGAGAAATTGTGTTGACACAGTCTCCAGCCACCCTGTCTTTGTCTCCAGGGGAAAGAGCCACCCTC\
+        TCCTGCAGGGCCAGTCAGAGTGTTAGCAGCTACTTAGCCTGGTACCAACAGAAACCTGGCCAGGCTCCCAGGCTCCTCATCTATGAT\
+        GCATCCAACAGGGCCACTGGCATCCCAGCCAGGTTCAGTGGCAGTGGGTCTGGGACAGACTTCACTCTCACCATCAGCAGCCTAGAG\
+        CCTGAAGATTTTGCAGTTTATTACTGTCAGCAGCGTAGCAACTGGCCTCCCACTTTCGGCGGAGGGACCAAGGTGGAGATCAAACGA\
+        ACTGTGGCTGCACCATCTGTCTTCATCTTCCCGCCATCTGATGAGCAGTTGAAATCTGGAACTGCCTCTGTTGTGTGCCTGCTGAAT\
+        AACTTCTATCCCAGAGAGGCCAAAGTACAGTGGAAGGTGGATAACGC";
+
+    #[test]
+    fn test_annotate_request_round_trip() {
+        let mut refdata = RefData::new();
+        make_vdj_ref_data(
+            &mut refdata,
+            false,
+            "human",
+            false,
+            true,
+            true,
+            &human_supp_regions(),
+            &mouse_supp_regions(),
+        );
+
+        // Send the contig as a JSON request, and read back the JSON response.
+
+        let req = SeqRequest {
+            id: Some("contig_1".to_string()),
+            seq: CONTIG.to_string(),
+            rc: false,
+        };
+        let line = serde_json::to_string(&req).unwrap();
+        let reqs = parse_requests(&line).unwrap();
+        assert_eq!(reqs.len(), 1);
+        let out = serde_json::to_string(&annotate_request(&reqs[0], &refdata)).unwrap();
+        let res: SeqAnnotation = serde_json::from_str(&out).unwrap();
+        assert_eq!(res.id.as_deref(), Some("contig_1"));
+        assert_eq!(res.len, 552);
+        assert_eq!(res.error, None);
+        assert_eq!(res.v_gene.as_deref(), Some("IGKV3-11"));
+        assert_eq!(res.j_gene.as_deref(), Some("IGKJ4"));
+        assert_eq!(res.c_gene.as_deref(), Some("IGKC"));
+        assert_eq!(res.cdr3_aa.as_deref(), Some("CQQRSNWPPTF"));
+        assert_eq!(res.cdr3_start, Some(355));
+        assert_eq!(res.cdr3_nt.as_ref().map(|x| x.len()), Some(33));
+        assert!(res.productive);
+        assert!(!res.alignments.is_empty());
+
+        // The same contig, reverse complemented and sent as a bare sequence, gives the same
+        // CDR3 if rc is set.
+
+        let rc = strme(&DnaString::from_dna_string(CONTIG).rc().to_ascii_vec()).to_string();
+        let mut reqs = parse_requests(&rc).unwrap();
+        assert_eq!(reqs[0].id, None);
+        reqs[0].rc = true;
+        let res = annotate_request(&reqs[0], &refdata);
+        assert_eq!(res.cdr3_aa.as_deref(), Some("CQQRSNWPPTF"));
+
+        // Bad input is reported, not annotated.
+
+        assert!(parse_requests("{\"seq\": 1}").is_err());
+        let res = annotate_request(&parse_requests("ACGTN").unwrap()[0], &refdata);
+        assert!(res.error.is_some());
+    }
+
+    #[test]
+    fn test_parse_error_responses() {
+        // A malformed request still gets its id back, if the line is valid JSON.
+
+        for (line, ids) in [
+            ("{\"id\": \"a\", \"seq\": 1}", vec![Some("a")]),
+            (
+                "[{\"id\": \"a\", \"seq\": \"ACGT\"}, {\"id\": \"b\"}]",
+                vec![Some("a"), Some("b")],
+            ),
+            ("{\"id\": \"a\", \"seq\": ", vec![None]),
+        ] {
+            let e = parse_requests(line).unwrap_err();
+            let res = parse_error_responses(line, &e);
+            assert_eq!(res.iter().map(|r| r.id.as_deref()).collect::<Vec<_>>(), ids);
+            assert!(res.iter().all(|r| r.error.is_some()));
+        }
+    }
+}